@@ -0,0 +1,101 @@
+/**
+ * Derive macros for `trident`, re-exported by it with the `derive` feature.
+ */
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, DeriveInput, GenericParam, Path};
+
+/**
+ * Implement `trident::Erasable`, asserting at compile time that the type fits inline in a
+ * `Trident`.
+ *
+ * With `#[erasable(spill)]`, the type is instead allowed to be boxed, and isn't checked. With
+ * `#[erasable(crate = path)]`, `trident` is found at `path`, for example if it's been renamed.
+ */
+#[proc_macro_derive(Erasable, attributes(erasable))]
+pub fn derive_erasable(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    expand_erasable(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand_erasable(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let mut spill = false;
+    let mut krate: Path = syn::parse_quote!(::trident);
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("erasable")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("spill") {
+                spill = true;
+                Ok(())
+            } else if meta.path.is_ident("crate") {
+                krate = meta.value()?.parse()?;
+                Ok(())
+            } else {
+                Err(meta.error("expected `spill` or `crate`"))
+            }
+        })?;
+    }
+
+    // a type's size can't depend on its lifetimes, so check it with them all 'static
+    let mut static_args = Vec::new();
+    for param in &input.generics.params {
+        match param {
+            GenericParam::Lifetime(_) => static_args.push(quote!('static)),
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    param,
+                    "Erasable can only be derived for types without type or const parameters",
+                ))
+            }
+        }
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let ty = if static_args.is_empty() {
+        quote!(#name)
+    } else {
+        quote!(#name<#(#static_args),*>)
+    };
+
+    let check = if spill {
+        quote!()
+    } else {
+        let size_msg = format!(
+            "`{}` is larger than three words, so would be boxed. Add #[erasable(spill)] to allow this",
+            name
+        );
+        let align_msg = format!(
+            "`{}` is more aligned than a word, so would be boxed. Add #[erasable(spill)] to allow this",
+            name
+        );
+        let size_of = format_ident!("SizeOf{}", name);
+
+        quote! {
+            const _: () = assert!(
+                ::core::mem::size_of::<#ty>() <= ::core::mem::size_of::<[usize; 3]>(),
+                #size_msg
+            );
+            const _: () = assert!(
+                ::core::mem::align_of::<#ty>() <= ::core::mem::align_of::<usize>(),
+                #align_msg
+            );
+            // reports the actual size, as a type mismatch, should the type not fit
+            const _: () = {
+                struct #size_of<const BYTES: usize>;
+                const SIZE: usize = ::core::mem::size_of::<#ty>();
+                const LIMIT: usize = ::core::mem::size_of::<[usize; 3]>();
+                let _: #size_of<LIMIT> = #size_of::<{ if SIZE > LIMIT { SIZE } else { LIMIT } }>;
+            };
+        }
+    };
+
+    Ok(quote! {
+        #check
+
+        impl #impl_generics #krate::Erasable for #name #ty_generics #where_clause {}
+    })
+}
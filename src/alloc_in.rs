@@ -0,0 +1,266 @@
+/**
+ * Custom allocator support: a `TridentIn<T, A>` spills into an `A` rather than the global heap.
+ */
+use std::{
+    alloc::{handle_alloc_error, Layout},
+    marker::PhantomData,
+    mem::{self, ManuallyDrop},
+    ops::{Deref, DerefMut},
+    ptr::{self, NonNull},
+};
+
+use allocator_api2::alloc::Allocator;
+
+use crate::limits::{self, SIZE_LIMIT};
+use crate::{AllocError, Erased, Trident};
+
+/**
+ * A struct that stores a `T`, either inline or, if `T` is larger than 3 words, allocated in `A`.
+ *
+ * The allocator handle is stored in the words left over after the heap pointer, so must be at
+ * most 2 words, e.g. `&MyArena` or a zero-sized allocator. When `T` is inline, no allocation is
+ * needed and the handle is dropped on construction.
+ */
+pub struct TridentIn<T, A: Allocator> {
+    erased: Erased,
+    _phantom: PhantomData<(T, A)>,
+}
+
+impl<T> Trident<T> {
+    /**
+     * Create a `TridentIn<T, A>`, allocating in `alloc` if `T` is too large to be stored inline.
+     */
+    pub fn new_in<A: Allocator>(t: T, alloc: A) -> TridentIn<T, A> {
+        TridentIn::new_in(t, alloc)
+    }
+}
+
+impl<T, A: Allocator> TridentIn<T, A> {
+    const ALLOCATOR_FITS: () = assert!(
+        mem::size_of::<A>() <= SIZE_LIMIT - mem::size_of::<usize>()
+            && mem::align_of::<A>() <= mem::align_of::<usize>(),
+        "allocator handle is too large to store in a TridentIn"
+    );
+
+    fn should_inline() -> bool {
+        limits::should_inline::<T>()
+    }
+
+    /**
+     * Create a `TridentIn<T, A>`, allocating in `alloc` if `T` is too large to be stored inline.
+     */
+    pub fn new_in(t: T, alloc: A) -> Self {
+        match Self::try_new_in(t, alloc) {
+            Ok(ret) => ret,
+            Err(AllocError) => handle_alloc_error(Layout::new::<T>()),
+        }
+    }
+
+    /**
+     * Create a `TridentIn<T, A>`, returning an error rather than aborting if allocation fails.
+     *
+     * Inline `T`s never fail. On failure, `t` is dropped.
+     */
+    pub fn try_new_in(t: T, alloc: A) -> Result<Self, AllocError> {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::ALLOCATOR_FITS;
+
+        if Self::should_inline() {
+            return Ok(Self {
+                erased: Erased::new(t),
+                _phantom: PhantomData,
+            });
+        }
+
+        let ptr = alloc
+            .allocate(Layout::new::<T>())
+            .map_err(|_| AllocError)?
+            .cast::<T>()
+            .as_ptr();
+
+        unsafe {
+            ptr::write(ptr, t);
+        }
+
+        // SAFETY: a boxed payload, followed by our allocator
+        let mut erased = unsafe { Erased::from_spilled(ptr) };
+        unsafe {
+            ptr::write(erased.words_mut()[1..].as_mut_ptr() as *mut A, alloc);
+        }
+
+        Ok(Self {
+            erased,
+            _phantom: PhantomData,
+        })
+    }
+
+    /**
+     * Get the allocator, if `T` is stored in it.
+     */
+    pub fn allocator(&self) -> Option<&A> {
+        if Self::should_inline() {
+            None
+        } else {
+            // SAFETY: spilled, so we were constructed with an allocator after the pointer
+            Some(unsafe { &*(self.erased.words()[1..].as_ptr() as *const A) })
+        }
+    }
+
+    /**
+     * Get a pointer to the contained `T`.
+     */
+    pub fn as_ptr(&self) -> *const T {
+        // SAFETY: we were created with the same T we request
+        unsafe { self.erased.as_ptr() }
+    }
+
+    /**
+     * Get a mutable pointer to the contained `T`.
+     */
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        // SAFETY: we were created with the same T we request
+        unsafe { self.erased.as_mut_ptr() }
+    }
+
+    /**
+     * Convert to the contained `T`, freeing any allocation.
+     */
+    pub fn into_inner(self) -> T {
+        let mut this = ManuallyDrop::new(self);
+
+        // SAFETY: `this` is never dropped, so we take ownership of T and the allocator
+        unsafe {
+            let t = ptr::read(this.as_mut_ptr());
+            this.deallocate();
+            t
+        }
+    }
+
+    /**
+     * Free the allocation (if any) and drop the allocator, leaving T untouched.
+     *
+     * Unsafe because it must only be called once, with T already moved out or dropped.
+     */
+    unsafe fn deallocate(&mut self) {
+        if Self::should_inline() {
+            return;
+        }
+
+        let alloc = ptr::read(self.erased.words()[1..].as_ptr() as *const A);
+        let ptr = NonNull::new_unchecked(self.as_mut_ptr() as *mut u8);
+
+        alloc.deallocate(ptr, Layout::new::<T>());
+    }
+}
+
+// SAFETY: we own a T and an A, so are as thread-safe as they are
+unsafe impl<T: Send, A: Allocator + Send> Send for TridentIn<T, A> {}
+unsafe impl<T: Sync, A: Allocator + Sync> Sync for TridentIn<T, A> {}
+
+impl<T, A: Allocator> Drop for TridentIn<T, A> {
+    fn drop(&mut self) {
+        unsafe {
+            ptr::drop_in_place(self.as_mut_ptr());
+            self.deallocate();
+        }
+    }
+}
+
+impl<T, A: Allocator> AsRef<T> for TridentIn<T, A> {
+    fn as_ref(&self) -> &T {
+        unsafe { &*self.as_ptr() }
+    }
+}
+
+impl<T, A: Allocator> AsMut<T> for TridentIn<T, A> {
+    fn as_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.as_mut_ptr() }
+    }
+}
+
+impl<T, A: Allocator> Deref for TridentIn<T, A> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.as_ref()
+    }
+}
+
+impl<T, A: Allocator> DerefMut for TridentIn<T, A> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.as_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{alloc::Layout, cell::Cell, mem, ptr::NonNull};
+
+    use allocator_api2::alloc::{AllocError, Allocator, Global};
+
+    use super::TridentIn;
+    use crate::Trident;
+
+    #[derive(Default)]
+    struct Counting {
+        live: Cell<isize>,
+    }
+
+    unsafe impl Allocator for Counting {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+            self.live.set(self.live.get() + 1);
+            Global.allocate(layout)
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            self.live.set(self.live.get() - 1);
+            Global.deallocate(ptr, layout)
+        }
+    }
+
+    #[test]
+    fn stays_three_words() {
+        assert_eq!(
+            mem::size_of::<TridentIn<[u8; 100], &Counting>>(),
+            mem::size_of::<Trident<()>>()
+        );
+    }
+
+    #[test]
+    fn spills_into_allocator() {
+        let counting = Counting::default();
+
+        let mut t = Trident::new_in([1u64; 8], &counting);
+        assert_eq!(counting.live.get(), 1);
+        assert!(t.allocator().is_some());
+
+        t[7] = 2;
+        assert_eq!(t[..], [1, 1, 1, 1, 1, 1, 1, 2]);
+
+        drop(t);
+        assert_eq!(counting.live.get(), 0);
+    }
+
+    #[test]
+    fn keeps_small_values_inline() {
+        let counting = Counting::default();
+
+        let t = TridentIn::new_in(5u32, &counting);
+        assert_eq!(counting.live.get(), 0);
+        assert!(t.allocator().is_none());
+        assert_eq!(*t, 5);
+    }
+
+    #[test]
+    fn into_inner_frees() {
+        let counting = Counting::default();
+
+        let t = TridentIn::new_in((0..16).collect::<Vec<i32>>(), &counting);
+        let large = TridentIn::new_in([String::from("x"), String::new()], &counting);
+        assert_eq!(counting.live.get(), 1);
+
+        assert_eq!(t.into_inner().len(), 16);
+        assert_eq!(large.into_inner()[0], "x");
+        assert_eq!(counting.live.get(), 0);
+    }
+}
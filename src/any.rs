@@ -0,0 +1,241 @@
+use std::{
+    any::{Any, TypeId},
+    fmt,
+    marker::PhantomData,
+    mem::ManuallyDrop,
+};
+
+use crate::payload::{self, Payload};
+use crate::Trident;
+
+/**
+ * A type-erased `T: Any`, which can be safely downcast back to `T`, as `Box<dyn Any>`.
+ *
+ * `T`s which fit in two words are stored inline, as in `SmallFn`, and larger ones are boxed.
+ * Alongside the payload is a pointer to the `T`'s type ID and drop glue, so an `AnyTrident` is
+ * three words.
+ *
+ * As the `T` may be neither `Send` nor `Sync`, an `AnyTrident` is neither. `AnyMessage` holds a
+ * `T: Send`, and can be sent between threads:
+ *
+ * ```compile_fail
+ * let any = trident::AnyTrident::new(std::rc::Rc::new(5u32));
+ * std::thread::spawn(move || drop(any));
+ * ```
+ */
+pub struct AnyTrident {
+    payload: Payload,
+    vtable: &'static AnyVTable,
+    // the payload may be neither Send nor Sync, see AnyMessage
+    _not_send_sync: PhantomData<*mut ()>,
+}
+
+struct AnyVTable {
+    type_id: fn() -> TypeId,
+    drop: unsafe fn(*mut Payload),
+    inline: bool,
+}
+
+struct VTableFor<T>(PhantomData<T>);
+
+impl<T: Any> VTableFor<T> {
+    const VTABLE: AnyVTable = AnyVTable {
+        type_id: TypeId::of::<T>,
+        drop: Payload::drop_in_place::<T>,
+        inline: payload::inlines::<T>(),
+    };
+}
+
+impl AnyTrident {
+    /**
+     * Create an `AnyTrident` from a `T`.
+     */
+    pub fn new<T: Any>(t: T) -> Self {
+        Self {
+            payload: Payload::new(t),
+            vtable: &VTableFor::<T>::VTABLE,
+            _not_send_sync: PhantomData,
+        }
+    }
+
+    /**
+     * Create an `AnyTrident` from a `Trident<T>`, reusing its allocation if the `T` is boxed in
+     * both.
+     */
+    pub fn from_trident<T: Any>(t: Trident<T>) -> Self {
+        Self {
+            payload: Payload::from_trident(t),
+            vtable: &VTableFor::<T>::VTABLE,
+            _not_send_sync: PhantomData,
+        }
+    }
+
+    /**
+     * Whether the contained value is stored inline.
+     */
+    pub fn is_inline(&self) -> bool {
+        self.vtable.inline
+    }
+
+    /**
+     * Whether the contained value is a `T`.
+     */
+    pub fn is<T: Any>(&self) -> bool {
+        (self.vtable.type_id)() == TypeId::of::<T>()
+    }
+
+    /**
+     * Get a reference to the contained value, if it's a `T`.
+     */
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        if self.is::<T>() {
+            // SAFETY: we hold a T, borrowed as we are
+            Some(unsafe {
+                &*Payload::resolve::<T>(&self.payload as *const Payload as *mut Payload)
+            })
+        } else {
+            None
+        }
+    }
+
+    /**
+     * Get a mutable reference to the contained value, if it's a `T`.
+     */
+    pub fn downcast_mut<T: Any>(&mut self) -> Option<&mut T> {
+        if self.is::<T>() {
+            // SAFETY: we hold a T, borrowed as we are
+            Some(unsafe { &mut *Payload::resolve::<T>(&mut self.payload) })
+        } else {
+            None
+        }
+    }
+
+    /**
+     * Convert to a `Trident<T>`, if the contained value is a `T`, otherwise return `self`.
+     *
+     * A boxed `T` keeps its allocation.
+     */
+    pub fn downcast<T: Any>(self) -> Result<Trident<T>, Self> {
+        if !self.is::<T>() {
+            return Err(self);
+        }
+
+        let mut this = ManuallyDrop::new(self);
+
+        // SAFETY: we hold a T, which is moved out and not dropped
+        Ok(unsafe {
+            if payload::inlines::<T>() {
+                Trident::new(Payload::take::<T>(&mut this.payload))
+            } else {
+                Trident::from(Payload::take_box::<T>(&mut this.payload))
+            }
+        })
+    }
+}
+
+impl Drop for AnyTrident {
+    fn drop(&mut self) {
+        // SAFETY: the vtable was created for the T we hold
+        unsafe { (self.vtable.drop)(&mut self.payload) }
+    }
+}
+
+impl<T: Any> From<Trident<T>> for AnyTrident {
+    fn from(t: Trident<T>) -> Self {
+        Self::from_trident(t)
+    }
+}
+
+impl fmt::Debug for AnyTrident {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AnyTrident").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use super::*;
+
+    #[test]
+    fn downcasts() {
+        let mut small = AnyTrident::new(3u16);
+        let large = AnyTrident::new([1u64; 8]);
+
+        assert!(small.is::<u16>());
+        assert!(!small.is::<u32>());
+        assert_eq!(small.downcast_ref::<u32>(), None);
+
+        *small.downcast_mut::<u16>().unwrap() += 1;
+        assert_eq!(small.downcast_ref::<u16>(), Some(&4));
+
+        let large = large.downcast::<[u32; 16]>().unwrap_err();
+        assert_eq!(large.downcast::<[u64; 8]>().unwrap().into_inner(), [1; 8]);
+    }
+
+    #[test]
+    fn stores_two_words_inline() {
+        let small = AnyTrident::new([1usize; 2]);
+        let large = AnyTrident::new([2usize; 3]);
+
+        assert!(small.is_inline());
+        assert!(!large.is_inline());
+        assert_eq!(
+            std::mem::size_of::<AnyTrident>(),
+            3 * std::mem::size_of::<usize>()
+        );
+    }
+
+    #[test]
+    fn reuses_allocations() {
+        let t = Trident::new([3u64; 8]);
+        let addr = t.as_ptr();
+
+        let any = AnyTrident::from_trident(t);
+        let t = any.downcast::<[u64; 8]>().unwrap();
+        assert_eq!(t.as_ptr(), addr);
+
+        let full = AnyTrident::from(Trident::new([4usize; 3]));
+        assert_eq!(full.downcast::<[usize; 3]>().unwrap().into_inner(), [4; 3]);
+    }
+
+    #[test]
+    fn mutates_inline_cells() {
+        let any = AnyTrident::new(Cell::new(1u8));
+        let (a, b) = (&any, &any);
+
+        let cell = a.downcast_ref::<Cell<u8>>().unwrap();
+        cell.set(b.downcast_ref::<Cell<u8>>().unwrap().get() + 1);
+        assert_eq!(any.downcast::<Cell<u8>>().unwrap().into_inner().get(), 2);
+    }
+
+    #[test]
+    fn drops() {
+        struct Dtor(Rc<Cell<u32>>, #[allow(dead_code)] [usize; 4]);
+
+        impl Drop for Dtor {
+            fn drop(&mut self) {
+                self.0.set(self.0.get() + 1);
+            }
+        }
+
+        let drops = Rc::new(Cell::new(0));
+        drop(AnyTrident::new(Dtor(drops.clone(), [0; 4])));
+        drop(AnyTrident::new(drops.clone()));
+        assert_eq!(drops.get(), 1);
+
+        let t = AnyTrident::new(Dtor(drops.clone(), [0; 4]))
+            .downcast::<Dtor>()
+            .unwrap();
+        assert_eq!(drops.get(), 1);
+        drop(t);
+        assert_eq!(drops.get(), 2);
+        assert_eq!(Rc::strong_count(&drops), 1);
+    }
+
+    #[test]
+    fn debugs() {
+        assert_eq!(format!("{:?}", AnyTrident::new(1)), "AnyTrident { .. }");
+    }
+}
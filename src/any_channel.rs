@@ -0,0 +1,308 @@
+use std::{
+    any::Any,
+    error::Error,
+    fmt,
+    sync::mpsc::{self, RecvError, SendError, TryRecvError},
+};
+
+use crate::{AnyTrident, Trident};
+
+/**
+ * Create a channel carrying `AnyMessage`s, so values of many types, returning its sending and
+ * receiving halves.
+ *
+ * As for `std::sync::mpsc::channel`, the channel is unbounded and there may be many senders but
+ * only one receiver. Small messages are stored inline, without an allocation each.
+ */
+pub fn any_channel() -> (AnySender, AnyReceiver) {
+    let (tx, rx) = mpsc::channel();
+    (AnySender(tx), AnyReceiver(rx))
+}
+
+/**
+ * A type-erased `T: Any + Send`, as `AnyTrident`, which can be sent between threads.
+ */
+pub struct AnyMessage(AnyTrident);
+
+// SAFETY: an AnyMessage is only created from a T: Send
+unsafe impl Send for AnyMessage {}
+
+/**
+ * The sending half of an `any_channel`, which can be cloned for more senders.
+ */
+#[derive(Clone)]
+pub struct AnySender(mpsc::Sender<AnyMessage>);
+
+/**
+ * The receiving half of an `any_channel`.
+ */
+pub struct AnyReceiver(mpsc::Receiver<AnyMessage>);
+
+/**
+ * The error returned from `AnyReceiver::recv_as`.
+ */
+#[derive(Debug)]
+pub enum RecvAsError {
+    /**
+     * All senders have been dropped, and there are no more messages.
+     */
+    Disconnected,
+    /**
+     * The next message was of a different type, so is returned as-is.
+     */
+    Unexpected(AnyMessage),
+}
+
+/**
+ * Dispatch of an `AnyMessage` to the first handler for its type, from `AnyMessage::dispatch`.
+ *
+ * Handlers are given with `on`, and the result of the matching one is retrieved with `finish`,
+ * or `otherwise`, which handles messages of any other type.
+ */
+#[must_use = "the message is only dispatched when the result is retrieved"]
+pub struct AnyDispatch<R> {
+    state: Result<R, AnyMessage>,
+}
+
+impl AnyMessage {
+    /**
+     * Create an `AnyMessage` from a `T`.
+     */
+    pub fn new<T: Any + Send>(t: T) -> Self {
+        Self(AnyTrident::new(t))
+    }
+
+    /**
+     * Whether the message is a `T`.
+     */
+    pub fn is<T: Any>(&self) -> bool {
+        self.0.is::<T>()
+    }
+
+    /**
+     * Get a reference to the message, if it's a `T`.
+     */
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        self.0.downcast_ref()
+    }
+
+    /**
+     * Get a mutable reference to the message, if it's a `T`.
+     */
+    pub fn downcast_mut<T: Any>(&mut self) -> Option<&mut T> {
+        self.0.downcast_mut()
+    }
+
+    /**
+     * Get the message, if it's a `T`, otherwise return `self`.
+     */
+    pub fn downcast<T: Any>(self) -> Result<T, Self> {
+        self.0
+            .downcast::<T>()
+            .map(Trident::into_inner)
+            .map_err(Self)
+    }
+
+    /**
+     * Begin dispatching the message to handlers by its type.
+     */
+    pub fn dispatch<R>(self) -> AnyDispatch<R> {
+        AnyDispatch { state: Err(self) }
+    }
+}
+
+impl<R> AnyDispatch<R> {
+    /**
+     * Handle the message with `f`, if it's a `T` and hasn't already been handled.
+     */
+    pub fn on<T: Any, F: FnOnce(T) -> R>(self, f: F) -> Self {
+        let state = match self.state {
+            Err(message) => message.downcast().map(f),
+            handled => handled,
+        };
+        Self { state }
+    }
+
+    /**
+     * Get the result of the handler, or the message if none handled it.
+     */
+    pub fn finish(self) -> Result<R, AnyMessage> {
+        self.state
+    }
+
+    /**
+     * Get the result of the handler, or handle the message with `f` if none did.
+     */
+    pub fn otherwise<F: FnOnce(AnyMessage) -> R>(self, f: F) -> R {
+        self.state.unwrap_or_else(f)
+    }
+}
+
+impl AnySender {
+    /**
+     * Send `t`, or return it if the receiver has been dropped.
+     */
+    pub fn send<T: Any + Send>(&self, t: T) -> Result<(), SendError<T>> {
+        self.send_message(AnyMessage::new(t))
+            .map_err(|SendError(message)| {
+                SendError(message.downcast().expect("message was created from a T"))
+            })
+    }
+
+    /**
+     * Send a message, or return it if the receiver has been dropped.
+     */
+    pub fn send_message(&self, message: AnyMessage) -> Result<(), SendError<AnyMessage>> {
+        self.0.send(message)
+    }
+}
+
+impl AnyReceiver {
+    /**
+     * Wait for the next message, failing once all senders have been dropped.
+     */
+    pub fn recv(&self) -> Result<AnyMessage, RecvError> {
+        self.0.recv()
+    }
+
+    /**
+     * Get the next message, if one has already been sent.
+     */
+    pub fn try_recv(&self) -> Result<AnyMessage, TryRecvError> {
+        self.0.try_recv()
+    }
+
+    /**
+     * Wait for the next message, which is expected to be a `T`.
+     *
+     * If it's of a different type, it's returned in `RecvAsError::Unexpected`.
+     */
+    pub fn recv_as<T: Any>(&self) -> Result<T, RecvAsError> {
+        self.recv()
+            .map_err(|RecvError| RecvAsError::Disconnected)?
+            .downcast()
+            .map_err(RecvAsError::Unexpected)
+    }
+}
+
+impl fmt::Debug for AnyMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AnyMessage").finish_non_exhaustive()
+    }
+}
+
+impl fmt::Debug for AnySender {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AnySender").finish_non_exhaustive()
+    }
+}
+
+impl fmt::Debug for AnyReceiver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AnyReceiver").finish_non_exhaustive()
+    }
+}
+
+impl<R> fmt::Debug for AnyDispatch<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AnyDispatch")
+            .field("handled", &self.state.is_ok())
+            .finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for RecvAsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Disconnected => f.write_str("receiving on a closed channel"),
+            Self::Unexpected(_) => f.write_str("received a message of an unexpected type"),
+        }
+    }
+}
+
+impl Error for RecvAsError {}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Ping(u32);
+
+    #[derive(Debug, PartialEq)]
+    struct Resize {
+        width: u32,
+        height: u32,
+    }
+
+    #[test]
+    fn multiplexes_types() {
+        let (tx, rx) = any_channel();
+        tx.send([0u64; 5]).unwrap();
+
+        let sender = thread::spawn({
+            let tx = tx.clone();
+            move || {
+                tx.send(Ping(1)).unwrap();
+                tx.send(Resize {
+                    width: 640,
+                    height: 480,
+                })
+                .unwrap();
+                tx.send(String::from("quit")).unwrap();
+            }
+        });
+        drop(tx);
+        sender.join().unwrap();
+
+        assert_eq!(rx.recv().unwrap().downcast::<[u64; 5]>().unwrap(), [0; 5]);
+        assert_eq!(rx.recv_as::<Ping>().unwrap(), Ping(1));
+
+        let message = match rx.recv_as::<Ping>() {
+            Err(RecvAsError::Unexpected(message)) => message,
+            other => panic!("expected a Resize, got {other:?}"),
+        };
+        assert_eq!(message.downcast_ref::<Resize>().map(|r| r.width), Some(640));
+
+        assert_eq!(rx.recv_as::<String>().unwrap(), "quit");
+        assert!(matches!(
+            rx.recv_as::<Ping>(),
+            Err(RecvAsError::Disconnected)
+        ));
+        assert!(matches!(rx.try_recv(), Err(TryRecvError::Disconnected)));
+    }
+
+    #[test]
+    fn dispatches_by_type() {
+        let describe = |message: AnyMessage| {
+            message
+                .dispatch()
+                .on(|Ping(n)| format!("ping {n}"))
+                .on(|r: Resize| format!("resize {}x{}", r.width, r.height))
+                .otherwise(|_| String::from("unknown"))
+        };
+
+        assert_eq!(describe(AnyMessage::new(Ping(2))), "ping 2");
+        assert_eq!(
+            describe(AnyMessage::new(Resize {
+                width: 3,
+                height: 4
+            })),
+            "resize 3x4"
+        );
+        assert_eq!(describe(AnyMessage::new(5u8)), "unknown");
+
+        let unhandled = AnyMessage::new(6u8).dispatch().on(|Ping(n)| n).finish();
+        assert_eq!(unhandled.unwrap_err().downcast::<u8>().unwrap(), 6);
+    }
+
+    #[test]
+    fn returns_unsent_values() {
+        let (tx, rx) = any_channel();
+        drop(rx);
+
+        assert_eq!(tx.send(Ping(7)).unwrap_err().0, Ping(7));
+    }
+}
@@ -0,0 +1,224 @@
+use std::{
+    any::{Any, TypeId},
+    collections::hash_map::{self, HashMap},
+    fmt,
+    marker::PhantomData,
+};
+
+use crate::AnyTrident;
+
+/**
+ * A map holding at most one value of each type, keyed by the type, as for "extensions" attached
+ * to a request or entity.
+ *
+ * Values are stored as `AnyTrident`s, so small values are inline in the table, without an
+ * allocation each.
+ */
+#[derive(Default)]
+pub struct AnyMap {
+    values: HashMap<TypeId, AnyTrident>,
+}
+
+/**
+ * A view into the `T` slot of an `AnyMap`, which may be vacant, from `AnyMap::entry`.
+ */
+pub struct AnyMapEntry<'a, T> {
+    inner: hash_map::Entry<'a, TypeId, AnyTrident>,
+    _phantom: PhantomData<T>,
+}
+
+/**
+ * Downcast a value from the `T` slot.
+ */
+fn downcast_mut<T: Any>(value: &mut AnyTrident) -> &mut T {
+    value.downcast_mut().expect("keyed by type")
+}
+
+impl AnyMap {
+    /**
+     * Create an empty map.
+     */
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /**
+     * The number of values.
+     */
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /**
+     * Whether there are no values.
+     */
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /**
+     * Whether there's a `T`.
+     */
+    pub fn contains<T: Any>(&self) -> bool {
+        self.values.contains_key(&TypeId::of::<T>())
+    }
+
+    /**
+     * Insert `t`, returning the `T` it replaces, if any.
+     */
+    pub fn insert<T: Any>(&mut self, t: T) -> Option<T> {
+        self.values
+            .insert(TypeId::of::<T>(), AnyTrident::new(t))
+            .map(into_inner)
+    }
+
+    /**
+     * Get a reference to the `T`, if any.
+     */
+    pub fn get<T: Any>(&self) -> Option<&T> {
+        self.values
+            .get(&TypeId::of::<T>())
+            .map(|value| value.downcast_ref().expect("keyed by type"))
+    }
+
+    /**
+     * Get a mutable reference to the `T`, if any.
+     */
+    pub fn get_mut<T: Any>(&mut self) -> Option<&mut T> {
+        self.values.get_mut(&TypeId::of::<T>()).map(downcast_mut)
+    }
+
+    /**
+     * Remove the `T`, if any, and return it.
+     */
+    pub fn remove<T: Any>(&mut self) -> Option<T> {
+        self.values.remove(&TypeId::of::<T>()).map(into_inner)
+    }
+
+    /**
+     * Get the `T` slot, for in-place manipulation.
+     */
+    pub fn entry<T: Any>(&mut self) -> AnyMapEntry<'_, T> {
+        AnyMapEntry {
+            inner: self.values.entry(TypeId::of::<T>()),
+            _phantom: PhantomData,
+        }
+    }
+
+    /**
+     * Remove all values.
+     */
+    pub fn clear(&mut self) {
+        self.values.clear();
+    }
+}
+
+fn into_inner<T: Any>(value: AnyTrident) -> T {
+    value.downcast::<T>().expect("keyed by type").into_inner()
+}
+
+impl<'a, T: Any> AnyMapEntry<'a, T> {
+    /**
+     * Whether there's a `T`.
+     */
+    pub fn is_occupied(&self) -> bool {
+        matches!(self.inner, hash_map::Entry::Occupied(_))
+    }
+
+    /**
+     * Insert `t` if there's no `T`, and return a mutable reference to the `T`.
+     */
+    pub fn or_insert(self, t: T) -> &'a mut T {
+        self.or_insert_with(|| t)
+    }
+
+    /**
+     * Insert the result of `f` if there's no `T`, and return a mutable reference to the `T`.
+     */
+    pub fn or_insert_with<F: FnOnce() -> T>(self, f: F) -> &'a mut T {
+        downcast_mut(self.inner.or_insert_with(|| AnyTrident::new(f())))
+    }
+
+    /**
+     * Insert `T::default()` if there's no `T`, and return a mutable reference to the `T`.
+     */
+    pub fn or_default(self) -> &'a mut T
+    where
+        T: Default,
+    {
+        self.or_insert_with(T::default)
+    }
+
+    /**
+     * Modify the `T`, if there is one.
+     */
+    pub fn and_modify<F: FnOnce(&mut T)>(self, f: F) -> Self {
+        Self {
+            inner: self.inner.and_modify(|value| f(downcast_mut(value))),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl fmt::Debug for AnyMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AnyMap")
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> fmt::Debug for AnyMapEntry<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AnyMapEntry").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct RequestId(u64);
+
+    #[derive(Debug, PartialEq, Default)]
+    struct Timings([u64; 6]);
+
+    #[test]
+    fn holds_one_value_per_type() {
+        let mut map = AnyMap::new();
+
+        assert_eq!(map.insert(RequestId(1)), None);
+        assert_eq!(map.insert(String::from("user")), None);
+        assert_eq!(map.insert(RequestId(2)), Some(RequestId(1)));
+
+        assert_eq!(map.len(), 2);
+        assert!(map.contains::<String>());
+        assert!(!map.contains::<Timings>());
+        assert_eq!(map.get::<RequestId>(), Some(&RequestId(2)));
+
+        map.get_mut::<String>().unwrap().push_str("name");
+        assert_eq!(map.remove::<String>().as_deref(), Some("username"));
+        assert_eq!(map.remove::<String>(), None);
+        assert_eq!(map.len(), 1);
+
+        map.clear();
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn modifies_entries() {
+        let mut map = AnyMap::new();
+
+        assert!(!map.entry::<Timings>().is_occupied());
+        map.entry::<Timings>().or_default().0[0] = 5;
+        map.entry::<Timings>()
+            .and_modify(|t| t.0[1] = 7)
+            .or_insert(Timings([9; 6]));
+
+        assert_eq!(map.get::<Timings>(), Some(&Timings([5, 7, 0, 0, 0, 0])));
+
+        *map.entry().or_insert_with(|| 1u8) += 1;
+        assert_eq!(map.get::<u8>(), Some(&2));
+    }
+}
@@ -0,0 +1,42 @@
+/**
+ * `arbitrary` support, for fuzzing code which stores `Trident`s.
+ */
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+use crate::Trident;
+
+impl<'a, T> Arbitrary<'a> for Trident<T>
+where
+    T: Arbitrary<'a>,
+{
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        T::arbitrary(u).map(Trident::new)
+    }
+
+    fn arbitrary_take_rest(u: Unstructured<'a>) -> Result<Self> {
+        T::arbitrary_take_rest(u).map(Trident::new)
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        T::size_hint(depth)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arbitrary::{Arbitrary, Unstructured};
+
+    use crate::Trident;
+
+    #[test]
+    fn generates_from_bytes() {
+        let bytes = [7u8; 64];
+
+        let mut u = Unstructured::new(&bytes);
+        let small = Trident::<u32>::arbitrary(&mut u).unwrap();
+        let large = Trident::<[u8; 32]>::arbitrary(&mut u).unwrap();
+
+        assert_eq!(small, 0x0707_0707);
+        assert_eq!(large, [7; 32]);
+    }
+}
@@ -0,0 +1,225 @@
+/**
+ * A bump arena which `TridentIn`s can spill into, freeing all of their allocations at once.
+ */
+use std::{
+    alloc::{self, Layout},
+    cell::{Cell, RefCell},
+    fmt,
+    ptr::NonNull,
+};
+
+use allocator_api2::alloc::{AllocError, Allocator};
+
+use crate::{Trident, TridentIn};
+
+/**
+ * An arena for values spilled from `TridentIn`s.
+ *
+ * `&TridentArena` is an allocator, so `Trident::new_in(t, &arena)` creates a
+ * `TridentIn<T, &TridentArena>`, which borrows the arena. Large values are bump-allocated in the
+ * arena's chunks, and small ones are inline as usual. Dropping a `TridentIn` drops its value, but
+ * the memory is only freed, all at once, when the arena is reset or dropped, so many short-lived
+ * payloads don't fragment the global allocator.
+ */
+pub struct TridentArena {
+    chunks: RefCell<Vec<Chunk>>,
+    // offset of the free space in the last chunk
+    used: Cell<usize>,
+    chunk_size: usize,
+}
+
+struct Chunk {
+    ptr: NonNull<u8>,
+    layout: Layout,
+}
+
+const DEFAULT_CHUNK_SIZE: usize = 4096;
+const CHUNK_ALIGN: usize = 16;
+
+impl TridentArena {
+    /**
+     * Create an empty arena, which allocates nothing until a value is spilled into it.
+     */
+    pub const fn new() -> Self {
+        Self::with_chunk_size(DEFAULT_CHUNK_SIZE)
+    }
+
+    /**
+     * Create an empty arena, whose first chunk will be `chunk_size` bytes, or larger if needed
+     * for a value. Subsequent chunks double in size.
+     */
+    pub const fn with_chunk_size(chunk_size: usize) -> Self {
+        Self {
+            chunks: RefCell::new(Vec::new()),
+            used: Cell::new(0),
+            chunk_size,
+        }
+    }
+
+    /**
+     * Create a `TridentIn` allocating in the arena if `T` is too large to be stored inline.
+     */
+    pub fn alloc<T>(&self, t: T) -> TridentIn<T, &Self> {
+        Trident::new_in(t, self)
+    }
+
+    /**
+     * The total size of the arena's chunks, in bytes.
+     */
+    pub fn allocated_bytes(&self) -> usize {
+        self.chunks
+            .borrow()
+            .iter()
+            .map(|chunk| chunk.layout.size())
+            .sum()
+    }
+
+    /**
+     * Free all allocations, keeping the last chunk for reuse.
+     *
+     * No `TridentIn`s can still borrow the arena, as this takes `&mut self`.
+     */
+    pub fn reset(&mut self) {
+        let chunks = self.chunks.get_mut();
+        let last = chunks.pop();
+        for chunk in chunks.drain(..) {
+            chunk.free();
+        }
+        chunks.extend(last);
+        self.used.set(0);
+    }
+
+    fn allocate_layout(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        let mut chunks = self.chunks.borrow_mut();
+
+        if let Some(chunk) = chunks.last() {
+            if let Some(ptr) = chunk.bump(&self.used, layout) {
+                return Ok(ptr);
+            }
+        }
+
+        let size = chunks
+            .last()
+            .map_or(self.chunk_size, |chunk| chunk.layout.size() * 2)
+            .max(layout.size())
+            .max(CHUNK_ALIGN);
+        let chunk_layout = Layout::from_size_align(size, layout.align().max(CHUNK_ALIGN))
+            .map_err(|_| AllocError)?;
+
+        // SAFETY: the chunk layout is at least CHUNK_ALIGN in size
+        let ptr = NonNull::new(unsafe { alloc::alloc(chunk_layout) }).ok_or(AllocError)?;
+        chunks.push(Chunk {
+            ptr,
+            layout: chunk_layout,
+        });
+        self.used.set(0);
+
+        Ok(chunks
+            .last()
+            .unwrap()
+            .bump(&self.used, layout)
+            .expect("new chunk fits the layout"))
+    }
+}
+
+impl Chunk {
+    /**
+     * Allocate `layout` from the chunk's free space, starting at `used`.
+     */
+    fn bump(&self, used: &Cell<usize>, layout: Layout) -> Option<NonNull<u8>> {
+        let addr = self.ptr.as_ptr() as usize + used.get();
+        let start = used.get() + (addr.wrapping_neg() & (layout.align() - 1));
+        let end = start.checked_add(layout.size())?;
+
+        if end > self.layout.size() {
+            return None;
+        }
+        used.set(end);
+
+        // SAFETY: `start` is within the chunk
+        Some(unsafe { NonNull::new_unchecked(self.ptr.as_ptr().add(start)) })
+    }
+
+    fn free(self) {
+        // SAFETY: allocated with this layout in allocate_layout()
+        unsafe { alloc::dealloc(self.ptr.as_ptr(), self.layout) }
+    }
+}
+
+unsafe impl Allocator for &TridentArena {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let ptr = self.allocate_layout(layout)?;
+        Ok(NonNull::slice_from_raw_parts(ptr, layout.size()))
+    }
+
+    unsafe fn deallocate(&self, _ptr: NonNull<u8>, _layout: Layout) {
+        // freed when the arena is reset or dropped
+    }
+}
+
+impl Default for TridentArena {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for TridentArena {
+    fn drop(&mut self) {
+        for chunk in self.chunks.get_mut().drain(..) {
+            chunk.free();
+        }
+    }
+}
+
+impl fmt::Debug for TridentArena {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TridentArena")
+            .field("allocated_bytes", &self.allocated_bytes())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+
+    #[test]
+    fn spills_into_chunks() {
+        let rc = Rc::new(());
+        let arena = TridentArena::with_chunk_size(256);
+
+        let values = (0..100u64).map(|i| arena.alloc([i; 6])).collect::<Vec<_>>();
+        let large = Trident::new_in((rc.clone(), [0u8; 4000]), &arena);
+        let small = arena.alloc(1u8);
+
+        assert!(values.iter().enumerate().all(|(i, v)| v[5] == i as u64));
+        assert!(small.allocator().is_none());
+        assert!(large.allocator().is_some());
+        assert_eq!(Rc::strong_count(&rc), 2);
+
+        // 100 * 48 bytes, in chunks of 256, 512, ..., then one doubled for `large`
+        assert_eq!(arena.allocated_bytes(), (256 << 5) - 256 + (256 << 5));
+
+        drop(large);
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+
+    #[test]
+    fn resets() {
+        let mut arena = TridentArena::with_chunk_size(64);
+
+        for i in 0..10u64 {
+            drop(arena.alloc([i; 4]));
+        }
+        assert_eq!(arena.allocated_bytes(), 64 + 128 + 256);
+
+        arena.reset();
+        assert_eq!(arena.allocated_bytes(), 256);
+
+        let reused = arena.alloc([String::from("x"), String::from("y")]);
+        assert_eq!(reused[1], "y");
+        assert_eq!(arena.allocated_bytes(), 256);
+    }
+}
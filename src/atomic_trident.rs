@@ -0,0 +1,540 @@
+use std::{
+    cell::UnsafeCell,
+    fmt, hint, mem,
+    ops::Deref,
+    ptr,
+    sync::atomic::{AtomicPtr, AtomicUsize, Ordering},
+};
+
+use crate::limits;
+
+/**
+ * A value which can be republished atomically, while other threads read it without locking, for
+ * example a configuration.
+ *
+ * The value is boxed, so `store` and `swap` exchange a pointer. `read` returns a guard, and a
+ * replaced value is retired, then dropped once no guards remain, when the last guard is dropped or
+ * by the next `store`. A `Copy` value small enough to be inline can instead be an `AtomicCopy`,
+ * which needs no allocation.
+ */
+pub struct AtomicTrident<T> {
+    current: AtomicPtr<T>,
+    // a stack of replaced values
+    retired: AtomicPtr<Retired<T>>,
+    // the number of guards, which may be reading retired values
+    readers: AtomicUsize,
+}
+
+struct Retired<T> {
+    // a raw pointer rather than a box, which would assert uniqueness while readers may remain
+    value: *mut T,
+    next: *mut Retired<T>,
+}
+
+/**
+ * A reference to a value of an `AtomicTrident<T>`, from `AtomicTrident::read` or
+ * `AtomicTrident::swap`, which keeps the value alive even if it's replaced.
+ */
+pub struct AtomicTridentGuard<'a, T> {
+    atomic: &'a AtomicTrident<T>,
+    value: *const T,
+}
+
+impl<T> AtomicTrident<T> {
+    /**
+     * Create an `AtomicTrident<T>` containing `t`.
+     */
+    pub fn new(t: T) -> Self {
+        Self {
+            current: AtomicPtr::new(Box::into_raw(Box::new(t))),
+            retired: AtomicPtr::new(ptr::null_mut()),
+            readers: AtomicUsize::new(0),
+        }
+    }
+
+    /**
+     * Get a reference to the most recently stored value.
+     */
+    pub fn read(&self) -> AtomicTridentGuard<'_, T> {
+        // counted before loading, so a value we load isn't dropped until the guard is
+        self.readers.fetch_add(1, Ordering::SeqCst);
+
+        AtomicTridentGuard {
+            atomic: self,
+            value: self.current.load(Ordering::SeqCst),
+        }
+    }
+
+    /**
+     * Copy out the most recently stored value.
+     */
+    pub fn load(&self) -> T
+    where
+        T: Copy,
+    {
+        *self.read()
+    }
+
+    /**
+     * Publish `t`, retiring the previous value.
+     */
+    pub fn store(&self, t: T) {
+        drop(self.swap(t));
+    }
+
+    /**
+     * Publish `t`, returning a guard for the previous value, which is retired.
+     */
+    pub fn swap(&self, t: T) -> AtomicTridentGuard<'_, T> {
+        self.readers.fetch_add(1, Ordering::SeqCst);
+
+        let previous = self
+            .current
+            .swap(Box::into_raw(Box::new(t)), Ordering::SeqCst);
+
+        let node = Box::into_raw(Box::new(Retired {
+            value: previous,
+            next: ptr::null_mut(),
+        }));
+        // SAFETY: the node is ours
+        unsafe { self.retire(node, node) };
+
+        AtomicTridentGuard {
+            atomic: self,
+            value: previous,
+        }
+    }
+
+    /**
+     * Push the list of retired values from `head` to `tail`.
+     *
+     * Unsafe because the list must be ours, and `tail` must be its last node.
+     */
+    unsafe fn retire(&self, head: *mut Retired<T>, tail: *mut Retired<T>) {
+        let mut current = self.retired.load(Ordering::Relaxed);
+        loop {
+            (*tail).next = current;
+
+            match self.retired.compare_exchange_weak(
+                current,
+                head,
+                Ordering::SeqCst,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => break,
+                Err(actual) => current = actual,
+            }
+        }
+    }
+
+    /**
+     * Drop the retired values if no guards remain, or put them back for the last guard to drop.
+     */
+    fn collect(&self) {
+        // taken before checking for guards, so any guard reading one of them was counted before
+        // it was replaced, and so before the check
+        let head = self.retired.swap(ptr::null_mut(), Ordering::SeqCst);
+        if head.is_null() {
+            return;
+        }
+
+        if self.readers.load(Ordering::SeqCst) == 0 {
+            // SAFETY: the list is ours, and no guards can reach it
+            unsafe { free(head) };
+        } else {
+            let mut tail = head;
+            // SAFETY: the list is ours, and tail is its last node
+            unsafe {
+                while !(*tail).next.is_null() {
+                    tail = (*tail).next;
+                }
+                self.retire(head, tail);
+            }
+        }
+    }
+
+    /**
+     * Get a mutable reference to the value, which the `&mut self` guarantees is unique.
+     */
+    pub fn get_mut(&mut self) -> &mut T {
+        // SAFETY: the pointer is always to a live box, and the &mut self means no guards remain
+        unsafe { &mut **self.current.get_mut() }
+    }
+
+    /**
+     * Replace the value, returning the previous one, which the `&mut self` guarantees isn't being
+     * read.
+     */
+    pub fn replace(&mut self, t: T) -> T {
+        mem::replace(self.get_mut(), t)
+    }
+
+    /**
+     * Drop the retired values which are still waiting for guards, returning how many there were.
+     */
+    pub fn reclaim(&mut self) -> usize {
+        let head = mem::replace(self.retired.get_mut(), ptr::null_mut());
+
+        // SAFETY: the &mut self means no guards remain
+        unsafe { free(head) }
+    }
+
+    /**
+     * Convert to the current value.
+     */
+    pub fn into_inner(mut self) -> T {
+        self.reclaim();
+
+        let current = *self.current.get_mut();
+        mem::forget(self);
+
+        // SAFETY: we've forgotten self, so own the box
+        *unsafe { Box::from_raw(current) }
+    }
+}
+
+/**
+ * Drop a list of retired values, returning how many there were.
+ *
+ * Unsafe because the list must be ours, and no guards may be reading its values.
+ */
+unsafe fn free<T>(mut node: *mut Retired<T>) -> usize {
+    let mut count = 0;
+
+    while !node.is_null() {
+        // each node and the value it retired were boxed by swap()
+        let Retired { value, next } = *Box::from_raw(node);
+        drop(Box::from_raw(value));
+        node = next;
+        count += 1;
+    }
+    count
+}
+
+// SAFETY: we own Ts, which may be stored by one thread and dropped by another
+unsafe impl<T: Send> Send for AtomicTrident<T> {}
+// SAFETY: values are stored from and read by any thread
+unsafe impl<T: Send + Sync> Sync for AtomicTrident<T> {}
+
+impl<T> Drop for AtomicTrident<T> {
+    fn drop(&mut self) {
+        self.reclaim();
+
+        // SAFETY: the current value is always a live box
+        drop(unsafe { Box::from_raw(*self.current.get_mut()) });
+    }
+}
+
+impl<T: Default> Default for AtomicTrident<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T> From<T> for AtomicTrident<T> {
+    fn from(t: T) -> Self {
+        Self::new(t)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for AtomicTrident<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("AtomicTrident").field(&*self.read()).finish()
+    }
+}
+
+impl<T> Deref for AtomicTridentGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: the value isn't dropped while we're counted as a reader
+        unsafe { &*self.value }
+    }
+}
+
+impl<T> Drop for AtomicTridentGuard<'_, T> {
+    fn drop(&mut self) {
+        if self.atomic.readers.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.atomic.collect();
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for AtomicTridentGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/**
+ * A `Copy` value, stored inline, which can be republished atomically while other threads copy it
+ * out without locking, as `AtomicTrident<T>` without allocating.
+ *
+ * Two copies of the value are kept: `store` writes the inactive one, then makes it active, so a
+ * reader never sees a partly written value. Readers never wait, but may retry if a store
+ * completes as they start. A store waits for any readers still copying the inactive value, and for
+ * other stores.
+ *
+ * `T` must fit in a `Trident`'s 3 words, and fails to compile otherwise.
+ */
+pub struct AtomicCopy<T: Copy> {
+    values: UnsafeCell<[T; 2]>,
+    // the active index, and whether a store is in progress
+    state: AtomicUsize,
+    // the number of readers copying each value
+    readers: [AtomicUsize; 2],
+}
+
+const ACTIVE: usize = 1;
+const STORING: usize = 2;
+
+impl<T: Copy> AtomicCopy<T> {
+    const FITS: () = assert!(
+        limits::should_inline::<T>(),
+        "an AtomicCopy<T> can only hold a T which fits in 3 words"
+    );
+
+    /**
+     * Create an `AtomicCopy<T>` containing `t`.
+     */
+    pub const fn new(t: T) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::FITS;
+
+        Self {
+            values: UnsafeCell::new([t; 2]),
+            state: AtomicUsize::new(0),
+            readers: [AtomicUsize::new(0), AtomicUsize::new(0)],
+        }
+    }
+
+    /**
+     * Copy out the most recently stored value.
+     */
+    pub fn load(&self) -> T {
+        loop {
+            let active = self.state.load(Ordering::SeqCst) & ACTIVE;
+            self.readers[active].fetch_add(1, Ordering::SeqCst);
+
+            // if still active, a store can't begin writing it until we're done
+            if self.state.load(Ordering::SeqCst) & ACTIVE == active {
+                // SAFETY: as above, nothing is writing the value
+                let t = unsafe { (*self.values.get())[active] };
+                self.readers[active].fetch_sub(1, Ordering::SeqCst);
+                return t;
+            }
+
+            self.readers[active].fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+
+    /**
+     * Publish `t`.
+     */
+    pub fn store(&self, t: T) {
+        self.swap(t);
+    }
+
+    /**
+     * Publish `t`, returning the previous value.
+     */
+    pub fn swap(&self, t: T) -> T {
+        let mut state = self.state.load(Ordering::Relaxed);
+        loop {
+            if state & STORING != 0 {
+                hint::spin_loop();
+                state = self.state.load(Ordering::Relaxed);
+                continue;
+            }
+
+            match self.state.compare_exchange_weak(
+                state,
+                state | STORING,
+                Ordering::SeqCst,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => break,
+                Err(actual) => state = actual,
+            }
+        }
+
+        let active = state & ACTIVE;
+        let inactive = active ^ 1;
+        while self.readers[inactive].load(Ordering::SeqCst) != 0 {
+            hint::spin_loop();
+        }
+
+        let values = self.values.get();
+        // SAFETY: only we store, readers only read the active value, and the inactive one has no
+        // readers left, nor gains any until we make it active
+        let previous = unsafe {
+            (*values.cast::<T>().add(inactive)) = t;
+            *values.cast::<T>().add(active)
+        };
+
+        self.state.store(inactive, Ordering::SeqCst);
+        previous
+    }
+
+    /**
+     * Get a mutable reference to the value, which the `&mut self` guarantees is unique.
+     */
+    pub fn get_mut(&mut self) -> &mut T {
+        let active = *self.state.get_mut() & ACTIVE;
+        &mut self.values.get_mut()[active]
+    }
+
+    /**
+     * Convert to the current value.
+     */
+    pub fn into_inner(mut self) -> T {
+        *self.get_mut()
+    }
+}
+
+// SAFETY: values are copied in and out by any thread
+unsafe impl<T: Copy + Send> Sync for AtomicCopy<T> {}
+
+impl<T: Copy + Default> Default for AtomicCopy<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: Copy> From<T> for AtomicCopy<T> {
+    fn from(t: T) -> Self {
+        Self::new(t)
+    }
+}
+
+impl<T: Copy + fmt::Debug> fmt::Debug for AtomicCopy<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("AtomicCopy").field(&self.load()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{rc::Rc, sync::Arc, thread};
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct Config {
+        version: u32,
+        name: String,
+    }
+
+    #[test]
+    fn republishes_to_readers() {
+        let config = Arc::new(AtomicTrident::new(Config {
+            version: 0,
+            name: String::from("v0"),
+        }));
+
+        let readers = (0..2)
+            .map(|_| {
+                let config = Arc::clone(&config);
+                thread::spawn(move || {
+                    let mut seen = 0;
+                    while seen < 3 {
+                        let current = config.read();
+                        assert!(current.version >= seen);
+                        assert_eq!(current.name, format!("v{}", current.version));
+                        seen = current.version;
+                        drop(current);
+                        thread::yield_now();
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for version in 1..=3 {
+            config.store(Config {
+                version,
+                name: format!("v{version}"),
+            });
+        }
+        for reader in readers {
+            reader.join().unwrap();
+        }
+
+        config.store(Config {
+            version: 4,
+            name: String::from("v4"),
+        });
+        let mut config = Arc::into_inner(config).unwrap();
+        assert_eq!(config.reclaim(), 0);
+        assert_eq!(config.into_inner().name, "v4");
+    }
+
+    #[test]
+    fn reclaims_once_unread() {
+        let rc = Rc::new(());
+        let value = AtomicTrident::new(rc.clone());
+
+        let guard = value.read();
+        value.store(rc.clone());
+        value.store(rc.clone());
+        assert_eq!(Rc::strong_count(&rc), 4);
+
+        drop(guard);
+        assert_eq!(Rc::strong_count(&rc), 2);
+
+        let previous = value.swap(Rc::new(()));
+        assert!(Rc::ptr_eq(&previous, &rc));
+        assert_eq!(Rc::strong_count(&rc), 2);
+        drop(previous);
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+
+    #[test]
+    fn drops_retired_values() {
+        let rc = Rc::new(());
+        let mut value = AtomicTrident::new(rc.clone());
+
+        let guard = value.read();
+        value.store(rc.clone());
+        mem::forget(guard);
+        value.store(rc.clone());
+        assert_eq!(Rc::strong_count(&rc), 4);
+
+        assert_eq!(value.reclaim(), 2);
+        assert_eq!(Rc::strong_count(&rc), 2);
+
+        drop(value.replace(rc.clone()));
+        drop(value);
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+
+    #[test]
+    fn copies_out_inline_values() {
+        static POINT: AtomicCopy<(u64, u64)> = AtomicCopy::new((0, 0));
+
+        let readers = (0..2)
+            .map(|_| {
+                thread::spawn(|| {
+                    let mut seen = 0;
+                    while seen < 3 {
+                        let (x, y) = POINT.load();
+                        assert_eq!(x, y);
+                        assert!(x >= seen);
+                        seen = x;
+                        thread::yield_now();
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for n in 1..=3 {
+            POINT.store((n, n));
+        }
+        for reader in readers {
+            reader.join().unwrap();
+        }
+
+        assert_eq!(POINT.swap((4, 4)), (3, 3));
+        assert_eq!(format!("{POINT:?}"), "AtomicCopy((4, 4))");
+        assert_eq!(AtomicTrident::new(5u8).load(), 5);
+    }
+}
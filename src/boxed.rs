@@ -0,0 +1,176 @@
+/**
+ * A `Trident` variant which always stores its `T` on the heap, so the `T` never moves.
+ */
+use std::{
+    fmt,
+    ops::{Deref, DerefMut},
+    pin::Pin,
+};
+
+use crate::Trident;
+
+/**
+ * A struct that always stores a `T` on the heap, regardless of its size.
+ *
+ * Unlike a `Trident<T>`, moving a `BoxedTrident<T>` never moves the `T`, so it can be pinned
+ * unconditionally and (with the `stable_deref_trait` feature) implements `StableDeref`.
+ */
+pub struct BoxedTrident<T> {
+    boxed: Box<T>,
+}
+
+impl<T> BoxedTrident<T> {
+    /**
+     * Create a `BoxedTrident<T>` from a `T`.
+     */
+    pub fn new(t: T) -> Self {
+        Self { boxed: Box::new(t) }
+    }
+
+    /**
+     * Create a pinned `BoxedTrident<T>`.
+     */
+    pub fn pin(t: T) -> Pin<Self> {
+        Self::new(t).into_pin()
+    }
+
+    /**
+     * Convert to a pinned `BoxedTrident<T>`.
+     */
+    pub fn into_pin(self) -> Pin<Self> {
+        // SAFETY: T is boxed, so its address is stable for as long as we live
+        unsafe { Pin::new_unchecked(self) }
+    }
+
+    /**
+     * Get a pointer to the contained `T`.
+     */
+    pub fn as_ptr(&self) -> *const T {
+        &*self.boxed
+    }
+
+    /**
+     * Get a mutable pointer to the contained `T`.
+     */
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        &mut *self.boxed
+    }
+
+    /**
+     * Convert to the contained `T`.
+     */
+    pub fn into_inner(self) -> T {
+        *self.boxed
+    }
+
+    /**
+     * Convert to a `Trident<T>`, which will store the `T` inline if it's small enough.
+     */
+    pub fn into_trident(self) -> Trident<T> {
+        Trident::new(self.into_inner())
+    }
+}
+
+impl<T> From<T> for BoxedTrident<T> {
+    fn from(t: T) -> Self {
+        Self::new(t)
+    }
+}
+
+impl<T> From<Trident<T>> for BoxedTrident<T> {
+    fn from(t: Trident<T>) -> Self {
+        Self::new(t.into_inner())
+    }
+}
+
+impl<T> AsRef<T> for BoxedTrident<T> {
+    fn as_ref(&self) -> &T {
+        &self.boxed
+    }
+}
+
+impl<T> AsMut<T> for BoxedTrident<T> {
+    fn as_mut(&mut self) -> &mut T {
+        &mut self.boxed
+    }
+}
+
+impl<T> Deref for BoxedTrident<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.boxed
+    }
+}
+
+impl<T> DerefMut for BoxedTrident<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.boxed
+    }
+}
+
+impl<T> Clone for BoxedTrident<T>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        Self::new(self.as_ref().clone())
+    }
+}
+
+impl<T> fmt::Debug for BoxedTrident<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_ref(), f)
+    }
+}
+
+impl<T> fmt::Pointer for BoxedTrident<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Pointer::fmt(&self.as_ptr(), f)
+    }
+}
+
+#[cfg(feature = "stable_deref_trait")]
+unsafe impl<T> stable_deref_trait::StableDeref for BoxedTrident<T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::BoxedTrident;
+    use crate::Trident;
+
+    #[test]
+    fn small_values_dont_move() {
+        let t = BoxedTrident::new(3u8);
+        let addr = t.as_ptr();
+
+        let moved = t;
+        assert_eq!(moved.as_ptr(), addr);
+        assert_eq!(*moved, 3);
+    }
+
+    #[test]
+    fn pins_small_values() {
+        let mut pinned = BoxedTrident::pin(std::marker::PhantomPinned);
+        let addr = &*pinned as *const _;
+
+        let moved = &mut pinned;
+        assert_eq!(&**moved as *const _, addr);
+    }
+
+    #[test]
+    fn converts_to_and_from_trident() {
+        let boxed = BoxedTrident::<u32>::from(Trident::new(7));
+        assert_eq!(boxed.into_trident(), 7);
+    }
+
+    #[cfg(feature = "stable_deref_trait")]
+    #[test]
+    fn is_stable_deref() {
+        fn assert_stable<T: stable_deref_trait::StableDeref>(_: &T) {}
+
+        assert_stable(&BoxedTrident::new(1u8));
+    }
+}
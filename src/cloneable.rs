@@ -0,0 +1,187 @@
+use std::{marker::PhantomData, mem::ManuallyDrop, ptr};
+
+use crate::owned::drop_erased;
+use crate::{Erased, Trident};
+
+/**
+ * A type-erased `T: Clone`, as `OwnedErased`, which can also be cloned without knowing `T`.
+ *
+ * For example, a heterogeneous collection of `CloneableErased` can be deep-cloned. This costs a
+ * word for a pointer to `T`'s clone and drop functions.
+ *
+ * `T` must be `'static`, as a `CloneableErased` has no lifetime to stop it outliving anything the
+ * `T` borrows.
+ */
+pub struct CloneableErased {
+    erased: Erased,
+    vtable: &'static CloneVTable,
+}
+
+struct CloneVTable {
+    clone: unsafe fn(&Erased) -> Erased,
+    drop: unsafe fn(&mut Erased),
+}
+
+struct VTableFor<T>(PhantomData<T>);
+
+impl<T: Clone> VTableFor<T> {
+    const VTABLE: CloneVTable = CloneVTable {
+        clone: clone_erased::<T>,
+        drop: drop_erased::<T>,
+    };
+}
+
+impl CloneableErased {
+    /**
+     * Create a `CloneableErased` from a `T`.
+     */
+    pub fn new<T: Clone + 'static>(t: T) -> Self {
+        Self::from_trident(Trident::new(t))
+    }
+
+    /**
+     * Create a `CloneableErased` from a `Trident<T>`, keeping its storage.
+     */
+    pub fn from_trident<T: Clone + 'static>(t: Trident<T>) -> Self {
+        Self {
+            erased: t.into_erased(),
+            vtable: &VTableFor::<T>::VTABLE,
+        }
+    }
+
+    /**
+     * Get a reference to the contained `T`.
+     *
+     * # Safety
+     *
+     * Unsafe because we don't know that this is the same `T` that this `CloneableErased` was
+     * created with.
+     */
+    pub unsafe fn as_ref<T>(&self) -> &T {
+        self.erased.as_ref()
+    }
+
+    /**
+     * Get a mutable reference to the contained `T`.
+     *
+     * # Safety
+     *
+     * Unsafe because we don't know that this is the same `T` that this `CloneableErased` was
+     * created with.
+     */
+    pub unsafe fn as_mut_ref<T>(&mut self) -> &mut T {
+        self.erased.as_mut_ref()
+    }
+
+    /**
+     * Get the contained `T`.
+     *
+     * # Safety
+     *
+     * Unsafe because we don't know that this is the same `T` that this `CloneableErased` was
+     * created with.
+     */
+    pub unsafe fn into_inner<T>(self) -> T {
+        self.into_erased().into_inner()
+    }
+
+    /**
+     * Convert to a `Trident<T>`.
+     *
+     * # Safety
+     *
+     * Unsafe because we don't know that this is the same `T` that this `CloneableErased` was
+     * created with.
+     */
+    pub unsafe fn into_trident<T>(self) -> Trident<T> {
+        self.into_erased().into_trident()
+    }
+
+    /**
+     * Convert to an `Erased`, which won't drop the `T`.
+     */
+    pub fn into_erased(self) -> Erased {
+        let this = ManuallyDrop::new(self);
+
+        // SAFETY: `this` is never dropped, so ownership of the storage moves to the returned Erased
+        unsafe { ptr::read(&this.erased) }
+    }
+}
+
+impl Clone for CloneableErased {
+    fn clone(&self) -> Self {
+        Self {
+            // SAFETY: the vtable was created for the T we hold
+            erased: unsafe { (self.vtable.clone)(&self.erased) },
+            vtable: self.vtable,
+        }
+    }
+}
+
+impl Drop for CloneableErased {
+    fn drop(&mut self) {
+        // SAFETY: the vtable was created for the T we hold
+        unsafe { (self.vtable.drop)(&mut self.erased) }
+    }
+}
+
+impl<T: Clone + 'static> From<Trident<T>> for CloneableErased {
+    fn from(t: Trident<T>) -> Self {
+        Self::from_trident(t)
+    }
+}
+
+/**
+ * Clone the `T` held by `erased`.
+ *
+ * Unsafe because `erased` must hold a `T`.
+ */
+unsafe fn clone_erased<T: Clone>(erased: &Erased) -> Erased {
+    Erased::new(erased.as_ref::<T>().clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+
+    #[test]
+    fn deep_clones() {
+        let shared = Rc::new(());
+        let world = vec![
+            CloneableErased::new(1u8),
+            CloneableErased::new(String::from("two")),
+            CloneableErased::new([
+                shared.clone(),
+                shared.clone(),
+                shared.clone(),
+                shared.clone(),
+            ]),
+        ];
+
+        let mut snapshot = world.clone();
+        assert_eq!(Rc::strong_count(&shared), 9);
+
+        unsafe {
+            snapshot[1].as_mut_ref::<String>().push('!');
+
+            assert_eq!(world[0].as_ref::<u8>(), &1);
+            assert_eq!(world[1].as_ref::<String>(), "two");
+            assert_eq!(snapshot[1].as_ref::<String>(), "two!");
+        }
+
+        drop(world);
+        drop(snapshot);
+        assert_eq!(Rc::strong_count(&shared), 1);
+    }
+
+    #[test]
+    fn converts() {
+        let erased = CloneableErased::from(Trident::new(vec![1, 2]));
+        let cloned = erased.clone();
+
+        assert_eq!(unsafe { erased.into_inner::<Vec<i32>>() }, [1, 2]);
+        assert_eq!(*unsafe { cloned.into_trident::<Vec<i32>>() }, [1, 2]);
+    }
+}
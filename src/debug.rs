@@ -0,0 +1,183 @@
+use std::{fmt, marker::PhantomData, mem::ManuallyDrop, ptr};
+
+use crate::owned::drop_erased;
+use crate::{Erased, Trident};
+
+/**
+ * A type-erased `T: Debug`, as `OwnedErased`, which can also be formatted without knowing `T`.
+ *
+ * For example, erased values can still be logged or shown in panic messages. This costs a word
+ * for a pointer to `T`'s formatting and drop functions.
+ *
+ * `T` must be `'static`, as a `DebugErased` has no lifetime to stop it outliving anything the `T`
+ * borrows.
+ */
+pub struct DebugErased {
+    erased: Erased,
+    vtable: &'static DebugVTable,
+}
+
+struct DebugVTable {
+    fmt: unsafe fn(&Erased, &mut fmt::Formatter<'_>) -> fmt::Result,
+    drop: unsafe fn(&mut Erased),
+}
+
+struct VTableFor<T>(PhantomData<T>);
+
+impl<T: fmt::Debug> VTableFor<T> {
+    const VTABLE: DebugVTable = DebugVTable {
+        fmt: fmt_erased::<T>,
+        drop: drop_erased::<T>,
+    };
+}
+
+impl DebugErased {
+    /**
+     * Create a `DebugErased` from a `T`.
+     */
+    pub fn new<T: fmt::Debug + 'static>(t: T) -> Self {
+        Self::from_trident(Trident::new(t))
+    }
+
+    /**
+     * Create a `DebugErased` from a `Trident<T>`, keeping its storage.
+     */
+    pub fn from_trident<T: fmt::Debug + 'static>(t: Trident<T>) -> Self {
+        Self {
+            erased: t.into_erased(),
+            vtable: &VTableFor::<T>::VTABLE,
+        }
+    }
+
+    /**
+     * Get a reference to the contained `T`.
+     *
+     * # Safety
+     *
+     * Unsafe because we don't know that this is the same `T` that this `DebugErased` was
+     * created with.
+     */
+    pub unsafe fn as_ref<T>(&self) -> &T {
+        self.erased.as_ref()
+    }
+
+    /**
+     * Get a mutable reference to the contained `T`.
+     *
+     * # Safety
+     *
+     * Unsafe because we don't know that this is the same `T` that this `DebugErased` was
+     * created with.
+     */
+    pub unsafe fn as_mut_ref<T>(&mut self) -> &mut T {
+        self.erased.as_mut_ref()
+    }
+
+    /**
+     * Get the contained `T`.
+     *
+     * # Safety
+     *
+     * Unsafe because we don't know that this is the same `T` that this `DebugErased` was
+     * created with.
+     */
+    pub unsafe fn into_inner<T>(self) -> T {
+        self.into_erased().into_inner()
+    }
+
+    /**
+     * Convert to a `Trident<T>`.
+     *
+     * # Safety
+     *
+     * Unsafe because we don't know that this is the same `T` that this `DebugErased` was
+     * created with.
+     */
+    pub unsafe fn into_trident<T>(self) -> Trident<T> {
+        self.into_erased().into_trident()
+    }
+
+    /**
+     * Convert to an `Erased`, which won't drop the `T`.
+     */
+    pub fn into_erased(self) -> Erased {
+        let this = ManuallyDrop::new(self);
+
+        // SAFETY: `this` is never dropped, so ownership of the storage moves to the returned Erased
+        unsafe { ptr::read(&this.erased) }
+    }
+}
+
+/**
+ * Forwards to `T`'s `Debug` implementation.
+ */
+impl fmt::Debug for DebugErased {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // SAFETY: the vtable was created for the T we hold
+        unsafe { (self.vtable.fmt)(&self.erased, f) }
+    }
+}
+
+impl Drop for DebugErased {
+    fn drop(&mut self) {
+        // SAFETY: the vtable was created for the T we hold
+        unsafe { (self.vtable.drop)(&mut self.erased) }
+    }
+}
+
+impl<T: fmt::Debug + 'static> From<Trident<T>> for DebugErased {
+    fn from(t: Trident<T>) -> Self {
+        Self::from_trident(t)
+    }
+}
+
+/**
+ * Format the `T` held by `erased`.
+ *
+ * Unsafe because `erased` must hold a `T`.
+ */
+unsafe fn fmt_erased<T: fmt::Debug>(erased: &Erased, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    fmt::Debug::fmt(erased.as_ref::<T>(), f)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use super::*;
+
+    #[test]
+    fn formats() {
+        let values = [
+            DebugErased::new(1u8),
+            DebugErased::new("two"),
+            DebugErased::new([3u64; 4]),
+        ];
+
+        assert_eq!(format!("{:?}", values), r#"[1, "two", [3, 3, 3, 3]]"#);
+        assert_eq!(format!("{:#?}", values[0]), "1");
+    }
+
+    #[test]
+    fn drops() {
+        #[derive(Debug)]
+        struct Dtor(Rc<Cell<u32>>);
+
+        impl Drop for Dtor {
+            fn drop(&mut self) {
+                self.0.set(self.0.get() + 1);
+            }
+        }
+
+        let drops = Rc::new(Cell::new(0));
+        drop(DebugErased::new(Dtor(drops.clone())));
+        assert_eq!(drops.get(), 1);
+
+        let erased = DebugErased::from(Trident::new(Dtor(drops.clone())));
+        assert_eq!(format!("{:?}", erased), "Dtor(Cell { value: 1 })");
+        let t = unsafe { erased.into_trident::<Dtor>() };
+        assert_eq!(drops.get(), 1);
+        drop(t);
+        assert_eq!(drops.get(), 2);
+    }
+}
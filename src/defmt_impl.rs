@@ -0,0 +1,15 @@
+/**
+ * `defmt` support, for logging tridents from embedded targets.
+ */
+use defmt::{Format, Formatter};
+
+use crate::Trident;
+
+impl<T> Format for Trident<T>
+where
+    T: Format,
+{
+    fn format(&self, f: Formatter<'_>) {
+        self.as_ref().format(f)
+    }
+}
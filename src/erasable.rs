@@ -0,0 +1,41 @@
+/**
+ * A marker for types which have been checked, at compile time, to fit inline in a `Trident`, or
+ * which are explicitly allowed to be boxed.
+ *
+ * With the `derive` feature, `#[derive(Erasable)]` implements this, failing to compile with the
+ * type's size if it doesn't fit. `#[erasable(spill)]` allows a type which doesn't fit, so
+ * implements this without checking. Generic code can bound on `Erasable` to only accept types
+ * which have been checked.
+ */
+pub trait Erasable {}
+
+#[cfg(all(test, feature = "derive"))]
+mod tests {
+    use crate::{Erasable, Trident};
+
+    #[derive(Erasable)]
+    #[erasable(crate = crate)]
+    struct Small(#[allow(dead_code)] [u32; 4]);
+
+    #[derive(Erasable)]
+    #[erasable(crate = crate)]
+    struct Borrowed<'a>(&'a str, #[allow(dead_code)] usize);
+
+    #[derive(Erasable)]
+    #[erasable(crate = crate, spill)]
+    struct Large(#[allow(dead_code)] [u64; 8]);
+
+    fn is_inline<T: Erasable>(t: T) -> bool {
+        Trident::new(t).is_inline()
+    }
+
+    #[test]
+    fn derives() {
+        let s = String::from("borrowed");
+
+        assert!(is_inline(Small([1; 4])));
+        assert!(is_inline(Borrowed(&s, 1)));
+        assert!(!is_inline(Large([2; 8])));
+        assert_eq!(Borrowed(&s, 0).0, "borrowed");
+    }
+}
@@ -1,10 +1,23 @@
 /**
  * A struct that stores a type-erased `T`, either inline or, if `T` is larger than 3 words, allocated.
+ *
+ * As the payload's type is unknown, an `Erased` is neither `Send` nor `Sync`. See `ErasedSend`
+ * and `ErasedSync`.
  */
-use std::{mem, ptr};
+#[cfg(feature = "checked")]
+use std::any::{self, TypeId};
+use std::{
+    alloc::{self, Layout},
+    cell::UnsafeCell,
+    error::Error,
+    fmt,
+    marker::PhantomData,
+    mem::{self, MaybeUninit},
+    ptr,
+};
 
 use crate::into;
-use crate::limits::{self, NWORDS};
+use crate::limits::{self, NWORDS, SIZE_LIMIT};
 use crate::Trident;
 
 #[cfg(not(any(target_pointer_width = "64", target_pointer_width = "32")))]
@@ -13,7 +26,92 @@ compile_error!("Not a 32- or 64-bit machine");
 #[cfg_attr(target_pointer_width = "64", repr(C, align(8)))]
 #[cfg_attr(target_pointer_width = "32", repr(C, align(4)))]
 pub struct Erased {
-    words: [usize; NWORDS],
+    // MaybeUninit, as inline payloads may have padding or pointers, and UnsafeCell, as they may
+    // be mutated through a shared reference, e.g. a Cell or an atomic
+    words: UnsafeCell<Words>,
+    // the payload's type, if known, checked on access
+    #[cfg(feature = "checked")]
+    payload_type: Option<PayloadType>,
+    // the payload may be neither Send nor Sync, see ErasedSend and ErasedSync
+    _not_send_sync: PhantomData<*mut ()>,
+}
+
+/**
+ * A payload's type, recorded under the `checked` feature.
+ */
+#[cfg(feature = "checked")]
+#[derive(Clone, Copy)]
+pub(crate) struct PayloadType {
+    // compared, with lifetimes erased, as `TypeId::of` requires `'static`
+    id: TypeId,
+    // for error messages
+    name: &'static str,
+}
+
+#[cfg(feature = "checked")]
+impl PayloadType {
+    pub(crate) fn of<T>() -> Self {
+        Self {
+            id: type_id::<T>(),
+            name: any::type_name::<T>(),
+        }
+    }
+
+    pub(crate) fn is<T>(self) -> bool {
+        self.id == type_id::<T>()
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        self.name
+    }
+}
+
+/**
+ * The `TypeId` of `T` with its lifetimes erased, so `T` needn't be `'static`.
+ */
+#[cfg(feature = "checked")]
+fn type_id<T>() -> TypeId {
+    trait NonStaticAny {
+        fn type_id(&self) -> TypeId
+        where
+            Self: 'static;
+    }
+
+    impl<T> NonStaticAny for PhantomData<T> {
+        fn type_id(&self) -> TypeId
+        where
+            Self: 'static,
+        {
+            TypeId::of::<T>()
+        }
+    }
+
+    let phantom = PhantomData::<T>;
+    // SAFETY: lifetimes don't affect the layout or vtable, and TypeId::of only reads the type,
+    // with its lifetimes erased, so nothing outlives its borrow
+    let phantom =
+        unsafe { mem::transmute::<&dyn NonStaticAny, &(dyn NonStaticAny + 'static)>(&phantom) };
+    NonStaticAny::type_id(phantom)
+}
+
+/**
+ * The raw storage of an `Erased`.
+ */
+pub(crate) type Words = [MaybeUninit<usize>; NWORDS];
+
+/**
+ * The byte which flags that an inline-sized payload has been moved to the heap. See
+ * `limits::can_spill`.
+ */
+const SPILLED_FLAG: usize = SIZE_LIMIT - 1;
+
+/**
+ * Storage for an inline payload, with the spilled flag clear.
+ */
+const fn inline_words() -> Words {
+    let mut words = [MaybeUninit::uninit(); NWORDS];
+    words[NWORDS - 1] = MaybeUninit::new(0);
+    words
 }
 
 impl Erased {
@@ -22,86 +120,722 @@ impl Erased {
      *
      * `T`'s destructor cannot be run, as the type is erased.
      */
+    #[inline]
     pub fn new<T>(t: T) -> Self {
         if limits::should_inline::<T>() {
-            let mut ret = Self { words: [0; NWORDS] };
+            let mut ret = unsafe { Self::from_words(inline_words()) };
 
             unsafe {
-                ptr::copy_nonoverlapping(&t, ret.as_mut_ref(), 1);
+                ptr::write(ret.as_mut_ptr(), t);
             }
-            mem::forget(t);
 
-            ret
+            ret.retype::<T>()
+        } else {
+            unsafe { Self::from_spilled(spill(t)) }
+        }
+    }
+
+    /**
+     * Create an `Erased` from a `T` which is small enough to be stored inline, in a const context.
+     *
+     * The payload's type isn't recorded, so accesses aren't checked under the `checked` feature.
+     *
+     * # Panics
+     *
+     * Panics (at compile time, if evaluated in a const context) if `T` is too large to inline.
+     */
+    pub const fn new_inline<T>(t: T) -> Self {
+        assert!(
+            limits::should_inline::<T>(),
+            "T is too large to be stored inline"
+        );
+
+        let mut words = inline_words();
+        unsafe {
+            ptr::write(&mut words as *mut Words as *mut T, t);
+        }
+
+        // TypeId::of isn't const for non-'static types, so this is never checked
+        unsafe { Self::from_words(words) }
+    }
+
+    /**
+     * Create an `Erased` from a `T`, returning an error rather than aborting if allocation fails.
+     *
+     * Inline `T`s never fail. On failure, `t` is dropped.
+     */
+    pub fn try_new<T>(t: T) -> Result<Self, AllocError> {
+        if limits::should_inline::<T>() {
+            Ok(Self::new(t))
         } else {
-            let alloc = Box::new(t);
+            let ptr = try_allocate::<T>()?;
 
-            Self {
-                words: [Box::into_raw(alloc) as usize, 0, 0],
+            unsafe {
+                ptr::write(ptr, t);
             }
+
+            Ok(unsafe { Self::from_spilled(ptr) })
+        }
+    }
+
+    /**
+     * Create an `Erased` by initialising a `T` in place, in its final inline or heap location.
+     *
+     * `init` is given the uninitialised slot and must write a `T` to it, returning the reference
+     * from `MaybeUninit::write`. This avoids building a large `T` on the stack before moving it.
+     *
+     * # Panics
+     *
+     * Panics if `init` returns a reference to anything other than the slot it was given.
+     */
+    pub fn new_with<T, F>(init: F) -> Self
+    where
+        F: FnOnce(&mut MaybeUninit<T>) -> &mut T,
+    {
+        if limits::should_inline::<T>() {
+            let mut ret = unsafe { Self::from_words(inline_words()) };
+
+            init_slot(unsafe { ret.as_mut_ptr::<T>() }, init);
+
+            ret.retype::<T>()
+        } else {
+            let guard = DeallocGuard(allocate::<T>());
+
+            init_slot(guard.0, init);
+
+            unsafe { Self::from_spilled(mem::ManuallyDrop::new(guard).0) }
+        }
+    }
+
+    /**
+     * Create an `Erased` from its raw words.
+     *
+     * Unsafe because the words must describe a valid inline or boxed payload.
+     */
+    pub(crate) const unsafe fn from_words(words: Words) -> Self {
+        Self {
+            words: UnsafeCell::new(words),
+            #[cfg(feature = "checked")]
+            payload_type: None,
+            _not_send_sync: PhantomData,
+        }
+    }
+
+    /**
+     * Record that we hold a `T`, for checking on access.
+     */
+    #[cfg(feature = "checked")]
+    pub(crate) fn retype<T>(mut self) -> Self {
+        self.payload_type = Some(PayloadType::of::<T>());
+        self
+    }
+
+    #[cfg(not(feature = "checked"))]
+    #[inline(always)]
+    #[allow(clippy::extra_unused_type_parameters)]
+    pub(crate) fn retype<T>(self) -> Self {
+        self
+    }
+
+    /**
+     * Panic if we're known to hold something other than a `T`.
+     */
+    #[cfg(feature = "checked")]
+    #[track_caller]
+    fn check<T>(&self) {
+        if let Some(payload_type) = self.payload_type {
+            assert!(
+                payload_type.is::<T>(),
+                "Erased holds a `{}`, not a `{}`",
+                payload_type.name(),
+                any::type_name::<T>()
+            );
+        }
+    }
+
+    #[cfg(not(feature = "checked"))]
+    #[inline(always)]
+    fn check<T>(&self) {}
+
+    /**
+     * Decompose into raw words, for example to embed in a custom data structure.
+     *
+     * The words own the payload, until passed back to `from_raw_words`. A boxed payload's pointer
+     * is stored as a pointer, so keeps its provenance as long as the words are moved or copied as
+     * `MaybeUninit<usize>`. Reading a word out as a plain `usize` would discard it.
+     */
+    pub fn into_raw_words(self) -> [MaybeUninit<usize>; 3] {
+        self.words.into_inner()
+    }
+
+    /**
+     * Reconstruct an `Erased` from words returned by `into_raw_words`.
+     *
+     * # Safety
+     *
+     * `words` must have been returned by `into_raw_words`, and be passed here at most once if the
+     * payload isn't `Copy`.
+     *
+     * The words don't record the payload's type, so accesses aren't checked under the `checked`
+     * feature.
+     */
+    pub unsafe fn from_raw_words(words: [MaybeUninit<usize>; 3]) -> Self {
+        Self::from_words(words)
+    }
+
+    /**
+     * Get the raw words.
+     *
+     * Unsafe because an inline payload may be mutated through a shared reference, which the
+     * returned reference mustn't overlap.
+     */
+    #[cfg(feature = "allocator-api2")]
+    pub(crate) unsafe fn words(&self) -> &Words {
+        &*self.words.get()
+    }
+
+    /**
+     * Get the raw words, mutably.
+     */
+    #[cfg(feature = "allocator-api2")]
+    pub(crate) fn words_mut(&mut self) -> &mut Words {
+        self.words.get_mut()
+    }
+
+    /**
+     * Create an `Erased` from a boxed `T`.
+     *
+     * Unsafe because the `T` must be too large to be stored inline, or be `limits::can_spill`.
+     */
+    pub(crate) unsafe fn from_spilled<T>(ptr: *mut T) -> Self {
+        let mut words = [MaybeUninit::uninit(); NWORDS];
+        // written as a pointer rather than an address, to keep its provenance
+        ptr::write(words.as_mut_ptr() as *mut *mut T, ptr);
+
+        if limits::should_inline::<T>() {
+            words[NWORDS - 1] = MaybeUninit::new(0);
+            *(&mut words as *mut Words as *mut u8).add(SPILLED_FLAG) = 1;
         }
+
+        Self::from_words(words).retype::<T>()
+    }
+
+    /**
+     * Create an `Erased` with `t` on the heap, even if it would fit inline.
+     *
+     * Unsafe because the `T` must be too large to be stored inline, or be `limits::can_spill`.
+     */
+    pub(crate) unsafe fn new_spilled<T>(t: T) -> Self {
+        Self::from_spilled(spill(t))
+    }
+
+    /**
+     * Create an `Erased` with `t` on the heap, holding only its pointer, without the spilled flag.
+     *
+     * Only a holder which knows the payload is boxed, such as `Trident<T, AlwaysHeap>`, can access
+     * it, but that holds for any `T`, including those which fill all 3 words or are zero-sized.
+     */
+    pub(crate) fn new_boxed<T>(t: T) -> Self {
+        // SAFETY: spill() boxes the T
+        unsafe { Self::from_boxed(spill(t)) }
+    }
+
+    /**
+     * As `new_boxed`, for a `T` which is already boxed.
+     *
+     * Unsafe because `ptr` must be allocated as by `spill`, and owned by the returned `Erased`.
+     */
+    pub(crate) unsafe fn from_boxed<T>(ptr: *mut T) -> Self {
+        let mut words = [MaybeUninit::uninit(); NWORDS];
+        // written as a pointer rather than an address, to keep its provenance
+        ptr::write(words.as_mut_ptr() as *mut *mut T, ptr);
+
+        Self::from_words(words).retype::<T>()
+    }
+
+    /**
+     * Whether the contained `T` is stored inline, rather than having been moved to the heap.
+     *
+     * Unsafe because we don't know that this is the same `T` that this `Erased` was created with.
+     */
+    pub(crate) unsafe fn is_inline<T>(&self) -> bool {
+        self.is_inline_layout(Layout::new::<T>())
+    }
+
+    /**
+     * As `is_inline`, for a payload of the given layout.
+     *
+     * Unsafe because `layout` must be that of the payload this `Erased` was created with.
+     */
+    pub(crate) unsafe fn is_inline_layout(&self, layout: Layout) -> bool {
+        limits::inlines(layout)
+            && !(limits::can_spill_layout(layout)
+                && *(self.words.get() as *const u8).add(SPILLED_FLAG) != 0)
+    }
+
+    /**
+     * Get the pointer to a payload which isn't inline.
+     *
+     * Unsafe because the payload must not be inline.
+     */
+    pub(crate) unsafe fn spilled_ptr(&self) -> *mut u8 {
+        *(self.words.get() as *const *mut u8)
+    }
+
+    /**
+     * Move an inline `T` to the heap.
+     *
+     * Unsafe because the `T` must be inline and `limits::can_spill`.
+     */
+    pub(crate) unsafe fn spill_in_place<T>(&mut self) {
+        // allocate before moving anything, in case of unwinding
+        let ptr = allocate::<T>();
+        ptr::copy_nonoverlapping(self.as_ptr::<T>(), ptr, 1);
+
+        *self = Self::from_spilled(ptr);
+    }
+
+    /**
+     * Move a `T` which was moved to the heap back inline, freeing its allocation.
+     *
+     * Unsafe because the `T` must have been moved with `spill_in_place`.
+     */
+    pub(crate) unsafe fn unspill_in_place<T>(&mut self) {
+        let ptr = self.as_mut_ptr::<T>();
+        let t = ptr::read(ptr);
+        drop(DeallocGuard(ptr));
+
+        *self = Self::new(t);
     }
 
     /**
      * Get a pointer to the contained `T`.
+     *
+     * # Safety
+     *
      * Unsafe because we don't know that this is the same `T` that this `Erased` was created with.
      */
+    #[cfg_attr(feature = "checked", track_caller)]
     pub unsafe fn as_ptr<T>(&self) -> *const T {
-        if limits::should_inline::<T>() {
-            &self.words as *const _ as usize as *const T
+        self.as_ptr_with(self.is_inline::<T>())
+    }
+
+    /**
+     * As `as_ptr`, where whether the `T` is inline is already known, e.g. from a `Storage`.
+     *
+     * Unsafe because we must hold a `T`, stored inline if `inline` is set.
+     */
+    #[cfg_attr(feature = "checked", track_caller)]
+    pub(crate) unsafe fn as_ptr_with<T>(&self, inline: bool) -> *const T {
+        self.check::<T>();
+
+        if inline {
+            // via the UnsafeCell, so the pointer may be written through if T allows
+            self.words.get().cast()
         } else {
-            self.words[0] as *const T
+            *(self.words.get() as *const *const T)
         }
     }
 
     /**
      * Get a reference to the contained `T`.
+     *
+     * # Safety
+     *
      * Unsafe because we don't know that this is the same `T` that this `Erased` was created with.
      */
+    #[cfg_attr(feature = "checked", track_caller)]
     pub unsafe fn as_ref<T>(&self) -> &T {
         &*self.as_ptr()
     }
 
     /**
      * Get a mutable pointer to the contained `T`.
+     *
+     * # Safety
+     *
      * Unsafe because we don't know that this is the same `T` that this `Erased` was created with.
      */
+    #[cfg_attr(feature = "checked", track_caller)]
     pub unsafe fn as_mut_ptr<T>(&mut self) -> *mut T {
-        if limits::should_inline::<T>() {
-            &mut self.words as *mut _ as usize as *mut T
+        self.as_mut_ptr_with(self.is_inline::<T>())
+    }
+
+    /**
+     * As `as_mut_ptr`, where whether the `T` is inline is already known, as for `as_ptr_with`.
+     */
+    #[cfg_attr(feature = "checked", track_caller)]
+    pub(crate) unsafe fn as_mut_ptr_with<T>(&mut self, inline: bool) -> *mut T {
+        self.check::<T>();
+
+        if inline {
+            self.words.get_mut() as *mut Words as *mut T
         } else {
-            self.words[0] as *mut T
+            *(self.words.get() as *const *mut T)
         }
     }
 
     /**
      * Get a mutable reference to the contained `T`.
+     *
+     * # Safety
+     *
      * Unsafe because we don't know that this is the same `T` that this `Erased` was created with.
      */
+    #[cfg_attr(feature = "checked", track_caller)]
     pub unsafe fn as_mut_ref<T>(&mut self) -> &mut T {
         &mut *self.as_mut_ptr()
     }
 
     /**
      * Copy the contained `T`.
+     *
+     * # Safety
+     *
      * Unsafe because we don't know that this is the same `T` that this `Erased` was created with.
      */
+    #[cfg_attr(feature = "checked", track_caller)]
     pub unsafe fn get<T: Copy>(&self) -> T {
         *self.as_ref()
     }
 
     /**
      * Get the contained `T`.
+     *
+     * # Safety
+     *
      * Unsafe because we don't know that this is the same `T` that this `Erased` was created with.
      */
+    #[cfg_attr(feature = "checked", track_caller)]
     pub unsafe fn into_inner<T>(mut self) -> T {
-        into::into_inner(self.as_mut_ptr(), self)
+        let inline = self.is_inline::<T>();
+        into::into_inner(self.as_mut_ptr(), inline, self)
     }
 
     /**
      * Convert to a `Trident<T>`
+     *
+     * # Safety
+     *
      * Unsafe because we don't know that this is the same `T` that this `Erased` was created with.
      */
     pub unsafe fn into_trident<T>(self) -> Trident<T> {
         Trident::from_erased(self)
     }
+
+    /**
+     * Construct an `Erased` holding `t` directly at `dst`, for example in shared memory or an
+     * mmapped buffer.
+     *
+     * A `T` too large to be stored inline is allocated on this process's heap as usual, so only
+     * inline payloads are meaningful to another process.
+     *
+     * # Safety
+     *
+     * `dst` must be valid for writes of an `Erased`, and aligned to `mem::align_of::<Erased>()`,
+     * that is, to a word. Anything already at `dst` is overwritten without being dropped.
+     */
+    pub unsafe fn write_to<T>(dst: *mut Erased, t: T) {
+        ptr::write(dst, Erased::new(t));
+    }
+
+    /**
+     * Move the `T` out of an `Erased` at `src`, freeing any allocation.
+     *
+     * # Safety
+     *
+     * `src` must be valid for reads of an `Erased`, aligned as for `write_to`, and hold a `T`, for
+     * example from `write_to::<T>`. Afterwards, the `Erased` at `src` must be treated as
+     * uninitialised.
+     */
+    #[cfg_attr(feature = "checked", track_caller)]
+    pub unsafe fn read_from<T>(src: *const Erased) -> T {
+        ptr::read(src).into_inner()
+    }
+
+    /**
+     * Drop the `T` in an `Erased` at `ptr`, freeing any allocation.
+     *
+     * # Safety
+     *
+     * As `read_from`.
+     */
+    #[cfg_attr(feature = "checked", track_caller)]
+    pub unsafe fn drop_in_place<T>(ptr: *mut Erased) {
+        drop(Erased::read_from::<T>(ptr));
+    }
+
+    /**
+     * Swap payloads with `other`, whatever their types, by exchanging the words.
+     */
+    pub fn swap(&mut self, other: &mut Erased) {
+        mem::swap(self, other);
+    }
+
+    /**
+     * Replace our payload with `other`'s, returning the old one, whatever their types.
+     */
+    pub fn replace(&mut self, other: Erased) -> Erased {
+        mem::replace(self, other)
+    }
+
+    /**
+     * Discard the `Erased` without dropping the contained `T` or freeing its allocation.
+     *
+     * An `Erased` never runs `T`'s destructor, so this is equivalent to dropping it, but states
+     * the intent, for example once ownership of the `T` has been handed elsewhere via `as_ptr`.
+     */
+    pub fn forget(self) {
+        // nothing to do, we have no destructor
+    }
+}
+
+/**
+ * The error returned when a fallible constructor fails to allocate.
+ */
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct AllocError;
+
+impl fmt::Display for AllocError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("memory allocation failed")
+    }
+}
+
+impl Error for AllocError {}
+
+/**
+ * Allocate space for a `T`, which must be too large to be stored inline, or `limits::can_spill`.
+ */
+fn try_allocate<T>() -> Result<*mut T, AllocError> {
+    // an over-aligned ZST isn't inlined, but needs no allocation
+    if mem::size_of::<T>() == 0 {
+        return Ok(ptr::NonNull::dangling().as_ptr());
+    }
+
+    // SAFETY: T isn't zero-sized
+    let ptr = unsafe { alloc::alloc(Layout::new::<T>()) } as *mut T;
+
+    if ptr.is_null() {
+        Err(AllocError)
+    } else {
+        Ok(ptr)
+    }
+}
+
+/**
+ * As `try_allocate`, but aborts on allocation failure.
+ */
+fn allocate<T>() -> *mut T {
+    try_allocate().unwrap_or_else(|AllocError| alloc::handle_alloc_error(Layout::new::<T>()))
+}
+
+/**
+ * Move `t` to the heap.
+ *
+ * The allocation is made before `t` is written, so a large `t` is copied once, straight into
+ * place, rather than via an intermediate `Box::new` frame.
+ */
+#[inline(always)]
+fn spill<T>(t: T) -> *mut T {
+    let ptr = allocate::<T>();
+
+    unsafe {
+        ptr::write(ptr, t);
+    }
+
+    ptr
+}
+
+/**
+ * Free a boxed `T`'s allocation, without dropping the `T`. A zero-sized `T` was never allocated.
+ *
+ * Unsafe because `ptr` must be from `try_allocate::<T>`, and not used again.
+ */
+pub(crate) unsafe fn dealloc<T>(ptr: *mut T) {
+    if mem::size_of::<T>() != 0 {
+        alloc::dealloc(ptr as *mut u8, Layout::new::<T>());
+    }
+}
+
+/**
+ * Frees a boxed `T`'s allocation, without dropping the `T`, e.g. if its initialisation unwinds.
+ */
+pub(crate) struct DeallocGuard<T>(pub(crate) *mut T);
+
+impl<T> Drop for DeallocGuard<T> {
+    fn drop(&mut self) {
+        unsafe { dealloc(self.0) }
+    }
+}
+
+/**
+ * Initialise the `T` at `slot` with `init`, which must return a reference to `slot`'s contents.
+ *
+ * # Panics
+ *
+ * Panics if `init` returns a reference to some other `T`.
+ */
+fn init_slot<T, F>(slot: *mut T, init: F)
+where
+    F: FnOnce(&mut MaybeUninit<T>) -> &mut T,
+{
+    // SAFETY: slot is valid for writes and suitably aligned for a T
+    let init_ptr: *mut T = init(unsafe { &mut *(slot as *mut MaybeUninit<T>) });
+
+    assert!(
+        ptr::eq(init_ptr, slot),
+        "new_with() initialiser must return a reference to the slot it was given"
+    );
+}
+
+/**
+ * Formats the address of the `Erased`'s inline storage.
+ *
+ * As the payload's type is unknown, this cannot follow a spilled payload to its heap allocation.
+ * Use `as_ptr::<T>()`, or format the corresponding `Trident<T>`, for that.
+ */
+impl fmt::Pointer for Erased {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Pointer::fmt(&(self.words.get() as *const Words), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{mem::MaybeUninit, rc::Rc};
+
+    use super::Erased;
+
+    #[test]
+    fn boxes_over_aligned_zsts_without_allocating() {
+        #[derive(Debug, PartialEq)]
+        #[repr(align(64))]
+        struct Aligned;
+
+        let erased = Erased::new(Aligned);
+        let with = Erased::new_with::<Aligned, _>(|slot| slot.write(Aligned));
+        let mut tried = Erased::try_new(Aligned).unwrap();
+
+        unsafe {
+            assert!(!erased.is_inline::<Aligned>());
+            assert_eq!(erased.as_ptr::<Aligned>() as usize % 64, 0);
+            assert_eq!(erased.into_inner::<Aligned>(), Aligned);
+            assert_eq!(with.into_inner::<Aligned>(), Aligned);
+            Erased::drop_in_place::<Aligned>(&mut tried);
+        }
+    }
+
+    #[test]
+    fn boxes_over_aligned_types() {
+        #[derive(Clone, Copy, Debug, PartialEq)]
+        #[repr(align(16))]
+        struct Aligned(u32);
+
+        let erased = [
+            Erased::new(Aligned(1)),
+            Erased::try_new(Aligned(2)).unwrap(),
+            Erased::new_with(|slot| slot.write(Aligned(3))),
+        ];
+
+        for (i, erased) in erased.into_iter().enumerate() {
+            unsafe {
+                assert!(!erased.is_inline::<Aligned>());
+                assert_eq!(erased.as_ptr::<Aligned>() as usize % 16, 0);
+                assert_eq!(erased.into_inner::<Aligned>(), Aligned(i as u32 + 1));
+            }
+        }
+    }
+
+    #[test]
+    fn swaps_and_replaces() {
+        let mut a = Erased::new(1u8);
+        let mut b = Erased::new([2u64; 5]);
+
+        a.swap(&mut b);
+        let old = a.replace(Erased::new("three"));
+
+        unsafe {
+            assert_eq!(old.into_inner::<[u64; 5]>(), [2; 5]);
+            assert_eq!(a.into_inner::<&str>(), "three");
+            assert_eq!(b.into_inner::<u8>(), 1);
+        }
+    }
+
+    #[test]
+    fn round_trips_raw_words() {
+        let words = Erased::new(vec![1u8; 3]).into_raw_words();
+        let inline = unsafe { Erased::from_raw_words(words).into_inner::<Vec<u8>>() };
+        assert_eq!(inline, [1; 3]);
+
+        // a copy of the words keeps the boxed pointer's provenance
+        let words = Erased::new([String::from("x"), String::from("y")]).into_raw_words();
+        let copied = words;
+        let boxed = unsafe { Erased::from_raw_words(copied).into_inner::<[String; 2]>() };
+        assert_eq!(boxed, ["x", "y"]);
+    }
+
+    #[test]
+    fn constructs_in_place() {
+        let mut buf = [MaybeUninit::<Erased>::uninit(), MaybeUninit::uninit()];
+        let rc = Rc::new(());
+
+        unsafe {
+            Erased::write_to(buf[0].as_mut_ptr(), 5u32);
+            Erased::write_to(
+                buf[1].as_mut_ptr(),
+                [rc.clone(), rc.clone(), rc.clone(), rc.clone()],
+            );
+            assert_eq!(buf[0].assume_init_ref().get::<u32>(), 5);
+            assert_eq!(Rc::strong_count(&rc), 5);
+
+            assert_eq!(Erased::read_from::<u32>(buf[0].as_ptr()), 5);
+            Erased::drop_in_place::<[Rc<()>; 4]>(buf[1].as_mut_ptr());
+        }
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+
+    #[cfg(feature = "checked")]
+    #[test]
+    fn accepts_matching_type() {
+        let erased = Erased::new([1u64; 6]);
+        assert_eq!(unsafe { erased.into_inner::<[u64; 6]>() }, [1; 6]);
+
+        let t = crate::Trident::<String>::uninit().write(String::from("x"));
+        assert_eq!(unsafe { t.into_erased().into_inner::<String>() }, "x");
+    }
+
+    #[cfg(feature = "checked")]
+    #[test]
+    fn accepts_borrowed_types() {
+        let x = 5u32;
+        let erased = Erased::new((&x, 1u8));
+        assert_eq!(unsafe { erased.into_inner::<(&u32, u8)>() }, (&5, 1));
+    }
+
+    #[cfg(feature = "checked")]
+    #[test]
+    #[should_panic(
+        expected = "not a `trident::erased::tests::rejects_same_named_types::{{closure}}`"
+    )]
+    fn rejects_same_named_types() {
+        fn erase<F: Fn() -> u32>(f: F) -> Erased {
+            Erased::new(f)
+        }
+        fn call<F: Fn() -> u32 + Copy>(erased: &Erased, _: &F) -> u32 {
+            unsafe { erased.get::<F>()() }
+        }
+
+        let (a, b) = (1u32, 2u32);
+        let erased = erase(move || a);
+        let _ = call(&erased, &move || b);
+    }
+
+    #[cfg(feature = "checked")]
+    #[test]
+    #[should_panic(expected = "Erased holds a `u32`, not a `f32`")]
+    fn rejects_mismatched_type() {
+        let erased = Erased::new(1u32);
+        let _ = unsafe { erased.get::<f32>() };
+    }
 }
@@ -0,0 +1,249 @@
+use std::{collections::VecDeque, fmt};
+
+use crate::OwnedErased;
+
+/**
+ * A ring buffer of values of differing types, each stored as an `OwnedErased`, so recording its
+ * own drop glue, and contiguously if inline-sized, otherwise boxed.
+ *
+ * Values are pushed at the back and popped from the front, as for a queue of commands. The deque
+ * either grows as needed, or has a fixed capacity, when it never reallocates and pushes fail once
+ * it's full.
+ *
+ * The deque doesn't record the values' types, so typed pops and accesses are unsafe, as for
+ * `OwnedErased`. Values can also be pushed and popped as `OwnedErased`s, without knowing their
+ * types.
+ */
+#[derive(Default)]
+pub struct ErasedDeque {
+    entries: VecDeque<OwnedErased>,
+    fixed_capacity: Option<usize>,
+}
+
+impl ErasedDeque {
+    /**
+     * Create an empty, growable deque.
+     */
+    pub const fn new() -> Self {
+        Self {
+            entries: VecDeque::new(),
+            fixed_capacity: None,
+        }
+    }
+
+    /**
+     * Create an empty, growable deque, with space for at least `capacity` values before
+     * reallocating.
+     */
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity),
+            fixed_capacity: None,
+        }
+    }
+
+    /**
+     * Create an empty deque which holds at most `capacity` values, allocating for them up front.
+     */
+    pub fn with_fixed_capacity(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity),
+            fixed_capacity: Some(capacity),
+        }
+    }
+
+    /**
+     * The maximum number of values, if the capacity is fixed.
+     */
+    pub fn fixed_capacity(&self) -> Option<usize> {
+        self.fixed_capacity
+    }
+
+    /**
+     * The number of values.
+     */
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /**
+     * Whether there are no values.
+     */
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /**
+     * Whether the capacity is fixed and there's no room for another value.
+     */
+    pub fn is_full(&self) -> bool {
+        self.fixed_capacity == Some(self.len())
+    }
+
+    /**
+     * Push `t` at the back.
+     *
+     * `T` must be `'static`, as the deque has no lifetime to stop it outliving anything `t`
+     * borrows.
+     *
+     * # Panics
+     *
+     * Panics if the deque is full.
+     */
+    #[track_caller]
+    pub fn push_back<T: 'static>(&mut self, t: T) {
+        self.push_back_owned(OwnedErased::new(t));
+    }
+
+    /**
+     * Push `t` at the back, or return it if the deque is full. `T` must be `'static`, as for
+     * `push_back`.
+     */
+    pub fn try_push_back<T: 'static>(&mut self, t: T) -> Result<(), T> {
+        if self.is_full() {
+            return Err(t);
+        }
+        self.entries.push_back(OwnedErased::new(t));
+        Ok(())
+    }
+
+    /**
+     * Push a value at the back, without knowing its type.
+     *
+     * # Panics
+     *
+     * Panics if the deque is full.
+     */
+    #[track_caller]
+    pub fn push_back_owned(&mut self, value: OwnedErased) {
+        assert!(!self.is_full(), "pushed onto a full ErasedDeque");
+        self.entries.push_back(value);
+    }
+
+    /**
+     * Push a value at the back, without knowing its type, or return it if the deque is full.
+     */
+    pub fn try_push_back_owned(&mut self, value: OwnedErased) -> Result<(), OwnedErased> {
+        if self.is_full() {
+            return Err(value);
+        }
+        self.entries.push_back(value);
+        Ok(())
+    }
+
+    /**
+     * Pop the value at the front, if any.
+     *
+     * # Safety
+     *
+     * Unsafe because we don't know that the front value is a `T`.
+     */
+    pub unsafe fn pop_front<T>(&mut self) -> Option<T> {
+        self.entries.pop_front().map(|value| value.into_inner())
+    }
+
+    /**
+     * Pop the value at the front, if any, without knowing its type.
+     */
+    pub fn pop_front_owned(&mut self) -> Option<OwnedErased> {
+        self.entries.pop_front()
+    }
+
+    /**
+     * Get a reference to the value at the front, if any.
+     *
+     * # Safety
+     *
+     * Unsafe because we don't know that the front value is a `T`.
+     */
+    pub unsafe fn front<T>(&self) -> Option<&T> {
+        self.entries.front().map(|value| value.as_ref())
+    }
+
+    /**
+     * Get a mutable reference to the value at the front, if any.
+     *
+     * # Safety
+     *
+     * Unsafe because we don't know that the front value is a `T`.
+     */
+    pub unsafe fn front_mut<T>(&mut self) -> Option<&mut T> {
+        self.entries.front_mut().map(|value| value.as_mut_ref())
+    }
+
+    /**
+     * Drop all values, keeping the capacity.
+     */
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+impl fmt::Debug for ErasedDeque {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ErasedDeque")
+            .field("len", &self.len())
+            .field("fixed_capacity", &self.fixed_capacity)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+
+    enum Command {
+        Spawn(u32),
+        Rename([u8; 32]),
+    }
+
+    #[test]
+    fn queues_mixed_types() {
+        let mut deque = ErasedDeque::new();
+
+        deque.push_back(Command::Spawn(3));
+        deque.push_back(String::from("tick"));
+        deque.push_back_owned(OwnedErased::new(Command::Rename([b'x'; 32])));
+
+        unsafe {
+            *deque.front_mut::<Command>().unwrap() = Command::Spawn(4);
+            assert!(matches!(deque.pop_front(), Some(Command::Spawn(4))));
+            assert_eq!(deque.pop_front::<String>().as_deref(), Some("tick"));
+            assert!(matches!(
+                deque.pop_front_owned().map(|c| c.into_inner()),
+                Some(Command::Rename(name)) if name == [b'x'; 32]
+            ));
+            assert!(deque.front::<u8>().is_none());
+        }
+        assert!(deque.is_empty());
+    }
+
+    #[test]
+    fn fixed_capacity_rejects_pushes() {
+        let rc = Rc::new(());
+        let mut deque = ErasedDeque::with_fixed_capacity(2);
+
+        deque.push_back(rc.clone());
+        assert_eq!(deque.try_push_back(5u16), Ok(()));
+        assert!(deque.is_full());
+        assert_eq!(deque.try_push_back(6u16), Err(6));
+        assert!(deque.try_push_back_owned(OwnedErased::new(7u8)).is_err());
+
+        drop(deque.pop_front_owned());
+        assert_eq!(Rc::strong_count(&rc), 1);
+        deque.push_back(rc.clone());
+        assert_eq!(deque.len(), 2);
+
+        drop(deque);
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "full ErasedDeque")]
+    fn panics_pushing_when_full() {
+        let mut deque = ErasedDeque::with_fixed_capacity(0);
+        deque.push_back(());
+    }
+}
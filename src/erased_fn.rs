@@ -0,0 +1,150 @@
+use std::{
+    fmt,
+    marker::PhantomData,
+    mem::{self, MaybeUninit},
+    ptr,
+};
+
+use crate::limits::NWORDS;
+
+/**
+ * The words left for the context, after the function pointer.
+ */
+const CONTEXT_WORDS: usize = NWORDS - 1;
+
+/**
+ * A function pointer, packaged with a context which is passed to it on each call.
+ *
+ * The context is stored inline in the two words after the function pointer, so an `ErasedFn` is
+ * three words, and tables of callbacks need no allocation. As the context is never dropped, it
+ * must be `Copy`, for example an index, a pointer or a small struct of them, and, as an
+ * `ErasedFn` borrows nothing, `'static`. Like `Erased`, an `ErasedFn` is neither `Send` nor
+ * `Sync`.
+ */
+pub struct ErasedFn<A, R = ()> {
+    f: unsafe fn(*const (), A) -> R,
+    context: [MaybeUninit<usize>; CONTEXT_WORDS],
+    _not_send_sync: PhantomData<*mut ()>,
+}
+
+struct ContextFor<C>(PhantomData<C>);
+
+impl<C> ContextFor<C> {
+    const FITS: () = assert!(
+        mem::size_of::<C>() <= mem::size_of::<[usize; CONTEXT_WORDS]>()
+            && mem::align_of::<C>() <= mem::align_of::<usize>(),
+        "an ErasedFn's context must fit in 2 words"
+    );
+}
+
+impl<A, R> ErasedFn<A, R> {
+    /**
+     * Package `f` with `context`, which is passed to `f` on each call.
+     *
+     * Fails to compile if `C` doesn't fit in two words.
+     */
+    pub const fn new<C: Copy + 'static>(context: C, f: fn(&C, A) -> R) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = ContextFor::<C>::FITS;
+
+        let mut words = [MaybeUninit::uninit(); CONTEXT_WORDS];
+
+        // SAFETY: C fits, per the assertion above, and the words are aligned for it
+        unsafe { ptr::write(&mut words as *mut _ as *mut C, context) };
+
+        Self {
+            // SAFETY: `&C` and `*const ()` are ABI-compatible, and we only ever pass a pointer to
+            // our C
+            f: unsafe { mem::transmute::<fn(&C, A) -> R, unsafe fn(*const (), A) -> R>(f) },
+            context: words,
+            _not_send_sync: PhantomData,
+        }
+    }
+
+    /**
+     * Call the function with the context and `a`.
+     */
+    pub fn call(&self, a: A) -> R {
+        // SAFETY: `f` takes a pointer to the C in our context
+        unsafe { (self.f)(self.context.as_ptr() as *const (), a) }
+    }
+
+    /**
+     * Get the context.
+     *
+     * # Safety
+     *
+     * Unsafe because we don't know that this is the same `C` that this `ErasedFn` was created with.
+     */
+    pub unsafe fn context<C: Copy>(&self) -> C {
+        ptr::read(self.context.as_ptr() as *const C)
+    }
+}
+
+impl<A, R> Clone for ErasedFn<A, R> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+/**
+ * The context is `Copy`, so can be copied without knowing its type.
+ */
+impl<A, R> Copy for ErasedFn<A, R> {}
+
+impl<A, R> fmt::Debug for ErasedFn<A, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ErasedFn").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use super::*;
+
+    #[derive(Clone, Copy)]
+    struct Scale {
+        factor: i32,
+        offset: i32,
+    }
+
+    #[test]
+    fn calls_with_context() {
+        let table: [ErasedFn<i32, i32>; 3] = [
+            ErasedFn::new(
+                Scale {
+                    factor: 2,
+                    offset: 1,
+                },
+                |s, x| s.factor * x + s.offset,
+            ),
+            ErasedFn::new(10i64, |n, x| *n as i32 - x),
+            ErasedFn::new((), |(), x| x),
+        ];
+
+        let results = table.iter().map(|f| f.call(3)).collect::<Vec<_>>();
+        assert_eq!(results, [7, 7, 3]);
+
+        assert_eq!(unsafe { table[1].context::<i64>() }, 10);
+        assert_eq!(
+            std::mem::size_of::<ErasedFn<i32, i32>>(),
+            3 * std::mem::size_of::<usize>()
+        );
+    }
+
+    #[test]
+    fn copies() {
+        static HITS: AtomicU32 = AtomicU32::new(0);
+
+        let on_hit = ErasedFn::new(&HITS, |hits, n: u32| {
+            hits.fetch_add(n, Ordering::Relaxed);
+        });
+
+        let copy = on_hit;
+        on_hit.call(2);
+        copy.call(3);
+        assert_eq!(HITS.load(Ordering::Relaxed), 5);
+    }
+}
@@ -0,0 +1,188 @@
+use std::{marker::PhantomData, ptr::NonNull};
+
+/**
+ * A type-erased `&'a T`.
+ *
+ * Unlike `&dyn Any`, `T` needn't be `'static`, but the caller must remember `T` to downcast.
+ */
+#[derive(Clone, Copy, Debug)]
+pub struct ErasedRef<'a> {
+    ptr: NonNull<()>,
+    _phantom: PhantomData<&'a ()>,
+}
+
+/**
+ * A type-erased `&'a mut T`.
+ *
+ * As `ErasedRef`, `T` needn't be `'static`, but the caller must remember `T` to downcast.
+ */
+#[derive(Debug)]
+pub struct ErasedMut<'a> {
+    ptr: NonNull<()>,
+    _phantom: PhantomData<&'a mut ()>,
+}
+
+impl<'a> ErasedRef<'a> {
+    /**
+     * Erase a `&'a T`.
+     */
+    pub fn new<T>(t: &'a T) -> Self {
+        Self {
+            ptr: NonNull::from(t).cast(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /**
+     * Get the erased pointer.
+     */
+    pub fn as_ptr(self) -> *const () {
+        self.ptr.as_ptr()
+    }
+
+    /**
+     * Recover the `&'a T`.
+     *
+     * # Safety
+     *
+     * Unsafe because we don't know that this is the same `T` that this `ErasedRef` was created
+     * with.
+     */
+    pub unsafe fn downcast<T>(self) -> &'a T {
+        self.ptr.cast().as_ref()
+    }
+}
+
+impl<'a> ErasedMut<'a> {
+    /**
+     * Erase a `&'a mut T`.
+     */
+    pub fn new<T>(t: &'a mut T) -> Self {
+        Self {
+            ptr: NonNull::from(t).cast(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /**
+     * Get the erased pointer.
+     */
+    pub fn as_mut_ptr(&mut self) -> *mut () {
+        self.ptr.as_ptr()
+    }
+
+    /**
+     * Borrow as a shorter-lived `ErasedRef`.
+     */
+    pub fn as_erased_ref(&self) -> ErasedRef<'_> {
+        ErasedRef {
+            ptr: self.ptr,
+            _phantom: PhantomData,
+        }
+    }
+
+    /**
+     * Reborrow as a shorter-lived `ErasedMut`, leaving `self` usable afterwards.
+     */
+    pub fn reborrow(&mut self) -> ErasedMut<'_> {
+        ErasedMut {
+            ptr: self.ptr,
+            _phantom: PhantomData,
+        }
+    }
+
+    /**
+     * Get a reference to the `T`, for as long as `self` is borrowed.
+     *
+     * # Safety
+     *
+     * Unsafe because we don't know that this is the same `T` that this `ErasedMut` was created
+     * with.
+     */
+    pub unsafe fn downcast_ref<T>(&self) -> &T {
+        self.ptr.cast().as_ref()
+    }
+
+    /**
+     * Get a mutable reference to the `T`, for as long as `self` is borrowed.
+     *
+     * # Safety
+     *
+     * Unsafe because we don't know that this is the same `T` that this `ErasedMut` was created
+     * with.
+     */
+    pub unsafe fn downcast_mut<T>(&mut self) -> &mut T {
+        self.ptr.cast().as_mut()
+    }
+
+    /**
+     * Recover the `&'a mut T`.
+     *
+     * # Safety
+     *
+     * Unsafe because we don't know that this is the same `T` that this `ErasedMut` was created
+     * with.
+     */
+    pub unsafe fn downcast<T>(self) -> &'a mut T {
+        self.ptr.cast().as_mut()
+    }
+}
+
+impl<'a, T> From<&'a T> for ErasedRef<'a> {
+    fn from(t: &'a T) -> Self {
+        Self::new(t)
+    }
+}
+
+impl<'a, T> From<&'a mut T> for ErasedMut<'a> {
+    fn from(t: &'a mut T) -> Self {
+        Self::new(t)
+    }
+}
+
+impl<'a> From<ErasedMut<'a>> for ErasedRef<'a> {
+    fn from(erased: ErasedMut<'a>) -> Self {
+        Self {
+            ptr: erased.ptr,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dispatch(kind: u8, arg: ErasedRef<'_>) -> String {
+        unsafe {
+            match kind {
+                0 => arg.downcast::<u32>().to_string(),
+                _ => arg.downcast::<&str>().to_string(),
+            }
+        }
+    }
+
+    #[test]
+    fn erases_non_static_borrows() {
+        let local = String::from("borrowed");
+        let s: &str = &local;
+
+        assert_eq!(dispatch(0, ErasedRef::new(&7u32)), "7");
+        assert_eq!(dispatch(1, (&s).into()), "borrowed");
+    }
+
+    #[test]
+    fn mutates() {
+        let mut v = vec![1];
+        let mut erased = ErasedMut::new(&mut v);
+
+        unsafe {
+            erased.reborrow().downcast::<Vec<i32>>().push(2);
+            erased.downcast_mut::<Vec<i32>>().push(3);
+            assert_eq!(erased.as_erased_ref().downcast::<Vec<i32>>().len(), 3);
+
+            let shared = ErasedRef::from(erased);
+            assert_eq!(shared.downcast::<Vec<i32>>(), &[1, 2, 3]);
+        }
+    }
+}
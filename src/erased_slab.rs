@@ -0,0 +1,251 @@
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use crate::ErasedSlot;
+
+/**
+ * A slab of values of differing types, each stored in an `ErasedSlot`, so inline if small, and
+ * addressed by generational keys.
+ *
+ * Inserting returns a `SlabKey<T>`, a stable handle to the value, which is `Copy`, so can be
+ * shared. Once the value is removed its slot is reused, but the key no longer matches, so stale
+ * handles are detected rather than reaching the slot's new value.
+ */
+#[derive(Default)]
+pub struct ErasedSlab {
+    entries: Vec<Entry>,
+    vacant: Vec<usize>,
+    len: usize,
+}
+
+#[derive(Default)]
+struct Entry {
+    slot: ErasedSlot,
+    generation: u64,
+}
+
+/**
+ * A handle to a `T` in an `ErasedSlab`.
+ *
+ * Generations are unique across all slabs, so a key matches only the insertion it was returned
+ * from, and can't reach a value of another type.
+ */
+pub struct SlabKey<T> {
+    index: usize,
+    generation: u64,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+/**
+ * Unique across all slabs, so keys can't be mixed up between them. Zero marks a vacant entry.
+ */
+static NEXT_GENERATION: AtomicU64 = AtomicU64::new(1);
+
+impl ErasedSlab {
+    /**
+     * Create an empty slab.
+     */
+    pub const fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            vacant: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /**
+     * The number of values.
+     */
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /**
+     * Whether there are no values.
+     */
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /**
+     * Insert `t`, reusing a vacant slot if there is one, and return its key.
+     *
+     * `T` must be `'static`, as the slab has no lifetime to stop it outliving anything `t`
+     * borrows.
+     */
+    pub fn insert<T: 'static>(&mut self, t: T) -> SlabKey<T> {
+        let generation = NEXT_GENERATION.fetch_add(1, Ordering::Relaxed);
+        let index = self.vacant.pop().unwrap_or_else(|| {
+            self.entries.push(Entry::default());
+            self.entries.len() - 1
+        });
+
+        let entry = &mut self.entries[index];
+        entry.slot.fill(t);
+        entry.generation = generation;
+        self.len += 1;
+
+        SlabKey {
+            index,
+            generation,
+            _phantom: PhantomData,
+        }
+    }
+
+    fn entry<T>(&self, key: SlabKey<T>) -> Option<&Entry> {
+        self.entries
+            .get(key.index)
+            .filter(|entry| entry.generation == key.generation)
+    }
+
+    /**
+     * Whether the value `key` was returned for is still in the slab.
+     */
+    pub fn contains<T>(&self, key: SlabKey<T>) -> bool {
+        self.entry(key).is_some()
+    }
+
+    /**
+     * Get a reference to the value `key` was returned for, if it's still in the slab.
+     */
+    pub fn get<T>(&self, key: SlabKey<T>) -> Option<&T> {
+        // SAFETY: the generation matches, so the slot was filled with a T, returning `key`
+        self.entry(key)
+            .and_then(|entry| unsafe { entry.slot.get() })
+    }
+
+    /**
+     * Get a mutable reference to the value `key` was returned for, if it's still in the slab.
+     */
+    pub fn get_mut<T>(&mut self, key: SlabKey<T>) -> Option<&mut T> {
+        self.entry(key)?;
+
+        // SAFETY: as get()
+        unsafe { self.entries[key.index].slot.get_mut() }
+    }
+
+    /**
+     * Remove the value `key` was returned for, if it's still in the slab, and return it.
+     */
+    pub fn remove<T>(&mut self, key: SlabKey<T>) -> Option<T> {
+        self.entry(key)?;
+
+        let entry = &mut self.entries[key.index];
+        entry.generation = 0;
+        self.vacant.push(key.index);
+        self.len -= 1;
+
+        // SAFETY: as get()
+        unsafe { entry.slot.take() }
+    }
+
+    /**
+     * Remove and drop all values. Their keys no longer match.
+     */
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.vacant.clear();
+        self.len = 0;
+    }
+}
+
+impl fmt::Debug for ErasedSlab {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ErasedSlab")
+            .field("len", &self.len)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> Clone for SlabKey<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for SlabKey<T> {}
+
+impl<T> PartialEq for SlabKey<T> {
+    fn eq(&self, other: &Self) -> bool {
+        (self.index, self.generation) == (other.index, other.generation)
+    }
+}
+
+impl<T> Eq for SlabKey<T> {}
+
+impl<T> Hash for SlabKey<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (self.index, self.generation).hash(state)
+    }
+}
+
+impl<T> fmt::Debug for SlabKey<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SlabKey")
+            .field("index", &self.index)
+            .field("generation", &self.generation)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Sprite {
+        x: i32,
+        y: i32,
+    }
+
+    #[test]
+    fn holds_mixed_types() {
+        let mut slab = ErasedSlab::new();
+
+        let sprite = slab.insert(Sprite { x: 1, y: 2 });
+        let name = slab.insert(String::from("player"));
+        let large = slab.insert([7u64; 10]);
+
+        slab.get_mut(sprite).unwrap().x += 10;
+        assert_eq!(slab.get(sprite), Some(&Sprite { x: 11, y: 2 }));
+        assert_eq!(slab.get(name).map(String::as_str), Some("player"));
+        assert_eq!(slab.get(large).map(|a| a.iter().sum::<u64>()), Some(70));
+
+        assert_eq!(slab.len(), 3);
+        assert_eq!(slab.remove(name).as_deref(), Some("player"));
+        assert_eq!(slab.len(), 2);
+    }
+
+    #[test]
+    fn detects_stale_keys() {
+        let rc = Rc::new(());
+        let mut slab = ErasedSlab::new();
+        let mut other = ErasedSlab::new();
+
+        let stale = slab.insert(rc.clone());
+        let copy = stale;
+        assert_eq!(slab.remove(stale), Some(rc.clone()));
+
+        let reused = slab.insert(5u32);
+        assert_eq!(reused.index, stale.index);
+        assert!(!slab.contains(copy));
+        assert!(slab.get(copy).is_none());
+        assert!(slab.remove(copy).is_none());
+
+        let foreign = other.insert(6u32);
+        assert_eq!(foreign.index, reused.index);
+        assert!(slab.get(foreign).is_none());
+
+        other.insert(rc.clone());
+        assert_eq!(Rc::strong_count(&rc), 2);
+        other.clear();
+        assert_eq!(Rc::strong_count(&rc), 1);
+        assert!(other.get(foreign).is_none());
+    }
+}
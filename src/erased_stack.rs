@@ -0,0 +1,244 @@
+use std::{
+    fmt,
+    marker::PhantomData,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use crate::OwnedErased;
+
+/**
+ * A stack of values of differing types, each stored as an `OwnedErased`, so contiguously if
+ * inline-sized, otherwise boxed.
+ *
+ * Pushing returns a `StackToken<T>`, which is the only way to pop the value, so the value's type
+ * needn't be tracked alongside the stack. For example, a bytecode VM can keep operands of mixed
+ * types without boxing each one.
+ */
+#[derive(Default)]
+pub struct ErasedStack {
+    entries: Vec<Entry>,
+}
+
+struct Entry {
+    value: OwnedErased,
+    serial: u64,
+}
+
+/**
+ * A handle to a `T` pushed onto an `ErasedStack`, which pops it.
+ *
+ * A token matches only the push it was returned from, on any stack, so can't retrieve a different
+ * entry, even once its own has been dropped, for example by `ErasedStack::truncate`.
+ */
+#[must_use = "the token is the only way to pop the value"]
+pub struct StackToken<T> {
+    index: usize,
+    serial: u64,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+/**
+ * Unique across all stacks, so tokens can't be mixed up between them.
+ */
+static NEXT_SERIAL: AtomicU64 = AtomicU64::new(0);
+
+impl ErasedStack {
+    /**
+     * Create an empty stack.
+     */
+    pub const fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /**
+     * Create an empty stack, with space for at least `capacity` values before reallocating.
+     */
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: Vec::with_capacity(capacity),
+        }
+    }
+
+    /**
+     * The number of values on the stack.
+     */
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /**
+     * Whether the stack is empty.
+     */
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /**
+     * Push `t`, returning the token which pops it.
+     *
+     * `T` must be `'static`, as the stack has no lifetime to stop it outliving anything `t`
+     * borrows.
+     */
+    pub fn push<T: 'static>(&mut self, t: T) -> StackToken<T> {
+        let serial = NEXT_SERIAL.fetch_add(1, Ordering::Relaxed);
+        let index = self.entries.len();
+
+        self.entries.push(Entry {
+            value: OwnedErased::new(t),
+            serial,
+        });
+
+        StackToken {
+            index,
+            serial,
+            _phantom: PhantomData,
+        }
+    }
+
+    /**
+     * Pop the value `token` was returned for.
+     *
+     * # Panics
+     *
+     * Panics if the value isn't at the top of the stack, for example if values pushed after it
+     * are still there, or it's been dropped.
+     */
+    #[track_caller]
+    pub fn pop<T>(&mut self, token: StackToken<T>) -> T {
+        assert!(
+            token.index + 1 == self.entries.len() && self.matches(&token),
+            "popped a token for a value which isn't at the top of the stack"
+        );
+
+        let entry = self.entries.pop().unwrap();
+
+        // SAFETY: the token was returned from pushing this entry, as a T
+        unsafe { entry.value.into_inner() }
+    }
+
+    /**
+     * Get a reference to the value `token` was returned for, if it's still on the stack.
+     */
+    pub fn get<T>(&self, token: &StackToken<T>) -> Option<&T> {
+        if self.matches(token) {
+            // SAFETY: the token was returned from pushing this entry, as a T
+            Some(unsafe { self.entries[token.index].value.as_ref() })
+        } else {
+            None
+        }
+    }
+
+    /**
+     * Get a mutable reference to the value `token` was returned for, if it's still on the stack.
+     */
+    pub fn get_mut<T>(&mut self, token: &StackToken<T>) -> Option<&mut T> {
+        if self.matches(token) {
+            // SAFETY: the token was returned from pushing this entry, as a T
+            Some(unsafe { self.entries[token.index].value.as_mut_ref() })
+        } else {
+            None
+        }
+    }
+
+    fn matches<T>(&self, token: &StackToken<T>) -> bool {
+        self.entries
+            .get(token.index)
+            .is_some_and(|entry| entry.serial == token.serial)
+    }
+
+    /**
+     * Drop values from the top of the stack, until `len` remain. Their tokens no longer match.
+     */
+    pub fn truncate(&mut self, len: usize) {
+        self.entries.truncate(len);
+    }
+
+    /**
+     * Drop all values. Their tokens no longer match.
+     */
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+impl fmt::Debug for ErasedStack {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ErasedStack")
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> fmt::Debug for StackToken<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StackToken")
+            .field("index", &self.index)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use super::*;
+
+    #[test]
+    fn pops_mixed_types() {
+        let mut stack = ErasedStack::new();
+
+        let a = stack.push(2i64);
+        let b = stack.push(String::from("operand"));
+        let c = stack.push([1.5f64; 8]);
+
+        assert_eq!(stack.len(), 3);
+        *stack.get_mut(&b).unwrap() += "s";
+
+        assert_eq!(stack.pop(c), [1.5; 8]);
+        assert_eq!(stack.pop(b), "operands");
+        assert_eq!(stack.pop(a), 2);
+        assert!(stack.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "isn't at the top of the stack")]
+    fn rejects_out_of_order_pops() {
+        let mut stack = ErasedStack::new();
+
+        let a = stack.push(1u8);
+        let _b = stack.push(2u8);
+        stack.pop(a);
+    }
+
+    #[test]
+    fn rejects_stale_and_foreign_tokens() {
+        let drops = Rc::new(Cell::new(0));
+
+        struct Counted(Rc<Cell<u32>>);
+
+        impl Drop for Counted {
+            fn drop(&mut self) {
+                self.0.set(self.0.get() + 1);
+            }
+        }
+
+        let mut stack = ErasedStack::with_capacity(2);
+        let mut other = ErasedStack::new();
+
+        let stale = stack.push(Counted(drops.clone()));
+        stack.clear();
+        assert_eq!(drops.get(), 1);
+
+        let _reused = stack.push(0u32);
+        let foreign = other.push(Counted(drops.clone()));
+
+        assert!(stack.get(&stale).is_none());
+        assert!(stack.get(&foreign).is_none());
+        assert!(other.get(&foreign).is_some());
+
+        drop(other);
+        assert_eq!(drops.get(), 2);
+    }
+}
@@ -0,0 +1,519 @@
+#[cfg(feature = "checked")]
+use std::any;
+use std::{
+    alloc::{self, Layout},
+    any::TypeId,
+    cmp, fmt,
+    marker::PhantomData,
+    mem,
+    ptr::{self, NonNull},
+    slice,
+};
+
+#[cfg(feature = "checked")]
+use crate::erased::PayloadType;
+
+/**
+ * A vector of elements of a single type, which it doesn't know, only their `Layout` and how to
+ * drop them, as given on creation. For example, a column of components in an ECS.
+ *
+ * Elements are pushed and accessed through unsafe typed methods, or as raw bytes, for example to
+ * move an element between vectors of the same layout. If created from a known `T`, with `new`,
+ * the elements can also be viewed safely, as a `&[T]`.
+ *
+ * Like `Erased`, an `ErasedVec` is neither `Send` nor `Sync`.
+ */
+pub struct ErasedVec {
+    ptr: NonNull<u8>,
+    len: usize,
+    cap: usize,
+    item_layout: Layout,
+    drop: Option<unsafe fn(*mut u8)>,
+    // the elements' type, if created from a known `T`
+    type_id: Option<TypeId>,
+    // likewise, checked on typed access
+    #[cfg(feature = "checked")]
+    item_type: Option<PayloadType>,
+    _not_send_sync: PhantomData<*mut ()>,
+}
+
+/**
+ * Drop the `T` at `ptr`, as an `ErasedVec`'s drop glue.
+ */
+unsafe fn drop_item<T>(ptr: *mut u8) {
+    ptr::drop_in_place(ptr as *mut T)
+}
+
+impl ErasedVec {
+    /**
+     * Create an empty `ErasedVec` of `T`s.
+     */
+    pub fn new<T: 'static>() -> Self {
+        let drop = if mem::needs_drop::<T>() {
+            Some(drop_item::<T> as unsafe fn(*mut u8))
+        } else {
+            None
+        };
+
+        // SAFETY: the drop glue is for a T
+        let mut v = unsafe { Self::with_layout(Layout::new::<T>(), drop) };
+        v.type_id = Some(TypeId::of::<T>());
+        #[cfg(feature = "checked")]
+        {
+            v.item_type = Some(PayloadType::of::<T>());
+        }
+        v
+    }
+
+    /**
+     * Create an empty `ErasedVec` of `T`s, with space for at least `capacity` of them.
+     */
+    pub fn with_capacity<T: 'static>(capacity: usize) -> Self {
+        let mut v = Self::new::<T>();
+        v.reserve(capacity);
+        v
+    }
+
+    /**
+     * Create an empty `ErasedVec` of elements with the given layout, and dropped by `drop`, if
+     * they need dropping.
+     *
+     * # Safety
+     *
+     * `drop`, if given, must drop an element, given a pointer to it.
+     */
+    pub unsafe fn with_layout(item_layout: Layout, drop: Option<unsafe fn(*mut u8)>) -> Self {
+        let item_layout = item_layout.pad_to_align();
+
+        Self {
+            ptr: dangling(item_layout),
+            len: 0,
+            cap: if item_layout.size() == 0 {
+                usize::MAX
+            } else {
+                0
+            },
+            item_layout,
+            drop,
+            type_id: None,
+            #[cfg(feature = "checked")]
+            item_type: None,
+            _not_send_sync: PhantomData,
+        }
+    }
+
+    /**
+     * The layout of each element, padded to its alignment.
+     */
+    pub fn item_layout(&self) -> Layout {
+        self.item_layout
+    }
+
+    /**
+     * The number of elements.
+     */
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /**
+     * Whether there are no elements.
+     */
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /**
+     * The number of elements which can be held without reallocating.
+     */
+    pub fn capacity(&self) -> usize {
+        self.cap
+    }
+
+    /**
+     * Whether the elements are `T`s, if the `ErasedVec` was created from a known `T`.
+     */
+    pub fn is<T: 'static>(&self) -> bool {
+        self.type_id == Some(TypeId::of::<T>())
+    }
+
+    /**
+     * Make space for at least `additional` more elements.
+     */
+    pub fn reserve(&mut self, additional: usize) {
+        let required = self.len.checked_add(additional).expect("capacity overflow");
+        if required <= self.cap {
+            return;
+        }
+
+        let cap = cmp::max(required, cmp::max(self.cap * 2, 4));
+        let layout = self.array_layout(cap);
+
+        // SAFETY: the element size is non-zero, as otherwise our capacity is unlimited. The old
+        // allocation, if any, was made with the old capacity's layout
+        let ptr = unsafe {
+            if self.cap == 0 {
+                alloc::alloc(layout)
+            } else {
+                alloc::realloc(
+                    self.ptr.as_ptr(),
+                    self.array_layout(self.cap),
+                    layout.size(),
+                )
+            }
+        };
+
+        self.ptr = NonNull::new(ptr).unwrap_or_else(|| alloc::handle_alloc_error(layout));
+        self.cap = cap;
+    }
+
+    fn array_layout(&self, n: usize) -> Layout {
+        let size = self
+            .item_layout
+            .size()
+            .checked_mul(n)
+            .expect("capacity overflow");
+
+        Layout::from_size_align(size, self.item_layout.align()).expect("capacity overflow")
+    }
+
+    /**
+     * Move `t` to the end of the vector.
+     *
+     * # Safety
+     *
+     * Our elements must be `T`s.
+     */
+    pub unsafe fn push<T>(&mut self, t: T) {
+        self.check::<T>();
+
+        let t = mem::ManuallyDrop::new(t);
+        self.push_raw(&*t as *const T as *const u8);
+    }
+
+    /**
+     * Move the element at `src` to the end of the vector, by copying its bytes.
+     *
+     * # Safety
+     *
+     * `src` must point to an element of our type, which mustn't be used or dropped afterwards.
+     */
+    pub unsafe fn push_raw(&mut self, src: *const u8) {
+        self.reserve(1);
+
+        ptr::copy_nonoverlapping(src, self.slot(self.len), self.item_layout.size());
+        self.len += 1;
+    }
+
+    fn slot(&self, i: usize) -> *mut u8 {
+        // SAFETY: callers keep `i` within the allocation, or our elements are zero-sized
+        unsafe { self.ptr.as_ptr().add(i * self.item_layout.size()) }
+    }
+
+    /**
+     * Get a pointer to the element at `i`, if in bounds.
+     */
+    pub fn get_ptr(&self, i: usize) -> Option<NonNull<u8>> {
+        if i < self.len {
+            NonNull::new(self.slot(i))
+        } else {
+            None
+        }
+    }
+
+    /**
+     * Get a reference to the element at `i`, if in bounds.
+     *
+     * # Safety
+     *
+     * Our elements must be `T`s.
+     */
+    pub unsafe fn get<T>(&self, i: usize) -> Option<&T> {
+        self.check::<T>();
+
+        self.get_ptr(i).map(|p| p.cast().as_ref())
+    }
+
+    /**
+     * Get a mutable reference to the element at `i`, if in bounds.
+     *
+     * # Safety
+     *
+     * Our elements must be `T`s.
+     */
+    pub unsafe fn get_mut<T>(&mut self, i: usize) -> Option<&mut T> {
+        self.check::<T>();
+
+        self.get_ptr(i).map(|p| p.cast().as_mut())
+    }
+
+    /**
+     * Move the last element out, if any.
+     *
+     * # Safety
+     *
+     * Our elements must be `T`s.
+     */
+    pub unsafe fn pop<T>(&mut self) -> Option<T> {
+        self.check::<T>();
+
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        Some(ptr::read(self.slot(self.len) as *const T))
+    }
+
+    /**
+     * Remove the element at `i`, replacing it with the last, and move it out.
+     *
+     * # Safety
+     *
+     * Our elements must be `T`s.
+     *
+     * # Panics
+     *
+     * Panics if `i` is out of bounds.
+     */
+    pub unsafe fn swap_remove<T>(&mut self, i: usize) -> T {
+        self.check::<T>();
+
+        let mut t = mem::MaybeUninit::<T>::uninit();
+        self.swap_remove_raw(i, t.as_mut_ptr() as *mut u8);
+        t.assume_init()
+    }
+
+    /**
+     * Remove the element at `i`, replacing it with the last, and move it to `dst`, by copying its
+     * bytes. The caller then owns the element.
+     *
+     * # Safety
+     *
+     * `dst` must be valid for writes of an element.
+     *
+     * # Panics
+     *
+     * Panics if `i` is out of bounds.
+     */
+    pub unsafe fn swap_remove_raw(&mut self, i: usize, dst: *mut u8) {
+        assert!(i < self.len, "index {} out of bounds of {}", i, self.len);
+
+        let size = self.item_layout.size();
+        self.len -= 1;
+        ptr::copy_nonoverlapping(self.slot(i), dst, size);
+        ptr::copy(self.slot(self.len), self.slot(i), size);
+    }
+
+    /**
+     * Remove the element at `i`, replacing it with the last, and drop it.
+     *
+     * # Panics
+     *
+     * Panics if `i` is out of bounds.
+     */
+    pub fn swap_remove_drop(&mut self, i: usize) {
+        assert!(i < self.len, "index {} out of bounds of {}", i, self.len);
+
+        let last = self.len - 1;
+        let size = self.item_layout.size();
+
+        // SAFETY: both in bounds. The removed element is forgotten before it's dropped, so a
+        // panicking drop can't drop it again
+        unsafe {
+            if i != last {
+                ptr::swap_nonoverlapping(self.slot(i), self.slot(last), size);
+            }
+            self.len = last;
+            if let Some(drop) = self.drop {
+                drop(self.slot(last));
+            }
+        }
+    }
+
+    /**
+     * Drop the elements from `len` onwards.
+     */
+    pub fn truncate(&mut self, len: usize) {
+        while self.len > len {
+            self.len -= 1;
+
+            if let Some(drop) = self.drop {
+                // SAFETY: in bounds, and no longer counted, so not dropped again
+                unsafe { drop(self.slot(self.len)) };
+            }
+        }
+    }
+
+    /**
+     * Drop all elements.
+     */
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    /**
+     * View the elements as a slice, if they're `T`s.
+     */
+    pub fn as_slice<T: 'static>(&self) -> Option<&[T]> {
+        if self.is::<T>() {
+            // SAFETY: we hold `len` initialised, contiguous, Ts
+            Some(unsafe { slice::from_raw_parts(self.ptr.as_ptr() as *const T, self.len) })
+        } else {
+            None
+        }
+    }
+
+    /**
+     * View the elements as a mutable slice, if they're `T`s.
+     */
+    pub fn as_mut_slice<T: 'static>(&mut self) -> Option<&mut [T]> {
+        if self.is::<T>() {
+            // SAFETY: we hold `len` initialised, contiguous, Ts
+            Some(unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr() as *mut T, self.len) })
+        } else {
+            None
+        }
+    }
+
+    /**
+     * Panic if we're known to hold something other than `T`s.
+     */
+    #[cfg(feature = "checked")]
+    #[track_caller]
+    fn check<T>(&self) {
+        if let Some(item_type) = self.item_type {
+            assert!(
+                item_type.is::<T>(),
+                "ErasedVec holds `{}`s, not `{}`s",
+                item_type.name(),
+                any::type_name::<T>()
+            );
+        }
+        debug_assert_eq!(Layout::new::<T>().pad_to_align(), self.item_layout);
+    }
+
+    #[cfg(not(feature = "checked"))]
+    #[inline(always)]
+    fn check<T>(&self) {
+        debug_assert_eq!(Layout::new::<T>().pad_to_align(), self.item_layout);
+    }
+}
+
+/**
+ * A well-aligned, non-null pointer, for an empty allocation of the given layout.
+ */
+fn dangling(layout: Layout) -> NonNull<u8> {
+    NonNull::new(ptr::without_provenance_mut(layout.align())).expect("alignment is non-zero")
+}
+
+impl Drop for ErasedVec {
+    fn drop(&mut self) {
+        self.clear();
+
+        if self.item_layout.size() != 0 && self.cap != 0 {
+            // SAFETY: allocated with this layout
+            unsafe { alloc::dealloc(self.ptr.as_ptr(), self.array_layout(self.cap)) };
+        }
+    }
+}
+
+impl fmt::Debug for ErasedVec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ErasedVec")
+            .field("len", &self.len)
+            .field("item_layout", &self.item_layout)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Position {
+        x: f32,
+        y: f32,
+    }
+
+    struct Counted(Rc<Cell<u32>>);
+
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    #[test]
+    fn pushes_and_views() {
+        let mut v = ErasedVec::new::<Position>();
+
+        for i in 0..10 {
+            unsafe {
+                v.push(Position {
+                    x: i as f32,
+                    y: 0.0,
+                })
+            };
+        }
+
+        assert_eq!(v.len(), 10);
+        assert!(v.capacity() >= 10);
+        assert_eq!(unsafe { v.get::<Position>(3) }.unwrap().x, 3.0);
+        assert!(unsafe { v.get::<Position>(10) }.is_none());
+
+        for p in v.as_mut_slice::<Position>().unwrap() {
+            p.y = p.x * 2.0;
+        }
+        assert_eq!(
+            v.as_slice::<Position>().unwrap()[4],
+            Position { x: 4.0, y: 8.0 }
+        );
+        assert!(v.as_slice::<[f32; 2]>().is_none());
+
+        assert_eq!(unsafe { v.swap_remove::<Position>(0) }.x, 0.0);
+        assert_eq!(unsafe { v.get::<Position>(0) }.unwrap().x, 9.0);
+        assert_eq!(unsafe { v.pop::<Position>() }.unwrap().x, 8.0);
+        assert_eq!(v.len(), 8);
+    }
+
+    #[test]
+    fn moves_between_columns() {
+        let drops = Rc::new(Cell::new(0));
+        let mut a = ErasedVec::new::<Counted>();
+        let mut b = unsafe { ErasedVec::with_layout(a.item_layout(), Some(drop_item::<Counted>)) };
+
+        unsafe {
+            a.push(Counted(drops.clone()));
+            a.push(Counted(drops.clone()));
+            a.push(Counted(drops.clone()));
+
+            let mut moved = mem::MaybeUninit::<Counted>::uninit();
+            a.swap_remove_raw(0, moved.as_mut_ptr() as *mut u8);
+            b.push_raw(moved.as_ptr() as *const u8);
+        }
+        assert_eq!((a.len(), b.len()), (2, 1));
+        assert!(b.as_slice::<Counted>().is_none());
+        assert_eq!(drops.get(), 0);
+
+        a.swap_remove_drop(1);
+        assert_eq!(drops.get(), 1);
+        drop(a);
+        drop(b);
+        assert_eq!(drops.get(), 3);
+    }
+
+    #[test]
+    fn holds_zero_sized_items() {
+        let mut v = ErasedVec::new::<()>();
+
+        for _ in 0..3 {
+            unsafe { v.push(()) };
+        }
+        assert_eq!(v.as_slice::<()>().unwrap().len(), 3);
+        v.truncate(1);
+        assert_eq!(v.len(), 1);
+    }
+}
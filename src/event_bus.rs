@@ -0,0 +1,245 @@
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    fmt,
+    marker::PhantomData,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use crate::AnyTrident;
+
+/**
+ * A publish/subscribe bus, calling the handlers subscribed to an event's type when it's published.
+ *
+ * Handlers are stored as `AnyTrident`s, so a handler whose captures fit in 2 words, such as one
+ * holding an `Rc` and a counter, is inline in the bus rather than boxed.
+ */
+#[derive(Default)]
+pub struct EventBus {
+    handlers: HashMap<TypeId, Vec<Handler>>,
+}
+
+struct Handler {
+    serial: u64,
+    // the `F` subscribed
+    f: AnyTrident,
+    // calls the `F` with an event it was subscribed to
+    call: fn(&mut AnyTrident, &dyn Any),
+}
+
+/**
+ * A handle to a handler subscribed to `E` events, which unsubscribes it.
+ *
+ * A subscription matches only the handler it was returned for, on any bus.
+ */
+#[must_use = "the subscription is the only way to unsubscribe the handler"]
+pub struct Subscription<E> {
+    serial: u64,
+    _phantom: PhantomData<fn(&E)>,
+}
+
+/**
+ * Unique across all buses, so subscriptions can't be mixed up between them.
+ */
+static NEXT_SERIAL: AtomicU64 = AtomicU64::new(0);
+
+/**
+ * Call the `F` in `f` with `event`, which is an `E`.
+ */
+fn call_handler<E: Any, F: FnMut(&E) + 'static>(f: &mut AnyTrident, event: &dyn Any) {
+    let f = f.downcast_mut::<F>().expect("subscribed with F");
+    f(event.downcast_ref().expect("keyed by type"))
+}
+
+impl EventBus {
+    /**
+     * Create a bus with no subscribers.
+     */
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /**
+     * Subscribe `f` to `E` events, returning a `Subscription` to unsubscribe it.
+     */
+    pub fn subscribe<E, F>(&mut self, f: F) -> Subscription<E>
+    where
+        E: Any,
+        F: FnMut(&E) + 'static,
+    {
+        let serial = NEXT_SERIAL.fetch_add(1, Ordering::Relaxed);
+
+        self.handlers
+            .entry(TypeId::of::<E>())
+            .or_default()
+            .push(Handler {
+                serial,
+                f: AnyTrident::new(f),
+                call: call_handler::<E, F>,
+            });
+
+        Subscription {
+            serial,
+            _phantom: PhantomData,
+        }
+    }
+
+    /**
+     * Unsubscribe a handler, returning whether it was subscribed to this bus.
+     */
+    pub fn unsubscribe<E: Any>(&mut self, subscription: Subscription<E>) -> bool {
+        let id = TypeId::of::<E>();
+        let Some(handlers) = self.handlers.get_mut(&id) else {
+            return false;
+        };
+        let Some(index) = handlers
+            .iter()
+            .position(|handler| handler.serial == subscription.serial)
+        else {
+            return false;
+        };
+
+        handlers.remove(index);
+        if handlers.is_empty() {
+            self.handlers.remove(&id);
+        }
+        true
+    }
+
+    /**
+     * Call each handler subscribed to `E` events with `event`, in the order they subscribed,
+     * returning how many were called.
+     */
+    pub fn publish<E: Any>(&mut self, event: &E) -> usize {
+        let Some(handlers) = self.handlers.get_mut(&TypeId::of::<E>()) else {
+            return 0;
+        };
+
+        for handler in handlers.iter_mut() {
+            (handler.call)(&mut handler.f, event);
+        }
+        handlers.len()
+    }
+
+    /**
+     * The number of handlers subscribed to `E` events.
+     */
+    pub fn subscribers<E: Any>(&self) -> usize {
+        self.handlers.get(&TypeId::of::<E>()).map_or(0, Vec::len)
+    }
+
+    /**
+     * Whether there are no handlers.
+     */
+    pub fn is_empty(&self) -> bool {
+        self.handlers.is_empty()
+    }
+
+    /**
+     * Unsubscribe all handlers.
+     */
+    pub fn clear(&mut self) {
+        self.handlers.clear();
+    }
+}
+
+impl fmt::Debug for EventBus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventBus")
+            .field("events", &self.handlers.len())
+            .field(
+                "handlers",
+                &self.handlers.values().map(Vec::len).sum::<usize>(),
+            )
+            .finish()
+    }
+}
+
+impl<E> PartialEq for Subscription<E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.serial == other.serial
+    }
+}
+
+impl<E> Eq for Subscription<E> {}
+
+impl<E> fmt::Debug for Subscription<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Subscription")
+            .field("serial", &self.serial)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::*;
+
+    struct Resized {
+        width: u32,
+    }
+
+    struct Closed;
+
+    #[test]
+    fn publishes_to_subscribers() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let mut bus = EventBus::new();
+
+        let l = log.clone();
+        let first = bus.subscribe(move |e: &Resized| l.borrow_mut().push(e.width));
+        let l = log.clone();
+        let mut calls = 0;
+        let _second = bus.subscribe(move |e: &Resized| {
+            calls += 1;
+            l.borrow_mut().push(e.width * 10 + calls);
+        });
+        let l = log.clone();
+        let _closed = bus.subscribe(move |_: &Closed| l.borrow_mut().push(0));
+
+        assert_eq!(bus.publish(&Resized { width: 2 }), 2);
+        assert_eq!(bus.publish(&Resized { width: 3 }), 2);
+        assert_eq!(bus.publish(&5u8), 0);
+        assert_eq!(*log.borrow(), [2, 21, 3, 32]);
+
+        assert!(bus.unsubscribe(first));
+        assert_eq!(bus.subscribers::<Resized>(), 1);
+        assert_eq!(bus.publish(&Closed), 1);
+        assert_eq!(bus.publish(&Resized { width: 4 }), 1);
+        assert_eq!(*log.borrow(), [2, 21, 3, 32, 0, 43]);
+    }
+
+    #[test]
+    fn rejects_other_subscriptions() {
+        let mut a = EventBus::new();
+        let mut b = EventBus::new();
+
+        let sub = a.subscribe(|_: &Closed| {});
+        let other = b.subscribe(|_: &Closed| {});
+        assert_ne!(sub, other);
+
+        assert!(!a.unsubscribe(other));
+        assert!(a.unsubscribe(sub));
+        assert!(a.is_empty());
+        assert_eq!(a.publish(&Closed), 0);
+    }
+
+    #[test]
+    fn drops_handlers() {
+        let rc = Rc::new(());
+        let mut bus = EventBus::new();
+
+        let captured = rc.clone();
+        let sub = bus.subscribe(move |_: &Closed| drop(captured.clone()));
+        let captured = rc.clone();
+        let _kept = bus.subscribe(move |_: &Resized| drop(captured.clone()));
+        assert_eq!(Rc::strong_count(&rc), 3);
+
+        bus.unsubscribe(sub);
+        assert_eq!(Rc::strong_count(&rc), 2);
+        bus.clear();
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+}
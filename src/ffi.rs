@@ -0,0 +1,166 @@
+use std::ffi::c_void;
+
+use crate::{OwnedErased, Trident};
+
+impl<T> Trident<T> {
+    /**
+     * Convert to a context pointer for a C callback, which owns the `T`.
+     *
+     * A boxed `T`'s allocation is handed over as is, an inline `T` is first moved to the heap.
+     * Reclaim the `T` with `from_c_context`, or borrow it in the callback with `c_context_mut`.
+     */
+    pub fn into_c_context(self) -> *mut c_void {
+        Box::into_raw(self.into_box()) as *mut c_void
+    }
+
+    /**
+     * Reclaim a `Trident<T>` from a context pointer returned by `into_c_context`.
+     *
+     * # Safety
+     *
+     * `ctx` must have been returned by `Trident::<T>::into_c_context`, for the same `T`, and must
+     * be passed here at most once.
+     */
+    pub unsafe fn from_c_context(ctx: *mut c_void) -> Self {
+        Box::from_raw(ctx as *mut T).into()
+    }
+
+    /**
+     * Borrow the `T` owned by a context pointer returned by `into_c_context`.
+     *
+     * # Safety
+     *
+     * `ctx` must have been returned by `Trident::<T>::into_c_context`, for the same `T`, and not
+     * yet reclaimed. The `T` mustn't be otherwise borrowed for `'a`.
+     */
+    pub unsafe fn c_context_mut<'a>(ctx: *mut c_void) -> &'a mut T {
+        &mut *(ctx as *mut T)
+    }
+}
+
+impl OwnedErased {
+    /**
+     * Convert to a context pointer for a C callback, which owns the payload.
+     *
+     * Reclaim it with `from_c_context`, which, when dropped, drops the payload.
+     */
+    pub fn into_c_context(self) -> *mut c_void {
+        Box::into_raw(Box::new(self)) as *mut c_void
+    }
+
+    /**
+     * Reclaim an `OwnedErased` from a context pointer returned by `into_c_context`.
+     *
+     * # Safety
+     *
+     * `ctx` must have been returned by `OwnedErased::into_c_context`, and must be passed here at
+     * most once.
+     */
+    pub unsafe fn from_c_context(ctx: *mut c_void) -> Self {
+        *Box::from_raw(ctx as *mut Self)
+    }
+}
+
+/**
+ * Define an `extern "C"` trampoline, which calls the closure owned by its context pointer.
+ *
+ * `c_trampoline!(on_event: fn(code: i32) -> bool)` defines `on_event::<F>`, for any
+ * `F: FnMut(i32) -> bool`. It takes the context from `Trident::<F>::into_c_context` as its first
+ * parameter, followed by those given, so can be registered with C alongside that context.
+ *
+ * With `once`, the trampoline instead reclaims the context and calls an `FnOnce`, so must be
+ * called exactly once: `c_trampoline!(once on_done: fn(status: i32))`.
+ *
+ * A panic in the closure can't unwind into C, so aborts.
+ */
+#[macro_export]
+macro_rules! c_trampoline {
+    ($name:ident: fn($($arg:ident: $ty:ty),* $(,)?) $(-> $ret:ty)?) => {
+        unsafe extern "C" fn $name<F>(
+            ctx: *mut ::std::ffi::c_void,
+            $($arg: $ty),*
+        ) $(-> $ret)?
+        where
+            F: FnMut($($ty),*) $(-> $ret)?,
+        {
+            let f = $crate::Trident::<F>::c_context_mut(ctx);
+            f($($arg),*)
+        }
+    };
+    (once $name:ident: fn($($arg:ident: $ty:ty),* $(,)?) $(-> $ret:ty)?) => {
+        unsafe extern "C" fn $name<F>(
+            ctx: *mut ::std::ffi::c_void,
+            $($arg: $ty),*
+        ) $(-> $ret)?
+        where
+            F: FnOnce($($ty),*) $(-> $ret)?,
+        {
+            let f = $crate::Trident::<F>::from_c_context(ctx).into_inner();
+            f($($arg),*)
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::c_void;
+
+    use crate::{OwnedErased, Trident};
+
+    // stand-ins for a C library's callback registration
+    type Callback = unsafe extern "C" fn(*mut c_void, i32) -> i32;
+
+    fn c_for_each(ctx: *mut c_void, cb: Callback) -> i32 {
+        (1..=3).map(|i| unsafe { cb(ctx, i) }).sum()
+    }
+
+    fn c_finish(ctx: *mut c_void, cb: unsafe extern "C" fn(*mut c_void, u8)) {
+        unsafe { cb(ctx, 7) }
+    }
+
+    c_trampoline!(each: fn(i: i32) -> i32);
+    c_trampoline!(once done: fn(status: u8));
+
+    fn register_each<F: FnMut(i32) -> i32>(f: F) -> [i32; 2] {
+        let ctx = Trident::new(f).into_c_context();
+        let sums = [c_for_each(ctx, each::<F>), c_for_each(ctx, each::<F>)];
+
+        drop(unsafe { Trident::<F>::from_c_context(ctx) });
+        sums
+    }
+
+    fn register_done<F: FnOnce(u8)>(f: F) {
+        c_finish(Trident::new(f).into_c_context(), done::<F>);
+    }
+
+    #[test]
+    fn calls_closure_through_context() {
+        let mut calls = Vec::new();
+
+        let sums = register_each(|i| {
+            calls.push(i);
+            i * 10
+        });
+
+        assert_eq!(sums, [60, 60]);
+        assert_eq!(calls, [1, 2, 3, 1, 2, 3]);
+    }
+
+    #[test]
+    fn calls_once() {
+        let mut status = None;
+        let large = [1u64; 4];
+
+        register_done(|s| status = Some(s + large[0] as u8));
+
+        assert_eq!(status, Some(8));
+    }
+
+    #[test]
+    fn reclaims_owned_erased() {
+        let ctx = OwnedErased::new([String::from("a"), String::from("b")]).into_c_context();
+
+        let erased = unsafe { OwnedErased::from_c_context(ctx) };
+        assert_eq!(unsafe { erased.as_ref::<[String; 2]>() }, &["a", "b"]);
+    }
+}
@@ -0,0 +1,137 @@
+/**
+ * `Fn*` forwarding for `Trident<F>`, allowing a contained closure to be called as `t(args)`, and
+ * likewise for `SmallFn`, `SmallFnMut` and `SmallFnOnce`, as `f(a)`.
+ */
+use std::marker::Tuple;
+
+use crate::{SmallFn, SmallFnMut, SmallFnOnce, Trident};
+
+impl<Args, F> FnOnce<Args> for Trident<F>
+where
+    Args: Tuple,
+    F: FnOnce<Args>,
+{
+    type Output = F::Output;
+
+    extern "rust-call" fn call_once(self, args: Args) -> F::Output {
+        self.into_inner().call_once(args)
+    }
+}
+
+impl<Args, F> FnMut<Args> for Trident<F>
+where
+    Args: Tuple,
+    F: FnMut<Args>,
+{
+    extern "rust-call" fn call_mut(&mut self, args: Args) -> F::Output {
+        self.as_mut_ref().call_mut(args)
+    }
+}
+
+impl<Args, F> Fn<Args> for Trident<F>
+where
+    Args: Tuple,
+    F: Fn<Args>,
+{
+    extern "rust-call" fn call(&self, args: Args) -> F::Output {
+        self.as_ref().call(args)
+    }
+}
+
+impl<A, R> FnOnce<(A,)> for SmallFn<A, R> {
+    type Output = R;
+
+    extern "rust-call" fn call_once(self, (a,): (A,)) -> R {
+        SmallFn::call(&self, a)
+    }
+}
+
+impl<A, R> FnMut<(A,)> for SmallFn<A, R> {
+    extern "rust-call" fn call_mut(&mut self, (a,): (A,)) -> R {
+        SmallFn::call(self, a)
+    }
+}
+
+impl<A, R> Fn<(A,)> for SmallFn<A, R> {
+    extern "rust-call" fn call(&self, (a,): (A,)) -> R {
+        SmallFn::call(self, a)
+    }
+}
+
+impl<A, R> FnOnce<(A,)> for SmallFnMut<A, R> {
+    type Output = R;
+
+    extern "rust-call" fn call_once(mut self, (a,): (A,)) -> R {
+        SmallFnMut::call(&mut self, a)
+    }
+}
+
+impl<A, R> FnMut<(A,)> for SmallFnMut<A, R> {
+    extern "rust-call" fn call_mut(&mut self, (a,): (A,)) -> R {
+        SmallFnMut::call(self, a)
+    }
+}
+
+impl<A, R> FnOnce<(A,)> for SmallFnOnce<A, R> {
+    type Output = R;
+
+    extern "rust-call" fn call_once(self, (a,): (A,)) -> R {
+        SmallFnOnce::call(self, a)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{SmallFn, SmallFnMut, SmallFnOnce, Trident};
+
+    #[test]
+    fn calls_small_closure() {
+        let offset = 3;
+        let t = Trident::new(|x: i32| x + offset);
+
+        assert_eq!(t(4), 7);
+        assert_eq!([1, 2].map(&t), [4, 5]);
+    }
+
+    #[test]
+    fn calls_large_mut_closure() {
+        let mut seen = Vec::new();
+        let big = [1u64; 8];
+        let mut t = Trident::new(|x: u64| seen.push(x + big[7]));
+
+        // call through `&mut`, otherwise closure kind inference settles on `Fn`
+        let call: &mut dyn FnMut(u64) = &mut t;
+        call(1);
+        call(2);
+        drop(t);
+
+        assert_eq!(seen, [2, 3]);
+    }
+
+    #[test]
+    fn calls_once() {
+        let s = String::from("moved");
+        let t = Trident::new(move || s);
+
+        fn call_once<F: FnOnce() -> String>(f: F) -> String {
+            f()
+        }
+        assert_eq!(call_once(t), "moved");
+    }
+
+    #[test]
+    fn calls_small_fns() {
+        let double = SmallFn::new(|x: i32| x * 2);
+        let mut count = 0;
+        let mut counter = SmallFnMut::new(move |()| {
+            count += 1;
+            count
+        });
+        let greet = SmallFnOnce::new(|name: &str| format!("hi {}", name));
+
+        assert_eq!([1, 2].map(&double), [2, 4]);
+        counter(());
+        assert_eq!(counter(()), 2);
+        assert_eq!(greet("there"), "hi there");
+    }
+}
@@ -0,0 +1,153 @@
+/**
+ * `Future` forwarding for `Trident<F>`.
+ *
+ * Pinning a `Trident<F>` structurally pins the contained `F`, whether it's inline or boxed, so no
+ * `Unpin` bound or extra allocation is required.
+ */
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use crate::{Storage, Trident};
+
+impl<F, S> Future for Trident<F, S>
+where
+    F: Future,
+    S: Storage,
+{
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+        self.as_pin_mut().poll(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        future::Future,
+        marker::PhantomPinned,
+        pin::{pin, Pin},
+        task::{Context, Poll, Waker},
+    };
+
+    use crate::Trident;
+
+    /// Completes after being polled `remaining` times, asserting it hasn't moved in between.
+    struct Countdown<Payload> {
+        remaining: u32,
+        addr: Option<usize>,
+        payload: Payload,
+        _pinned: PhantomPinned,
+    }
+
+    impl<Payload: Copy> Countdown<Payload> {
+        fn new(remaining: u32, payload: Payload) -> Self {
+            Self {
+                remaining,
+                addr: None,
+                payload,
+                _pinned: PhantomPinned,
+            }
+        }
+    }
+
+    impl<Payload: Copy> Future for Countdown<Payload> {
+        type Output = Payload;
+
+        fn poll(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Payload> {
+            // SAFETY: we don't move out of `this`
+            let this = unsafe { self.get_unchecked_mut() };
+            let addr = this as *mut _ as usize;
+
+            assert_eq!(*this.addr.get_or_insert(addr), addr);
+
+            if this.remaining == 0 {
+                Poll::Ready(this.payload)
+            } else {
+                this.remaining -= 1;
+                Poll::Pending
+            }
+        }
+    }
+
+    fn block_on<F: Future>(f: F) -> F::Output {
+        let mut f = pin!(f);
+        let mut cx = Context::from_waker(Waker::noop());
+
+        loop {
+            if let Poll::Ready(x) = f.as_mut().poll(&mut cx) {
+                return x;
+            }
+        }
+    }
+
+    #[test]
+    fn polls_inline_future() {
+        const { assert!(Trident::<Countdown<u8>>::IS_INLINE) }
+
+        let t = Trident::new(Countdown::new(3, 7u8));
+
+        assert_eq!(block_on(t), 7);
+    }
+
+    #[test]
+    fn polls_boxed_future() {
+        const { assert!(!Trident::<Countdown<[u64; 4]>>::IS_INLINE) }
+
+        let t = Trident::new(Countdown::new(3, [9u64; 4]));
+
+        assert_eq!(block_on(t), [9; 4]);
+    }
+
+    #[test]
+    fn awaits_in_async_block() {
+        let outer = async {
+            let a = Trident::new(async { 1 }).await;
+            let b = Trident::new(Countdown::new(2, [2u64; 4])).await;
+            a + b[0]
+        };
+
+        assert_eq!(block_on(outer), 3);
+    }
+
+    #[test]
+    fn polls_pinned_trident() {
+        let mut pinned = Trident::pin(Countdown::new(2, [3u64; 4]));
+        let mut cx = Context::from_waker(Waker::noop());
+
+        assert_eq!(pinned.as_mut().poll(&mut cx), Poll::Pending);
+
+        // moving the pinned trident mustn't move the future
+        let mut moved = pinned;
+        assert_eq!(moved.as_mut().poll(&mut cx), Poll::Pending);
+        assert_eq!(moved.as_mut().poll(&mut cx), Poll::Ready([3; 4]));
+    }
+
+    #[test]
+    fn pins_inline_future_by_boxing() {
+        const { assert!(Trident::<Countdown<u8>>::IS_INLINE) }
+
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut pinned = Trident::new(Countdown::new(1, 4u8)).into_pin();
+        assert_eq!(pinned.as_mut().poll(&mut cx), Poll::Pending);
+
+        let mut moved = pinned;
+        assert_eq!(moved.as_mut().poll(&mut cx), Poll::Ready(4));
+
+        // fills all 3 words, so can't be spilled in place
+        const { assert!(std::mem::size_of::<Countdown<()>>() == 3 * std::mem::size_of::<usize>()) }
+        let mut full = Trident::pin(Countdown::new(0, ()));
+        assert_eq!(full.as_mut().poll(&mut cx), Poll::Ready(()));
+    }
+
+    #[test]
+    fn pins_unpin_inline_with_pin_new() {
+        let mut pinned = Pin::new(Trident::new(std::future::ready(5)));
+        let mut cx = Context::from_waker(Waker::noop());
+
+        assert_eq!(pinned.as_mut().poll(&mut cx), Poll::Ready(5));
+    }
+}
@@ -1,20 +1,69 @@
-use std::{
-    alloc::{self, Layout},
-    mem, ptr,
-};
+use std::{mem, ptr};
 
-use crate::limits;
+use crate::erased;
 
-pub(crate) fn into_inner<T, Container>(ptr: *mut T, container: Container) -> T {
+pub(crate) fn into_inner<T, Container>(ptr: *mut T, inline: bool, container: Container) -> T {
     let t = unsafe { ptr::read(ptr) };
 
     // need to free the box without running T's dtor
-    if !limits::should_inline::<T>() {
+    if !inline {
         unsafe {
-            alloc::dealloc(ptr as *mut u8, Layout::new::<T>());
+            erased::dealloc(ptr);
         }
-        mem::forget(container);
     }
+    // and the container mustn't drop the T we've just read out
+    mem::forget(container);
 
     t
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, mem::ManuallyDrop};
+
+    use super::*;
+
+    struct Counted<'a>(&'a Cell<u32>);
+
+    impl Drop for Counted<'_> {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    /**
+     * Drops the `Counted` it holds, as a `Trident` does.
+     */
+    struct Container<'a>(ManuallyDrop<Counted<'a>>);
+
+    impl Drop for Container<'_> {
+        fn drop(&mut self) {
+            unsafe { ManuallyDrop::drop(&mut self.0) }
+        }
+    }
+
+    #[test]
+    fn forgets_inline_containers() {
+        let drops = Cell::new(0);
+        let mut container = Container(ManuallyDrop::new(Counted(&drops)));
+
+        let ptr: *mut Counted<'_> = &mut *container.0;
+        let counted = into_inner(ptr, true, container);
+        assert_eq!(drops.get(), 0);
+
+        drop(counted);
+        assert_eq!(drops.get(), 1);
+    }
+
+    #[test]
+    fn frees_boxed_values() {
+        let drops = Cell::new(0);
+        let ptr = Box::into_raw(Box::new(Counted(&drops)));
+
+        let counted = into_inner(ptr, false, ());
+        assert_eq!(drops.get(), 0);
+
+        drop(counted);
+        assert_eq!(drops.get(), 1);
+    }
+}
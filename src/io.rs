@@ -0,0 +1,125 @@
+/**
+ * Forwarding implementations of the `std::io` traits for `Trident<T>`.
+ */
+use std::io::{self, BufRead, IoSlice, IoSliceMut, Read, Seek, SeekFrom, Write};
+
+use crate::Trident;
+
+impl<R> Read for Trident<R>
+where
+    R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.as_mut_ref().read(buf)
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        self.as_mut_ref().read_vectored(bufs)
+    }
+
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        self.as_mut_ref().read_to_end(buf)
+    }
+
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        self.as_mut_ref().read_to_string(buf)
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        self.as_mut_ref().read_exact(buf)
+    }
+}
+
+impl<W> Write for Trident<W>
+where
+    W: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.as_mut_ref().write(buf)
+    }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        self.as_mut_ref().write_vectored(bufs)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.as_mut_ref().flush()
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.as_mut_ref().write_all(buf)
+    }
+}
+
+impl<S> Seek for Trident<S>
+where
+    S: Seek,
+{
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.as_mut_ref().seek(pos)
+    }
+
+    fn stream_position(&mut self) -> io::Result<u64> {
+        self.as_mut_ref().stream_position()
+    }
+}
+
+impl<B> BufRead for Trident<B>
+where
+    B: BufRead,
+{
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.as_mut_ref().fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.as_mut_ref().consume(amt)
+    }
+
+    fn read_until(&mut self, byte: u8, buf: &mut Vec<u8>) -> io::Result<usize> {
+        self.as_mut_ref().read_until(byte, buf)
+    }
+
+    fn read_line(&mut self, buf: &mut String) -> io::Result<usize> {
+        self.as_mut_ref().read_line(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Cursor, Read, Seek, SeekFrom, Write};
+
+    use crate::Trident;
+
+    #[test]
+    fn reads_and_seeks() {
+        let mut t = Trident::new(Cursor::new(b"hello world".to_vec()));
+
+        let mut buf = [0; 5];
+        t.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"hello");
+
+        t.seek(SeekFrom::Start(6)).unwrap();
+        let mut rest = String::new();
+        t.read_to_string(&mut rest).unwrap();
+        assert_eq!(rest, "world");
+    }
+
+    #[test]
+    fn writes() {
+        let mut t = Trident::new(Vec::new());
+
+        write!(t, "{}-{}", 1, 2).unwrap();
+        t.flush().unwrap();
+
+        assert_eq!(t.as_slice(), b"1-2");
+    }
+
+    #[test]
+    fn reads_lines_from_large_reader() {
+        let t = Trident::new(BufReader::new(&b"one\ntwo\n"[..]));
+
+        let lines: Vec<_> = t.lines().map(Result::unwrap).collect();
+        assert_eq!(lines, ["one", "two"]);
+    }
+}
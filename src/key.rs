@@ -0,0 +1,276 @@
+use std::{
+    any::{Any, TypeId},
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    mem::ManuallyDrop,
+    ptr,
+};
+
+use crate::owned::drop_erased;
+use crate::{Erased, Trident};
+
+/**
+ * A type-erased `T`, which can be compared, hashed and displayed without knowing `T`.
+ *
+ * For example, `KeyErased`s of different types can share a `HashMap` or `BTreeMap` as keys.
+ * Values of the same type compare and hash as `T` does. Values of different types are never
+ * equal, and order by their `TypeId`, which is consistent within a build but otherwise arbitrary.
+ * This costs a word for a pointer to `T`'s functions.
+ */
+pub struct KeyErased {
+    erased: Erased,
+    vtable: &'static KeyVTable,
+}
+
+struct KeyVTable {
+    type_id: fn() -> TypeId,
+    eq: unsafe fn(&Erased, &Erased) -> bool,
+    cmp: unsafe fn(&Erased, &Erased) -> Ordering,
+    hash: unsafe fn(&Erased, &mut dyn Hasher),
+    fmt: unsafe fn(&Erased, &mut fmt::Formatter<'_>) -> fmt::Result,
+    drop: unsafe fn(&mut Erased),
+}
+
+struct VTableFor<T>(PhantomData<T>);
+
+impl<T: Any + Ord + Hash + fmt::Display> VTableFor<T> {
+    const VTABLE: KeyVTable = KeyVTable {
+        type_id: TypeId::of::<T>,
+        eq: eq_erased::<T>,
+        cmp: cmp_erased::<T>,
+        hash: hash_erased::<T>,
+        fmt: fmt_erased::<T>,
+        drop: drop_erased::<T>,
+    };
+}
+
+impl KeyErased {
+    /**
+     * Create a `KeyErased` from a `T`.
+     */
+    pub fn new<T: Any + Ord + Hash + fmt::Display>(t: T) -> Self {
+        Self::from_trident(Trident::new(t))
+    }
+
+    /**
+     * Create a `KeyErased` from a `Trident<T>`, keeping its storage.
+     */
+    pub fn from_trident<T: Any + Ord + Hash + fmt::Display>(t: Trident<T>) -> Self {
+        Self {
+            erased: t.into_erased(),
+            vtable: &VTableFor::<T>::VTABLE,
+        }
+    }
+
+    /**
+     * Whether the contained value is a `T`.
+     */
+    pub fn is<T: Any>(&self) -> bool {
+        (self.vtable.type_id)() == TypeId::of::<T>()
+    }
+
+    /**
+     * Get a reference to the contained value, if it's a `T`.
+     */
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        if self.is::<T>() {
+            // SAFETY: we hold a T
+            Some(unsafe { self.erased.as_ref() })
+        } else {
+            None
+        }
+    }
+
+    /**
+     * Convert to a `Trident<T>`, if the contained value is a `T`, otherwise return `self`.
+     */
+    pub fn downcast<T: Any>(self) -> Result<Trident<T>, Self> {
+        if self.is::<T>() {
+            let this = ManuallyDrop::new(self);
+
+            // SAFETY: we hold a T, and `this` is never dropped, so ownership moves to the Trident
+            Ok(unsafe { ptr::read(&this.erased).into_trident() })
+        } else {
+            Err(self)
+        }
+    }
+
+    fn same_type(&self, other: &Self) -> bool {
+        ptr::eq(self.vtable, other.vtable) || self.type_id() == other.type_id()
+    }
+
+    fn type_id(&self) -> TypeId {
+        (self.vtable.type_id)()
+    }
+}
+
+impl PartialEq for KeyErased {
+    fn eq(&self, other: &Self) -> bool {
+        // SAFETY: both hold the T the vtable was created for
+        self.same_type(other) && unsafe { (self.vtable.eq)(&self.erased, &other.erased) }
+    }
+}
+
+impl Eq for KeyErased {}
+
+/**
+ * Orders by `TypeId`, then as `T` does.
+ */
+impl Ord for KeyErased {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if self.same_type(other) {
+            // SAFETY: both hold the T the vtable was created for
+            unsafe { (self.vtable.cmp)(&self.erased, &other.erased) }
+        } else {
+            self.type_id().cmp(&other.type_id())
+        }
+    }
+}
+
+impl PartialOrd for KeyErased {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/**
+ * Hashes the `TypeId`, then the `T`.
+ */
+impl Hash for KeyErased {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.type_id().hash(state);
+        // SAFETY: the vtable was created for the T we hold
+        unsafe { (self.vtable.hash)(&self.erased, state) }
+    }
+}
+
+/**
+ * Forwards to `T`'s `Display` implementation.
+ */
+impl fmt::Display for KeyErased {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // SAFETY: the vtable was created for the T we hold
+        unsafe { (self.vtable.fmt)(&self.erased, f) }
+    }
+}
+
+impl fmt::Debug for KeyErased {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("KeyErased")
+            .field(&format_args!("{}", self))
+            .finish()
+    }
+}
+
+impl Drop for KeyErased {
+    fn drop(&mut self) {
+        // SAFETY: the vtable was created for the T we hold
+        unsafe { (self.vtable.drop)(&mut self.erased) }
+    }
+}
+
+impl<T: Any + Ord + Hash + fmt::Display> From<Trident<T>> for KeyErased {
+    fn from(t: Trident<T>) -> Self {
+        Self::from_trident(t)
+    }
+}
+
+/**
+ * Compare the `T`s held by `a` and `b` for equality.
+ *
+ * Unsafe because `a` and `b` must hold a `T`.
+ */
+unsafe fn eq_erased<T: Eq>(a: &Erased, b: &Erased) -> bool {
+    a.as_ref::<T>() == b.as_ref::<T>()
+}
+
+/**
+ * Compare the `T`s held by `a` and `b`.
+ *
+ * Unsafe because `a` and `b` must hold a `T`.
+ */
+unsafe fn cmp_erased<T: Ord>(a: &Erased, b: &Erased) -> Ordering {
+    a.as_ref::<T>().cmp(b.as_ref::<T>())
+}
+
+/**
+ * Hash the `T` held by `erased`.
+ *
+ * Unsafe because `erased` must hold a `T`.
+ */
+unsafe fn hash_erased<T: Hash>(erased: &Erased, mut state: &mut dyn Hasher) {
+    erased.as_ref::<T>().hash(&mut state)
+}
+
+/**
+ * Display the `T` held by `erased`.
+ *
+ * Unsafe because `erased` must hold a `T`.
+ */
+unsafe fn fmt_erased<T: fmt::Display>(erased: &Erased, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    fmt::Display::fmt(erased.as_ref::<T>(), f)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashMap};
+
+    use super::*;
+
+    #[test]
+    fn compares() {
+        assert_eq!(KeyErased::new(1u8), KeyErased::new(1u8));
+        assert_ne!(KeyErased::new(1u8), KeyErased::new(2u8));
+        assert_ne!(KeyErased::new(1u8), KeyErased::new(1u16));
+
+        assert!(KeyErased::new(1u8) < KeyErased::new(2u8));
+        assert!(KeyErased::new("b") > KeyErased::new("a"));
+        assert_ne!(
+            KeyErased::new(1u8).cmp(&KeyErased::new(1u16)),
+            Ordering::Equal
+        );
+    }
+
+    #[derive(PartialEq, Eq, PartialOrd, Ord, Hash)]
+    struct Large([u64; 4]);
+
+    impl fmt::Display for Large {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{:?}", self.0)
+        }
+    }
+
+    #[test]
+    fn keys_maps() {
+        let mut hashed = HashMap::new();
+        hashed.insert(KeyErased::new(1i64), "int");
+        hashed.insert(KeyErased::new(String::from("one")), "string");
+        hashed.insert(KeyErased::new(Large([1; 4])), "array");
+
+        assert_eq!(hashed[&KeyErased::new(1i64)], "int");
+        assert_eq!(hashed[&KeyErased::new(String::from("one"))], "string");
+        assert_eq!(hashed[&KeyErased::new(Large([1; 4]))], "array");
+        assert_eq!(hashed.get(&KeyErased::new(1i32)), None);
+
+        let sorted = [3u8, 1, 2]
+            .into_iter()
+            .map(|i| (KeyErased::new(i), i))
+            .collect::<BTreeMap<_, _>>();
+        assert_eq!(sorted.values().copied().collect::<Vec<_>>(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn displays_and_downcasts() {
+        let key = KeyErased::new(String::from("sym"));
+
+        assert_eq!(key.to_string(), "sym");
+        assert_eq!(KeyErased::new(Large([2; 4])).to_string(), "[2, 2, 2, 2]");
+        assert_eq!(format!("{:?}", key), "KeyErased(sym)");
+        assert_eq!(key.downcast_ref::<u8>(), None);
+
+        let key = key.downcast::<u8>().unwrap_err();
+        assert_eq!(key.downcast::<String>().unwrap().into_inner(), "sym");
+    }
+}
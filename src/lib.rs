@@ -1,7 +1,160 @@
+#![cfg_attr(
+    feature = "nightly",
+    feature(
+        fn_traits,
+        layout_for_ptr,
+        ptr_metadata,
+        tuple_trait,
+        unboxed_closures,
+        unsize
+    )
+)]
+#![cfg_attr(
+    all(feature = "nightly", feature = "allocator-api2"),
+    feature(allocator_api)
+)]
+
+#[cfg(feature = "allocator-api2")]
+mod alloc_in;
+mod any;
+mod any_channel;
+mod any_map;
+#[cfg(feature = "arbitrary")]
+mod arbitrary_impl;
+#[cfg(feature = "allocator-api2")]
+mod arena;
+mod atomic_trident;
+mod boxed;
+mod cloneable;
+mod debug;
+#[cfg(feature = "defmt")]
+mod defmt_impl;
+mod erasable;
 mod erased;
+mod erased_deque;
+mod erased_fn;
+mod erased_ref;
+mod erased_slab;
+mod erased_stack;
+mod erased_vec;
+mod event_bus;
+mod ffi;
+#[cfg(feature = "nightly")]
+mod fn_traits;
+mod future;
 mod into;
+mod io;
+mod key;
 mod limits;
+mod once_trident;
+#[cfg(feature = "ops")]
+mod ops;
+mod option;
+mod owned;
+mod payload;
+#[cfg(feature = "bytemuck")]
+mod pod;
+#[cfg(feature = "allocator-api2")]
+mod pool;
+#[cfg(feature = "proptest")]
+mod proptest_impl;
+#[cfg(feature = "rkyv")]
+mod rkyv_impl;
+mod send;
+#[cfg(feature = "serde")]
+mod serde_impl;
+mod service_registry;
+mod sized;
+mod slot;
+mod small_cstr;
+mod small_dyn;
+mod small_error;
+mod small_fn;
+mod small_future;
+mod small_iter;
+mod small_rc;
+mod small_slice;
+mod small_str;
+#[cfg(feature = "smallbox")]
+mod smallbox_impl;
+mod stack_trident;
+#[cfg(feature = "allocator-api2")]
+mod static_pool;
+mod storage;
+#[cfg(feature = "futures")]
+mod stream;
+#[cfg(feature = "nightly")]
+mod thin_dyn;
 mod trident;
+mod trident_cell;
+mod trident_cow;
+mod trident_dyn;
+mod trident_ref_cell;
+mod trident_vec;
 
+#[cfg(feature = "allocator-api2")]
+pub use crate::alloc_in::*;
+pub use crate::any::*;
+pub use crate::any_channel::*;
+pub use crate::any_map::*;
+#[cfg(feature = "allocator-api2")]
+pub use crate::arena::*;
+pub use crate::atomic_trident::*;
+pub use crate::boxed::*;
+pub use crate::cloneable::*;
+pub use crate::debug::*;
+pub use crate::erasable::*;
 pub use crate::erased::*;
+pub use crate::erased_deque::*;
+pub use crate::erased_fn::*;
+pub use crate::erased_ref::*;
+pub use crate::erased_slab::*;
+pub use crate::erased_stack::*;
+pub use crate::erased_vec::*;
+pub use crate::event_bus::*;
+pub use crate::key::*;
+pub use crate::once_trident::*;
+pub use crate::option::*;
+pub use crate::owned::*;
+#[cfg(feature = "bytemuck")]
+pub use crate::pod::*;
+#[cfg(feature = "allocator-api2")]
+pub use crate::pool::*;
+#[cfg(feature = "proptest")]
+pub use crate::proptest_impl::*;
+#[cfg(feature = "rkyv")]
+pub use crate::rkyv_impl::*;
+pub use crate::send::*;
+pub use crate::service_registry::*;
+pub use crate::sized::*;
+pub use crate::slot::*;
+pub use crate::small_cstr::*;
+pub use crate::small_error::*;
+pub use crate::small_fn::*;
+pub use crate::small_future::*;
+pub use crate::small_iter::*;
+pub use crate::small_rc::*;
+pub use crate::small_slice::*;
+pub use crate::small_str::*;
+pub use crate::stack_trident::*;
+#[cfg(feature = "allocator-api2")]
+pub use crate::static_pool::*;
+pub use crate::storage::*;
+#[cfg(feature = "nightly")]
+pub use crate::thin_dyn::*;
 pub use crate::trident::*;
+pub use crate::trident_cell::*;
+pub use crate::trident_cow::*;
+pub use crate::trident_dyn::*;
+pub use crate::trident_ref_cell::*;
+pub use crate::trident_vec::*;
+#[cfg(feature = "derive")]
+pub use trident_derive::Erasable;
+
+/**
+ * Not public API, for use by macros which expand in other crates.
+ */
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::payload::Payload;
+}
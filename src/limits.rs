@@ -1,9 +1,38 @@
-use std::mem;
+use std::{alloc::Layout, mem};
 
 pub(crate) const NWORDS: usize = 3;
 
 pub(crate) const SIZE_LIMIT: usize = mem::size_of::<[usize; NWORDS]>();
 
-pub(crate) fn should_inline<T>() -> bool {
-    mem::size_of::<T>() <= SIZE_LIMIT
+/**
+ * Whether a `T` is stored inline.
+ *
+ * The storage is only aligned to a word, so a `T` aligned beyond that is boxed, however small.
+ */
+pub(crate) const fn should_inline<T>() -> bool {
+    inlines(Layout::new::<T>())
+}
+
+/**
+ * As `should_inline`, for a payload of the given layout.
+ */
+pub(crate) const fn inlines(layout: Layout) -> bool {
+    layout.size() <= SIZE_LIMIT && layout.align() <= mem::align_of::<usize>()
+}
+
+/**
+ * Whether an inline `T` can be moved to the heap on request.
+ *
+ * A moved `T` is flagged in the last byte of the storage, so `T` must leave that byte spare. A
+ * zero-sized `T` has nothing to move.
+ */
+pub(crate) const fn can_spill<T>() -> bool {
+    can_spill_layout(Layout::new::<T>())
+}
+
+/**
+ * As `can_spill`, for a payload of the given layout.
+ */
+pub(crate) const fn can_spill_layout(layout: Layout) -> bool {
+    layout.size() > 0 && layout.size() < SIZE_LIMIT
 }
@@ -0,0 +1,305 @@
+use std::{cell::UnsafeCell, fmt, ops::Deref};
+
+use crate::{OptionTrident, Trident};
+
+/**
+ * A cell which is written once, as `std::cell::OnceCell<T>`, in the three words of a
+ * `Trident<T>`, so small values are inline rather than needing a `OnceCell<Box<T>>`.
+ *
+ * The value is kept in an `OptionTrident<T>`, so being unset costs no extra word.
+ */
+pub struct OnceTrident<T> {
+    slot: UnsafeCell<OptionTrident<T>>,
+}
+
+/**
+ * A value computed by `F` on first access, as `std::cell::LazyCell<T, F>`, in the three words of
+ * a `Trident`.
+ *
+ * The `F` and, once computed, the `T` share the storage, so a `T` and `F` which fit inline
+ * together need no allocation, and otherwise one allocation holds either.
+ */
+pub struct LazyTrident<T, F = fn() -> T> {
+    state: UnsafeCell<Trident<State<T, F>>>,
+}
+
+enum State<T, F> {
+    Uninit(F),
+    Init(T),
+    Poisoned,
+}
+
+impl<T> OnceTrident<T> {
+    /**
+     * Create an empty cell.
+     */
+    pub const fn new() -> Self {
+        Self {
+            slot: UnsafeCell::new(OptionTrident::new()),
+        }
+    }
+
+    /**
+     * Get a reference to the value, if it's been set.
+     */
+    pub fn get(&self) -> Option<&T> {
+        // SAFETY: the slot is only written while empty, so never while a reference to its value
+        // is held
+        unsafe { (*self.slot.get()).as_ref() }
+    }
+
+    /**
+     * Get a mutable reference to the value, if it's been set.
+     */
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        self.slot.get_mut().as_mut()
+    }
+
+    /**
+     * Set the value, or return `t` if it's already set.
+     */
+    pub fn set(&self, t: T) -> Result<(), T> {
+        if self.get().is_some() {
+            return Err(t);
+        }
+
+        // SAFETY: the slot is empty, so no references to its value are held
+        unsafe { (*self.slot.get()).insert(t) };
+        Ok(())
+    }
+
+    /**
+     * Get a reference to the value, setting it to the result of `f` if it's not yet set.
+     *
+     * # Panics
+     *
+     * Panics if `f` initialises the cell reentrantly. If `f` panics, the cell remains unset.
+     */
+    pub fn get_or_init<F: FnOnce() -> T>(&self, f: F) -> &T {
+        if let Some(t) = self.get() {
+            return t;
+        }
+
+        let t = f();
+        assert!(self.get().is_none(), "reentrant init");
+
+        // SAFETY: the slot is empty, so no references to its value are held
+        unsafe { (*self.slot.get()).insert(t) }
+    }
+
+    /**
+     * Take the value, if any, leaving the cell unset.
+     */
+    pub fn take(&mut self) -> Option<T> {
+        self.slot.get_mut().take()
+    }
+
+    /**
+     * Convert to the value, if it's been set.
+     */
+    pub fn into_inner(self) -> Option<T> {
+        self.slot.into_inner().into_option()
+    }
+}
+
+impl<T, F: FnOnce() -> T> LazyTrident<T, F> {
+    /**
+     * Create a value which is computed by `f` when first accessed.
+     */
+    pub fn new(f: F) -> Self {
+        Self {
+            state: UnsafeCell::new(Trident::new(State::Uninit(f))),
+        }
+    }
+
+    /**
+     * Get a reference to the value, computing it if this is the first access.
+     *
+     * # Panics
+     *
+     * Panics if computing the value panicked on an earlier access.
+     */
+    pub fn force(this: &Self) -> &T {
+        if let State::Init(t) = this.get() {
+            return t;
+        }
+
+        // SAFETY: not yet initialised, so no references to the state are held. Poisoned until f
+        // returns, so a reentrant or panicking f leaves it poisoned
+        let f = match unsafe { (*this.state.get()).replace(State::Poisoned) } {
+            State::Uninit(f) => f,
+            _ => panic!("LazyTrident instance has previously been poisoned"),
+        };
+        let t = f();
+
+        // SAFETY: as above. A reentrant force would have panicked on the poisoned state
+        unsafe { (*this.state.get()).set(State::Init(t)) };
+
+        match this.get() {
+            State::Init(t) => t,
+            _ => unreachable!(),
+        }
+    }
+
+    fn get(&self) -> &State<T, F> {
+        // SAFETY: the state is only written before it's initialised, when no references to it
+        // are held
+        unsafe { &*self.state.get() }
+    }
+
+    /**
+     * Convert to the value, if it's been computed, otherwise return the function which would
+     * compute it.
+     */
+    pub fn into_inner(this: Self) -> Result<T, F> {
+        match this.state.into_inner().into_inner() {
+            State::Init(t) => Ok(t),
+            State::Uninit(f) => Err(f),
+            State::Poisoned => panic!("LazyTrident instance has previously been poisoned"),
+        }
+    }
+}
+
+impl<T> Default for OnceTrident<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> From<T> for OnceTrident<T> {
+    fn from(t: T) -> Self {
+        let cell = Self::new();
+        let _ = cell.set(t);
+        cell
+    }
+}
+
+impl<T: Clone> Clone for OnceTrident<T> {
+    fn clone(&self) -> Self {
+        self.get().cloned().map_or_else(Self::new, Self::from)
+    }
+}
+
+impl<T: PartialEq> PartialEq for OnceTrident<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.get() == other.get()
+    }
+}
+
+impl<T: Eq> Eq for OnceTrident<T> {}
+
+impl<T: Default> Default for LazyTrident<T> {
+    fn default() -> Self {
+        Self::new(T::default)
+    }
+}
+
+impl<T, F: FnOnce() -> T> Deref for LazyTrident<T, F> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        Self::force(self)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for OnceTrident<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_tuple("OnceTrident");
+        match self.get() {
+            Some(t) => d.field(t),
+            None => d.field(&format_args!("<uninit>")),
+        };
+        d.finish()
+    }
+}
+
+impl<T: fmt::Debug, F> fmt::Debug for LazyTrident<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_tuple("LazyTrident");
+        // SAFETY: as LazyTrident::get
+        match unsafe { &**self.state.get() } {
+            State::Init(t) => d.field(t),
+            _ => d.field(&format_args!("<uninit>")),
+        };
+        d.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::Cell,
+        mem,
+        panic::{self, AssertUnwindSafe},
+    };
+
+    use super::*;
+
+    #[test]
+    fn initialises_once() {
+        let cell = OnceTrident::new();
+        assert_eq!(cell.get(), None);
+        assert_eq!(format!("{cell:?}"), "OnceTrident(<uninit>)");
+
+        assert_eq!(*cell.get_or_init(|| [1u64; 2]), [1, 1]);
+        assert_eq!(*cell.get_or_init(|| unreachable!()), [1, 1]);
+        assert_eq!(cell.set([2; 2]), Err([2, 2]));
+
+        let mut large = OnceTrident::from(String::from("large").repeat(10));
+        large.get_mut().unwrap().truncate(5);
+        assert_eq!(large.clone(), OnceTrident::from(String::from("large")));
+        assert_eq!(large.take().as_deref(), Some("large"));
+        assert_eq!(large.into_inner(), None);
+    }
+
+    #[test]
+    fn computes_on_first_deref() {
+        let calls = Cell::new(0);
+        let lazy = LazyTrident::new(|| {
+            calls.set(calls.get() + 1);
+            [3u32; 10]
+        });
+
+        assert_eq!(calls.get(), 0);
+        assert_eq!(lazy[9], 3);
+        assert_eq!(lazy.iter().sum::<u32>(), 30);
+        assert_eq!(calls.get(), 1);
+        assert_eq!(LazyTrident::into_inner(lazy).ok(), Some([3; 10]));
+
+        let unforced = LazyTrident::new(|| 4u8);
+        assert_eq!(LazyTrident::into_inner(unforced).map_err(|f| f()), Err(4));
+
+        let default = LazyTrident::<Vec<i32>>::default();
+        assert!(default.is_empty());
+    }
+
+    #[test]
+    fn fits_in_three_words() {
+        let size = mem::size_of::<Trident<u8>>();
+
+        assert_eq!(mem::size_of::<OnceTrident<u8>>(), size);
+        assert_eq!(mem::size_of::<OnceTrident<[usize; 3]>>(), size);
+        assert_eq!(mem::size_of::<OnceTrident<String>>(), size);
+        assert_eq!(mem::size_of::<LazyTrident<[u64; 2]>>(), size);
+        assert_eq!(
+            mem::size_of::<LazyTrident<String, Box<dyn FnOnce() -> String>>>(),
+            size
+        );
+
+        let mut lazy = LazyTrident::new(|| 5u64);
+        assert!(lazy.state.get_mut().is_inline());
+        assert_eq!(*lazy, 5);
+    }
+
+    #[test]
+    fn poisons_on_panic() {
+        let lazy = LazyTrident::new(|| -> u8 { panic!("init failed") });
+
+        assert!(panic::catch_unwind(AssertUnwindSafe(|| *lazy)).is_err());
+        let err = panic::catch_unwind(AssertUnwindSafe(|| *lazy)).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<&str>(),
+            Some(&"LazyTrident instance has previously been poisoned")
+        );
+    }
+}
@@ -0,0 +1,102 @@
+/**
+ * Operator forwarding for `Trident<T>`, producing a `Trident<T::Output>`.
+ */
+use std::ops::{Add, BitAnd, BitOr, BitXor, Div, Mul, Neg, Not, Rem, Shl, Shr, Sub};
+
+use crate::Trident;
+
+macro_rules! forward_binop {
+    ($($trait:ident :: $method:ident),* $(,)?) => {
+        $(
+            impl<T, U> $trait<Trident<U>> for Trident<T>
+            where
+                T: $trait<U>,
+            {
+                type Output = Trident<T::Output>;
+
+                fn $method(self, rhs: Trident<U>) -> Self::Output {
+                    Trident::new(self.into_inner().$method(rhs.into_inner()))
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! forward_unop {
+    ($($trait:ident :: $method:ident),* $(,)?) => {
+        $(
+            impl<T> $trait for Trident<T>
+            where
+                T: $trait,
+            {
+                type Output = Trident<T::Output>;
+
+                fn $method(self) -> Self::Output {
+                    Trident::new(self.into_inner().$method())
+                }
+            }
+        )*
+    };
+}
+
+forward_binop!(
+    Add::add,
+    Sub::sub,
+    Mul::mul,
+    Div::div,
+    Rem::rem,
+    BitAnd::bitand,
+    BitOr::bitor,
+    BitXor::bitxor,
+    Shl::shl,
+    Shr::shr,
+);
+
+forward_unop!(Neg::neg, Not::not);
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Add;
+
+    use crate::Trident;
+
+    #[test]
+    fn forwards_arithmetic() {
+        let t = Trident::new(6) * Trident::new(7) - Trident::new(2);
+
+        assert_eq!(t, 40);
+        assert_eq!(-t, -40);
+        assert_eq!(Trident::new(7) % Trident::new(4), 3);
+    }
+
+    #[test]
+    fn forwards_bit_operators() {
+        assert_eq!(Trident::new(0b1100u8) & Trident::new(0b1010), 0b1000);
+        assert_eq!(Trident::new(0b1100u8) | Trident::new(0b1010), 0b1110);
+        assert_eq!(Trident::new(0b1100u8) ^ Trident::new(0b1010), 0b0110);
+        assert_eq!(Trident::new(1u32) << Trident::new(4u8), 16);
+        assert_eq!(!Trident::new(true), false);
+    }
+
+    #[test]
+    fn forwards_to_large_output() {
+        #[derive(Debug, PartialEq)]
+        struct Vector([i64; 4]);
+
+        impl Add for Vector {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                let mut out = self.0;
+                for (o, r) in out.iter_mut().zip(rhs.0) {
+                    *o += r;
+                }
+                Vector(out)
+            }
+        }
+
+        let sum = Trident::new(Vector([1, 2, 3, 4])) + Trident::new(Vector([4, 3, 2, 1]));
+
+        assert_eq!(sum, Vector([5; 4]));
+    }
+}
@@ -0,0 +1,338 @@
+use std::{
+    fmt,
+    marker::PhantomData,
+    mem::{self, ManuallyDrop},
+    ptr,
+};
+
+use crate::limits;
+use crate::Erased;
+
+/**
+ * A maybe-empty slot for a `T`, in the same three words as a `Trident<T>`.
+ *
+ * If `Option<T>` is small enough, it's stored inline. Otherwise the `T` is boxed and the empty
+ * state is a null pointer, so vacancy never costs an extra word. A `T` which only just fits
+ * inline, with no niche to spare for `None`, is boxed.
+ */
+pub struct OptionTrident<T> {
+    erased: Erased,
+    _phantom: PhantomData<T>,
+}
+
+impl<T> OptionTrident<T> {
+    const INLINE: bool = limits::should_inline::<Option<T>>();
+
+    /**
+     * Create an empty `OptionTrident<T>`.
+     */
+    pub const fn new() -> Self {
+        // both fit inline, so no allocation is needed
+        let erased = if Self::INLINE {
+            Erased::new_inline(None::<T>)
+        } else {
+            Erased::new_inline(None::<Box<T>>)
+        };
+
+        Self {
+            erased,
+            _phantom: PhantomData,
+        }
+    }
+
+    /**
+     * Create an `OptionTrident<T>` from an `Option<T>`.
+     */
+    pub fn from_option(o: Option<T>) -> Self {
+        let erased = if Self::INLINE {
+            Erased::new(o)
+        } else {
+            Erased::new(o.map(Box::new))
+        };
+
+        Self {
+            erased,
+            _phantom: PhantomData,
+        }
+    }
+
+    fn inline(&self) -> &Option<T> {
+        debug_assert!(Self::INLINE);
+        // SAFETY: we store an Option<T> when INLINE
+        unsafe { self.erased.as_ref() }
+    }
+
+    fn inline_mut(&mut self) -> &mut Option<T> {
+        debug_assert!(Self::INLINE);
+        // SAFETY: as above
+        unsafe { self.erased.as_mut_ref() }
+    }
+
+    fn boxed(&self) -> &Option<Box<T>> {
+        debug_assert!(!Self::INLINE);
+        // SAFETY: we store an Option<Box<T>> when not INLINE
+        unsafe { self.erased.as_ref() }
+    }
+
+    fn boxed_mut(&mut self) -> &mut Option<Box<T>> {
+        debug_assert!(!Self::INLINE);
+        // SAFETY: as above
+        unsafe { self.erased.as_mut_ref() }
+    }
+
+    /**
+     * Whether a `T` is present.
+     */
+    pub fn is_some(&self) -> bool {
+        if Self::INLINE {
+            self.inline().is_some()
+        } else {
+            self.boxed().is_some()
+        }
+    }
+
+    /**
+     * Whether the slot is empty.
+     */
+    pub fn is_none(&self) -> bool {
+        !self.is_some()
+    }
+
+    /**
+     * Get a reference to the `T`, if present.
+     */
+    pub fn as_ref(&self) -> Option<&T> {
+        if Self::INLINE {
+            self.inline().as_ref()
+        } else {
+            self.boxed().as_deref()
+        }
+    }
+
+    /**
+     * Get a mutable reference to the `T`, if present.
+     */
+    pub fn as_mut(&mut self) -> Option<&mut T> {
+        if Self::INLINE {
+            self.inline_mut().as_mut()
+        } else {
+            self.boxed_mut().as_deref_mut()
+        }
+    }
+
+    /**
+     * Take the `T`, if present, leaving the slot empty.
+     *
+     * A boxed `T`'s allocation is freed.
+     */
+    pub fn take(&mut self) -> Option<T> {
+        if Self::INLINE {
+            self.inline_mut().take()
+        } else {
+            self.boxed_mut().take().map(|b| *b)
+        }
+    }
+
+    /**
+     * Store `t`, dropping any previous `T`, and return a reference to it.
+     *
+     * If a boxed `T` is already present, its allocation is reused.
+     */
+    pub fn insert(&mut self, t: T) -> &mut T {
+        if Self::INLINE {
+            self.inline_mut().insert(t)
+        } else {
+            match self.boxed_mut() {
+                Some(b) => {
+                    **b = t;
+                    b
+                }
+                none => none.insert(Box::new(t)),
+            }
+        }
+    }
+
+    /**
+     * Store `t`, returning any previous `T`.
+     *
+     * If a boxed `T` is already present, its allocation is reused.
+     */
+    pub fn replace(&mut self, t: T) -> Option<T> {
+        match self.as_mut() {
+            Some(old) => Some(mem::replace(old, t)),
+            None => {
+                self.insert(t);
+                None
+            }
+        }
+    }
+
+    /**
+     * Get a reference to the `T`, first storing the result of `f` if the slot is empty.
+     */
+    pub fn get_or_insert_with<F>(&mut self, f: F) -> &mut T
+    where
+        F: FnOnce() -> T,
+    {
+        if self.is_none() {
+            self.insert(f());
+        }
+
+        self.as_mut().expect("just inserted")
+    }
+
+    /**
+     * Convert to an `Option<T>`.
+     */
+    pub fn into_option(self) -> Option<T> {
+        let this = ManuallyDrop::new(self);
+
+        // SAFETY: `this` is never dropped, so ownership of the storage moves to `erased`, which
+        // holds the type we read out
+        unsafe {
+            let erased = ptr::read(&this.erased);
+
+            if Self::INLINE {
+                erased.into_inner()
+            } else {
+                erased.into_inner::<Option<Box<T>>>().map(|b| *b)
+            }
+        }
+    }
+}
+
+// SAFETY: we own a T, so are as thread-safe as it is
+unsafe impl<T: Send> Send for OptionTrident<T> {}
+unsafe impl<T: Sync> Sync for OptionTrident<T> {}
+
+impl<T> Drop for OptionTrident<T> {
+    fn drop(&mut self) {
+        // SAFETY: we store the type we drop, see the accessors
+        unsafe {
+            if Self::INLINE {
+                ptr::drop_in_place(self.inline_mut());
+            } else {
+                ptr::drop_in_place(self.boxed_mut());
+            }
+        }
+    }
+}
+
+impl<T> Default for OptionTrident<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> From<Option<T>> for OptionTrident<T> {
+    fn from(o: Option<T>) -> Self {
+        Self::from_option(o)
+    }
+}
+
+impl<T> From<T> for OptionTrident<T> {
+    fn from(t: T) -> Self {
+        Self::from_option(Some(t))
+    }
+}
+
+impl<T> Clone for OptionTrident<T>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        Self::from_option(self.as_ref().cloned())
+    }
+}
+
+impl<T> fmt::Debug for OptionTrident<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.as_ref(), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Trident;
+    use std::cell::Cell;
+
+    #[test]
+    fn no_extra_word() {
+        let size = mem::size_of::<Trident<u8>>();
+
+        assert_eq!(mem::size_of::<OptionTrident<u8>>(), size);
+        assert_eq!(mem::size_of::<OptionTrident<[usize; 3]>>(), size);
+        assert_eq!(mem::size_of::<OptionTrident<[usize; 8]>>(), size);
+    }
+
+    #[test]
+    fn storage() {
+        const {
+            assert!(OptionTrident::<u8>::INLINE);
+            assert!(OptionTrident::<String>::INLINE);
+            assert!(!OptionTrident::<[usize; 3]>::INLINE);
+            assert!(!OptionTrident::<[usize; 8]>::INLINE);
+        }
+    }
+
+    #[test]
+    fn take_insert() {
+        let mut small = OptionTrident::new();
+        let mut large = OptionTrident::new();
+
+        assert!(small.is_none() && large.is_none());
+        assert_eq!(small.take(), None);
+
+        *small.insert(1u8) += 1;
+        *large.insert([1usize; 8]) = [2; 8];
+        assert!(small.is_some() && large.is_some());
+
+        assert_eq!(small.take(), Some(2));
+        assert_eq!(large.take(), Some([2; 8]));
+        assert!(small.is_none() && large.is_none());
+    }
+
+    #[test]
+    fn insert_reuses_box() {
+        let mut o = OptionTrident::from([1usize; 8]);
+        let ptr = o.as_ref().unwrap() as *const _;
+
+        o.insert([2; 8]);
+        assert_eq!(o.as_ref().unwrap() as *const _, ptr);
+        assert_eq!(o.replace([3; 8]), Some([2; 8]));
+        assert_eq!(o.into_option(), Some([3; 8]));
+    }
+
+    #[test]
+    fn get_or_insert_with() {
+        let mut o = OptionTrident::<[usize; 3]>::default();
+
+        assert_eq!(*o.get_or_insert_with(|| [1; 3]), [1; 3]);
+        assert_eq!(*o.get_or_insert_with(|| unreachable!()), [1; 3]);
+        assert_eq!(format!("{:?}", o), "Some([1, 1, 1])");
+    }
+
+    #[test]
+    fn drops() {
+        struct Dtor<'a>(&'a Cell<u32>, #[allow(dead_code)] [usize; 3]);
+
+        impl Drop for Dtor<'_> {
+            fn drop(&mut self) {
+                self.0.set(self.0.get() + 1);
+            }
+        }
+
+        let drops = Cell::new(0);
+        let mut o = OptionTrident::from(Dtor(&drops, [0; 3]));
+        o.insert(Dtor(&drops, [1; 3]));
+        assert_eq!(drops.get(), 1);
+
+        drop(o);
+        drop(OptionTrident::<Dtor>::new());
+        assert_eq!(drops.get(), 2);
+    }
+}
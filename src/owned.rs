@@ -0,0 +1,186 @@
+use std::{mem::ManuallyDrop, ptr};
+
+use crate::{Erased, Trident};
+
+/**
+ * A type-erased `T`, as `Erased`, which also records how to drop it.
+ *
+ * When an `OwnedErased` is dropped, so is the `T`, and any allocation is freed. This costs a word
+ * for the drop function.
+ *
+ * `T` must be `'static`, as an `OwnedErased` has no lifetime to stop it outliving anything the `T`
+ * borrows.
+ */
+pub struct OwnedErased {
+    erased: Erased,
+    drop: unsafe fn(&mut Erased),
+}
+
+impl OwnedErased {
+    /**
+     * Create an `OwnedErased` from a `T`.
+     */
+    pub fn new<T: 'static>(t: T) -> Self {
+        Self::from_trident(Trident::new(t))
+    }
+
+    /**
+     * Create an `OwnedErased` from a `Trident<T>`, keeping its storage.
+     */
+    pub fn from_trident<T: 'static>(t: Trident<T>) -> Self {
+        Self {
+            erased: t.into_erased(),
+            drop: drop_erased::<T>,
+        }
+    }
+
+    /**
+     * Get a reference to the contained `T`.
+     *
+     * # Safety
+     *
+     * Unsafe because we don't know that this is the same `T` that this `OwnedErased` was created
+     * with.
+     */
+    pub unsafe fn as_ref<T>(&self) -> &T {
+        self.erased.as_ref()
+    }
+
+    /**
+     * Get a mutable reference to the contained `T`.
+     *
+     * # Safety
+     *
+     * Unsafe because we don't know that this is the same `T` that this `OwnedErased` was created
+     * with.
+     */
+    pub unsafe fn as_mut_ref<T>(&mut self) -> &mut T {
+        self.erased.as_mut_ref()
+    }
+
+    /**
+     * Get the contained `T`.
+     *
+     * # Safety
+     *
+     * Unsafe because we don't know that this is the same `T` that this `OwnedErased` was created
+     * with.
+     */
+    pub unsafe fn into_inner<T>(self) -> T {
+        self.into_erased().into_inner()
+    }
+
+    /**
+     * Convert to a `Trident<T>`.
+     *
+     * # Safety
+     *
+     * Unsafe because we don't know that this is the same `T` that this `OwnedErased` was created
+     * with.
+     */
+    pub unsafe fn into_trident<T>(self) -> Trident<T> {
+        self.into_erased().into_trident()
+    }
+
+    /**
+     * Convert to an `Erased`, which won't drop the `T`.
+     */
+    pub fn into_erased(self) -> Erased {
+        let this = ManuallyDrop::new(self);
+
+        // SAFETY: `this` is never dropped, so ownership of the storage moves to the returned Erased
+        unsafe { ptr::read(&this.erased) }
+    }
+}
+
+impl Drop for OwnedErased {
+    fn drop(&mut self) {
+        // SAFETY: `drop` was created for the T we hold
+        unsafe { (self.drop)(&mut self.erased) }
+    }
+}
+
+impl<T: 'static> From<Trident<T>> for OwnedErased {
+    fn from(t: Trident<T>) -> Self {
+        Self::from_trident(t)
+    }
+}
+
+/**
+ * Drop the `T` held by `erased`, freeing any allocation.
+ *
+ * Unsafe because `erased` must hold a `T`, which mustn't be used again.
+ */
+pub(crate) unsafe fn drop_erased<T>(erased: &mut Erased) {
+    drop(ptr::read(erased).into_trident::<T>());
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use super::*;
+
+    struct Dtor<Payload> {
+        payload: Payload,
+        drops: Rc<Cell<u32>>,
+    }
+
+    impl<Payload> Drop for Dtor<Payload> {
+        fn drop(&mut self) {
+            self.drops.set(self.drops.get() + 1);
+        }
+    }
+
+    #[test]
+    fn drops_inline() {
+        let drops = Rc::new(Cell::new(0));
+
+        let erased = OwnedErased::new(Dtor {
+            payload: 1u8,
+            drops: drops.clone(),
+        });
+        assert_eq!(unsafe { erased.as_ref::<Dtor<u8>>() }.payload, 1);
+
+        drop(erased);
+        assert_eq!(drops.get(), 1);
+    }
+
+    #[test]
+    fn drops_spilled() {
+        let drops = Rc::new(Cell::new(0));
+
+        let mut erased = OwnedErased::new(Dtor {
+            payload: [1usize; 8],
+            drops: drops.clone(),
+        });
+        unsafe { erased.as_mut_ref::<Dtor<[usize; 8]>>() }.payload[0] = 2;
+
+        let mut spilled = Trident::new(Dtor {
+            payload: 3u8,
+            drops: drops.clone(),
+        });
+        spilled.spill_to_heap();
+        let spilled = OwnedErased::from(spilled);
+
+        drop(erased);
+        drop(spilled);
+        assert_eq!(drops.get(), 2);
+    }
+
+    #[test]
+    fn into_inner_doesnt_drop() {
+        let drops = Rc::new(Cell::new(0));
+
+        let erased = OwnedErased::new(Dtor {
+            payload: [1usize; 8],
+            drops: drops.clone(),
+        });
+        let t = unsafe { erased.into_inner::<Dtor<[usize; 8]>>() };
+        assert_eq!(drops.get(), 0);
+        assert_eq!(t.payload, [1; 8]);
+
+        drop(t);
+        assert_eq!(drops.get(), 1);
+    }
+}
@@ -0,0 +1,156 @@
+use std::{
+    alloc::Layout,
+    cell::UnsafeCell,
+    mem::{self, MaybeUninit},
+    ptr,
+};
+
+use crate::limits::NWORDS;
+use crate::Trident;
+
+/**
+ * The words left for a payload, after a vtable pointer.
+ */
+const PAYLOAD_WORDS: usize = NWORDS - 1;
+
+/**
+ * Two words holding a value inline, if it fits, otherwise a pointer to it, boxed.
+ *
+ * Like `Erased`, it doesn't know the type it holds. It's paired with a vtable pointer, which
+ * does, to make up the three words of `SmallFn`, `SmallFuture` and the like.
+ *
+ * Public only for `small_dyn!`, which expands in other crates.
+ */
+#[doc(hidden)]
+pub struct Payload {
+    // an UnsafeCell, as an inline value may be mutated through a shared `Payload`, e.g. a `Fn`
+    // capturing a Cell
+    words: UnsafeCell<[MaybeUninit<usize>; PAYLOAD_WORDS]>,
+}
+
+/**
+ * Whether a `T` is stored inline in a `Payload`.
+ */
+pub(crate) const fn inlines<T>() -> bool {
+    inlines_layout(Layout::new::<T>())
+}
+
+/**
+ * As `inlines`, for a value of the given layout.
+ */
+pub(crate) const fn inlines_layout(layout: Layout) -> bool {
+    layout.size() <= mem::size_of::<[usize; PAYLOAD_WORDS]>()
+        && layout.align() <= mem::align_of::<usize>()
+}
+
+impl Payload {
+    /**
+     * Create a `Payload` holding `t`, boxed if it doesn't fit.
+     */
+    pub fn new<T>(t: T) -> Self {
+        let mut words = [MaybeUninit::uninit(); PAYLOAD_WORDS];
+
+        // SAFETY: either T or a pointer to it fits, and the words are aligned for both
+        unsafe {
+            if inlines::<T>() {
+                ptr::write(words.as_mut_ptr() as *mut T, t);
+            } else {
+                ptr::write(
+                    words.as_mut_ptr() as *mut *mut T,
+                    Box::into_raw(Box::new(t)),
+                );
+            }
+        }
+
+        Self {
+            words: UnsafeCell::new(words),
+        }
+    }
+
+    /**
+     * Create a `Payload` from a boxed `T`, reusing its allocation if the `T` isn't inline.
+     */
+    pub fn from_box<T>(t: Box<T>) -> Self {
+        if inlines::<T>() {
+            return Self::new(*t);
+        }
+
+        let mut words = [MaybeUninit::uninit(); PAYLOAD_WORDS];
+
+        // SAFETY: a pointer fits, and the words are aligned for it
+        unsafe {
+            ptr::write(words.as_mut_ptr() as *mut *mut T, Box::into_raw(t));
+        }
+
+        Self {
+            words: UnsafeCell::new(words),
+        }
+    }
+
+    /**
+     * Create a `Payload` from a `Trident<T>`, reusing its allocation if the `T` is boxed in both.
+     */
+    pub fn from_trident<T>(t: Trident<T>) -> Self {
+        if inlines::<T>() {
+            Self::new(t.into_inner())
+        } else {
+            Self::from_box(t.into_box())
+        }
+    }
+
+    /**
+     * Find the `T` in the `Payload` at `this`.
+     *
+     * # Safety
+     *
+     * `this` must point to a `Payload` holding a `T`.
+     */
+    pub unsafe fn resolve<T>(this: *mut Payload) -> *mut T {
+        let words = UnsafeCell::raw_get(ptr::addr_of!((*this).words));
+
+        if inlines::<T>() {
+            words.cast()
+        } else {
+            *(words as *const *mut T)
+        }
+    }
+
+    /**
+     * Move the `T` out of the `Payload` at `this`, freeing it if boxed.
+     *
+     * Unsafe because `this` must point to a `Payload` holding a `T`, which isn't used again.
+     */
+    pub(crate) unsafe fn take<T>(this: *mut Payload) -> T {
+        let t = Self::resolve::<T>(this);
+
+        if inlines::<T>() {
+            ptr::read(t)
+        } else {
+            *Box::from_raw(t)
+        }
+    }
+
+    /**
+     * Move the `T` out of the `Payload` at `this` to a box, reusing its allocation if boxed.
+     *
+     * Unsafe because `this` must point to a `Payload` holding a `T`, which isn't used again.
+     */
+    pub(crate) unsafe fn take_box<T>(this: *mut Payload) -> Box<T> {
+        if inlines::<T>() {
+            Box::new(Self::take(this))
+        } else {
+            Box::from_raw(Self::resolve(this))
+        }
+    }
+
+    /**
+     * Drop the `T` in the `Payload` at `this`, freeing it if boxed.
+     *
+     * # Safety
+     *
+     * `this` must point to a `Payload` holding a `T`, which isn't used again.
+     */
+    pub unsafe fn drop_in_place<T>(this: *mut Payload) {
+        drop(Self::take::<T>(this));
+    }
+}
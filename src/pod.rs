@@ -0,0 +1,165 @@
+/**
+ * Plain-old-data erased storage, for payloads which can be treated as bytes.
+ */
+use std::{mem, ptr};
+
+use bytemuck::{Pod, Zeroable};
+
+use crate::limits::{NWORDS, SIZE_LIMIT};
+use crate::Erased;
+
+/**
+ * An erased `T: Pod`, always stored inline.
+ *
+ * As the payload is plain bytes, `PodErased` itself is `Pod`, so it can be copied into and out of
+ * buffers freely, and reading it back as any `Pod` type is safe.
+ */
+#[derive(Clone, Copy, Zeroable, Pod, PartialEq, Eq, Hash, Debug)]
+#[repr(C)]
+pub struct PodErased {
+    words: [usize; NWORDS],
+}
+
+impl PodErased {
+    /**
+     * Create a `PodErased` from a `T`.
+     *
+     * This is a `const fn`, so can be used to build tables of erased constants in a `static`.
+     *
+     * # Panics
+     *
+     * Panics (at compile time, if evaluated in a const context) if `T` is too large to be stored
+     * inline.
+     */
+    pub const fn new<T: Pod>(t: T) -> Self {
+        assert!(
+            mem::size_of::<T>() <= SIZE_LIMIT,
+            "type doesn't fit in a PodErased"
+        );
+
+        let mut words = [0; NWORDS];
+
+        // SAFETY: T fits, and may be unaligned. As it's Pod, it has no padding, so every byte of
+        // the words remains initialised
+        unsafe {
+            ptr::write_unaligned(&mut words as *mut _ as *mut T, t);
+        }
+
+        Self { words }
+    }
+
+    /**
+     * Create a `PodErased` from an `Erased` holding a `T`.
+     *
+     * # Safety
+     *
+     * Unsafe because we don't know that this is the same `T` that `erased` was created with.
+     */
+    pub unsafe fn from_erased<T: Pod>(erased: Erased) -> Self {
+        Self::new(erased.into_inner::<T>())
+    }
+
+    /**
+     * Read the payload as a `T`.
+     *
+     * This is safe for any `T: Pod`, though will only give a meaningful value for the `T` that this
+     * `PodErased` was created with. Any bytes beyond the original payload are zero.
+     *
+     * # Panics
+     *
+     * Panics if `T` is too large to be stored inline.
+     */
+    pub fn get<T: Pod>(&self) -> T {
+        bytemuck::pod_read_unaligned(&bytemuck::bytes_of(self)[..mem::size_of::<T>()])
+    }
+
+    /**
+     * Get the bytes of the payload, for example to write to a pipe or shared memory.
+     *
+     * These are in native byte order and word size, so should only be read back by `from_bytes`
+     * on the same platform.
+     */
+    pub fn as_bytes(&self) -> &[u8; SIZE_LIMIT] {
+        bytemuck::cast_ref(&self.words)
+    }
+
+    /**
+     * Create a `PodErased` from bytes returned by `as_bytes`, which needn't be aligned.
+     *
+     * Returns `None` if `bytes` isn't the size of a `PodErased`.
+     */
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() == SIZE_LIMIT {
+            Some(bytemuck::pod_read_unaligned(bytes))
+        } else {
+            None
+        }
+    }
+
+    /**
+     * Convert to an `Erased` holding a `T`.
+     */
+    pub fn into_erased<T: Pod>(self) -> Erased {
+        Erased::new(self.get::<T>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PodErased;
+
+    #[test]
+    fn round_trips_through_bytes() {
+        let erased = PodErased::new([1u16, 2, 3]);
+
+        let mut buf = vec![0u8; 2 * std::mem::size_of::<PodErased>()];
+        buf[..std::mem::size_of::<PodErased>()].copy_from_slice(bytemuck::bytes_of(&erased));
+
+        let copy: PodErased =
+            bytemuck::pod_read_unaligned(&buf[..std::mem::size_of::<PodErased>()]);
+        assert_eq!(copy.get::<[u16; 3]>(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn converts_to_and_from_erased() {
+        let erased = PodErased::new(0x1234_5678u32).into_erased::<u32>();
+
+        // SAFETY: created with a u32
+        let pod = unsafe { PodErased::from_erased::<u32>(erased) };
+        assert_eq!(pod.get::<u32>(), 0x1234_5678);
+    }
+
+    #[test]
+    fn round_trips_as_bytes() {
+        let erased = PodErased::new([7u32, 8]);
+
+        let mut ring = [0u8; 64];
+        let offset = 3;
+        ring[offset..][..erased.as_bytes().len()].copy_from_slice(erased.as_bytes());
+
+        let copy = PodErased::from_bytes(&ring[offset..][..erased.as_bytes().len()]).unwrap();
+        assert_eq!(copy, erased);
+        assert_eq!(copy.get::<[u32; 2]>(), [7, 8]);
+
+        assert_eq!(PodErased::from_bytes(&ring[..5]), None);
+    }
+
+    static TABLE: [PodErased; 3] = [
+        PodErased::new(1u8),
+        PodErased::new([2u16; 3]),
+        PodErased::new([3.5f32, 4.5]),
+    ];
+
+    #[test]
+    fn builds_in_const_context() {
+        assert_eq!(TABLE[0].get::<u8>(), 1);
+        assert_eq!(TABLE[1].get::<[u16; 3]>(), [2; 3]);
+        assert_eq!(TABLE[2].get::<[f32; 2]>(), [3.5, 4.5]);
+    }
+
+    #[test]
+    #[should_panic(expected = "type doesn't fit in a PodErased")]
+    fn rejects_large_types() {
+        PodErased::new([0u64; 4]);
+    }
+}
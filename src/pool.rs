@@ -0,0 +1,213 @@
+/**
+ * A pool which recycles the allocations of spilled values, to avoid the global allocator.
+ */
+use std::{
+    alloc::{self, Layout},
+    cell::RefCell,
+    fmt,
+    ptr::NonNull,
+};
+
+use allocator_api2::alloc::{AllocError, Allocator, Global};
+
+use crate::{Trident, TridentIn};
+
+/**
+ * A pool of blocks for values spilled from `TridentIn`s, recycled rather than freed.
+ *
+ * `&TridentPool` is an allocator, so `Trident::new_pooled(t, &pool)` creates a
+ * `TridentIn<T, &TridentPool>`, which borrows the pool. When it's dropped, its block returns to the
+ * pool, for the next value of a similar size, so once the pool is warm, or filled up front with
+ * `reserve`, spilling needn't call the global allocator, for example on an audio thread.
+ *
+ * Blocks are grouped in power-of-two size classes, from 32 bytes to 4KiB. Larger values are
+ * allocated and freed as usual. Blocks are only freed when the pool is dropped.
+ */
+#[derive(Default)]
+pub struct TridentPool {
+    // free blocks, indexed by size class
+    free: RefCell<Vec<Vec<NonNull<u8>>>>,
+}
+
+const MIN_CLASS_SHIFT: u32 = 5;
+const MAX_CLASS_SHIFT: u32 = 12;
+
+impl<T> Trident<T> {
+    /**
+     * Create a `TridentIn<T, &TridentPool>`, taking a block from `pool` if `T` is too large to be
+     * stored inline.
+     */
+    pub fn new_pooled(t: T, pool: &TridentPool) -> TridentIn<T, &TridentPool> {
+        TridentIn::new_in(t, pool)
+    }
+}
+
+impl TridentPool {
+    /**
+     * Create an empty pool, which allocates nothing until a value is spilled into it.
+     */
+    pub const fn new() -> Self {
+        Self {
+            free: RefCell::new(Vec::new()),
+        }
+    }
+
+    /**
+     * Fill the pool with blocks for `count` more `T`s, so they can be spilled without allocating.
+     *
+     * Does nothing if `T` is too large to be pooled.
+     */
+    pub fn reserve<T>(&self, count: usize) {
+        let Some(class) = size_class(Layout::new::<T>()) else {
+            return;
+        };
+
+        let blocks = (0..count).map(|_| allocate_block(class).expect("allocation failed"));
+        self.free_list(class, |list| list.extend(blocks));
+    }
+
+    /**
+     * The number of free blocks, across all size classes.
+     */
+    pub fn free_blocks(&self) -> usize {
+        self.free.borrow().iter().map(Vec::len).sum()
+    }
+
+    fn free_list<R>(&self, class: u32, f: impl FnOnce(&mut Vec<NonNull<u8>>) -> R) -> R {
+        let mut free = self.free.borrow_mut();
+        let index = (class - MIN_CLASS_SHIFT) as usize;
+
+        if free.len() <= index {
+            free.resize_with(index + 1, Vec::new);
+        }
+        f(&mut free[index])
+    }
+}
+
+/**
+ * The size class of a block which fits `layout`, as a power of two, if it's small enough to pool.
+ */
+fn size_class(layout: Layout) -> Option<u32> {
+    let size = layout
+        .size()
+        .max(layout.align())
+        .max(1 << MIN_CLASS_SHIFT)
+        .checked_next_power_of_two()?;
+    let class = size.trailing_zeros();
+
+    (class <= MAX_CLASS_SHIFT).then_some(class)
+}
+
+/**
+ * The layout of a block in `class`, aligned to its size so it fits any layout in the class.
+ */
+fn block_layout(class: u32) -> Layout {
+    Layout::from_size_align(1 << class, 1 << class).unwrap()
+}
+
+fn allocate_block(class: u32) -> Result<NonNull<u8>, AllocError> {
+    // SAFETY: the layout is non-zero in size
+    NonNull::new(unsafe { alloc::alloc(block_layout(class)) }).ok_or(AllocError)
+}
+
+unsafe impl Allocator for &TridentPool {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let Some(class) = size_class(layout) else {
+            return Global.allocate(layout);
+        };
+
+        let ptr = match self.free_list(class, Vec::pop) {
+            Some(ptr) => ptr,
+            None => allocate_block(class)?,
+        };
+        Ok(NonNull::slice_from_raw_parts(ptr, 1 << class))
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        match size_class(layout) {
+            Some(class) => self.free_list(class, |list| list.push(ptr)),
+            None => Global.deallocate(ptr, layout),
+        }
+    }
+}
+
+impl Drop for TridentPool {
+    fn drop(&mut self) {
+        for (index, list) in self.free.get_mut().iter().enumerate() {
+            let layout = block_layout(index as u32 + MIN_CLASS_SHIFT);
+
+            for ptr in list {
+                // SAFETY: allocated with this layout in allocate_block()
+                unsafe { alloc::dealloc(ptr.as_ptr(), layout) }
+            }
+        }
+    }
+}
+
+impl fmt::Debug for TridentPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TridentPool")
+            .field("free_blocks", &self.free_blocks())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recycles_blocks() {
+        let pool = TridentPool::new();
+
+        let a = Trident::new_pooled([1u64; 8], &pool);
+        let a_ptr = a.as_ptr() as *const u8;
+        drop(a);
+        assert_eq!(pool.free_blocks(), 1);
+
+        // a different type, in the same 64-byte class
+        let b = Trident::new_pooled([2u32; 12], &pool);
+        assert_eq!(b.as_ptr() as *const u8, a_ptr);
+        assert_eq!(pool.free_blocks(), 0);
+        assert_eq!(b[11], 2);
+
+        let small = Trident::new_pooled(3u8, &pool);
+        let huge = Trident::new_pooled([4u8; 5000], &pool);
+        drop((b, small, huge));
+        assert_eq!(pool.free_blocks(), 1);
+    }
+
+    #[test]
+    fn reserves() {
+        let pool = TridentPool::new();
+        pool.reserve::<[String; 4]>(3);
+        pool.reserve::<[u8; 10_000]>(3);
+        assert_eq!(pool.free_blocks(), 3);
+
+        let values = (0..3)
+            .map(|i| {
+                Trident::new_pooled(
+                    [i.to_string(), String::new(), String::new(), String::new()],
+                    &pool,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(pool.free_blocks(), 0);
+        assert_eq!(values[2][0], "2");
+
+        drop(values);
+        assert_eq!(pool.free_blocks(), 3);
+    }
+
+    #[test]
+    fn size_classes() {
+        assert_eq!(size_class(Layout::new::<[u8; 1]>()), Some(5));
+        assert_eq!(size_class(Layout::new::<[u8; 33]>()), Some(6));
+        assert_eq!(
+            size_class(Layout::from_size_align(8, 128).unwrap()),
+            Some(7)
+        );
+        assert_eq!(size_class(Layout::new::<[u8; 4096]>()), Some(12));
+        assert_eq!(size_class(Layout::new::<[u8; 4097]>()), None);
+    }
+}
@@ -0,0 +1,121 @@
+/**
+ * `proptest` support: an `Arbitrary` implementation and strategies generating tridents either
+ * side of the inline/boxed boundary.
+ */
+use proptest::{
+    arbitrary::{any, Arbitrary},
+    prop_oneof,
+    strategy::{BoxedStrategy, Map, Strategy},
+};
+
+use crate::limits::SIZE_LIMIT;
+use crate::Trident;
+
+/**
+ * A strategy generating `Trident`s from the values of `S`.
+ */
+pub type TridentStrategy<S> = Map<S, fn(<S as Strategy>::Value) -> Trident<<S as Strategy>::Value>>;
+
+impl<T> Arbitrary for Trident<T>
+where
+    T: Arbitrary,
+{
+    type Parameters = T::Parameters;
+    type Strategy = TridentStrategy<T::Strategy>;
+
+    fn arbitrary_with(args: T::Parameters) -> Self::Strategy {
+        T::arbitrary_with(args).prop_map(Trident::new)
+    }
+}
+
+/**
+ * Generate `Trident`s holding values generated by `strategy`.
+ */
+pub fn trident_strategy<S>(strategy: S) -> TridentStrategy<S>
+where
+    S: Strategy,
+{
+    strategy.prop_map(Trident::new)
+}
+
+/**
+ * A `Trident` of a payload whose size straddles the inline/boxed boundary.
+ */
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum AnySizePayload {
+    /// Smaller than the inline limit.
+    Small(Trident<[u8; SIZE_LIMIT - 1]>),
+    /// Exactly the inline limit, the largest inline payload.
+    Limit(Trident<[u8; SIZE_LIMIT]>),
+    /// One byte over the inline limit, the smallest boxed payload.
+    OverLimit(Trident<[u8; SIZE_LIMIT + 1]>),
+    /// Well over the inline limit.
+    Large(Trident<[u8; 4 * SIZE_LIMIT]>),
+}
+
+impl AnySizePayload {
+    /**
+     * Whether the payload is stored inline.
+     */
+    pub fn is_inline(&self) -> bool {
+        matches!(self, Self::Small(_) | Self::Limit(_))
+    }
+
+    /**
+     * The payload's bytes.
+     */
+    pub fn bytes(&self) -> &[u8] {
+        match self {
+            Self::Small(t) => &t[..],
+            Self::Limit(t) => &t[..],
+            Self::OverLimit(t) => &t[..],
+            Self::Large(t) => &t[..],
+        }
+    }
+}
+
+/**
+ * Generate `AnySizePayload`s, covering payloads on either side of the inline/boxed boundary.
+ */
+pub fn any_size_payload() -> BoxedStrategy<AnySizePayload> {
+    prop_oneof![
+        trident_strategy(any::<[u8; SIZE_LIMIT - 1]>()).prop_map(AnySizePayload::Small),
+        trident_strategy(any::<[u8; SIZE_LIMIT]>()).prop_map(AnySizePayload::Limit),
+        trident_strategy(any::<[u8; SIZE_LIMIT + 1]>()).prop_map(AnySizePayload::OverLimit),
+        trident_strategy(any::<[u8; 4 * SIZE_LIMIT]>()).prop_map(AnySizePayload::Large),
+    ]
+    .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::{any_size_payload, AnySizePayload};
+    use crate::{limits, Trident};
+
+    #[test]
+    fn payloads_straddle_boundary() {
+        const { assert!(Trident::<[u8; limits::SIZE_LIMIT]>::IS_INLINE) }
+        const { assert!(!Trident::<[u8; limits::SIZE_LIMIT + 1]>::IS_INLINE) }
+    }
+
+    proptest! {
+        #[test]
+        fn clones_any_size(payload in any_size_payload()) {
+            let cloned = payload.clone();
+
+            prop_assert_eq!(cloned.bytes(), payload.bytes());
+            prop_assert_eq!(cloned.is_inline(), !matches!(
+                payload,
+                AnySizePayload::OverLimit(_) | AnySizePayload::Large(_)
+            ));
+        }
+
+        #[test]
+        fn round_trips_arbitrary(t in any::<Trident<(u64, u64, u64, u64)>>()) {
+            let inner = *t;
+            prop_assert_eq!(t.into_inner(), inner);
+        }
+    }
+}
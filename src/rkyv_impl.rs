@@ -0,0 +1,116 @@
+/**
+ * `rkyv` support: a `Trident<T>` archives transparently as `T`'s archived form, wrapped in an
+ * `ArchivedTrident` so it can be deserialized back into a `Trident<T>`.
+ */
+use std::ops::Deref;
+
+use rkyv::{
+    bytecheck::CheckBytes, rancor::Fallible, traits::CopyOptimization, Archive, Deserialize, Place,
+    Portable, Serialize,
+};
+
+use crate::Trident;
+
+/**
+ * The archived form of a `Trident<T>`, laid out exactly as `T::Archived`.
+ */
+#[derive(Portable, CheckBytes, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[rkyv(crate = rkyv)]
+#[bytecheck(crate = rkyv::bytecheck)]
+#[repr(transparent)]
+pub struct ArchivedTrident<A>(A);
+
+impl<A> ArchivedTrident<A> {
+    /**
+     * Get a reference to the archived `T`.
+     */
+    pub fn get(&self) -> &A {
+        &self.0
+    }
+}
+
+impl<A> Deref for ArchivedTrident<A> {
+    type Target = A;
+
+    fn deref(&self) -> &A {
+        &self.0
+    }
+}
+
+impl<T> Archive for Trident<T>
+where
+    T: Archive,
+{
+    const COPY_OPTIMIZATION: CopyOptimization<Self> = CopyOptimization::disable();
+
+    type Archived = ArchivedTrident<T::Archived>;
+    type Resolver = T::Resolver;
+
+    fn resolve(&self, resolver: T::Resolver, out: Place<Self::Archived>) {
+        // SAFETY: ArchivedTrident is repr(transparent) over T::Archived
+        let out = unsafe { out.cast_unchecked::<T::Archived>() };
+
+        self.as_ref().resolve(resolver, out)
+    }
+}
+
+impl<T, S> Serialize<S> for Trident<T>
+where
+    T: Serialize<S>,
+    S: Fallible + ?Sized,
+{
+    fn serialize(&self, serializer: &mut S) -> Result<T::Resolver, S::Error> {
+        self.as_ref().serialize(serializer)
+    }
+}
+
+impl<T, D> Deserialize<Trident<T>, D> for ArchivedTrident<T::Archived>
+where
+    T: Archive,
+    T::Archived: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize(&self, deserializer: &mut D) -> Result<Trident<T>, D::Error> {
+        self.0.deserialize(deserializer).map(Trident::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rkyv::{rancor::Error, Archive, Deserialize, Serialize};
+
+    use crate::Trident;
+
+    #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+    #[rkyv(crate = rkyv)]
+    struct Asset {
+        id: u32,
+        small: Trident<(u16, u16)>,
+        large: Trident<[u64; 8]>,
+        name: Trident<String>,
+    }
+
+    #[test]
+    fn round_trips_through_archive() {
+        const { assert!(Trident::<(u16, u16)>::IS_INLINE) }
+        const { assert!(!Trident::<[u64; 8]>::IS_INLINE) }
+
+        let asset = Asset {
+            id: 3,
+            small: Trident::new((1, 2)),
+            large: Trident::new([9; 8]),
+            name: Trident::new("tree".to_string()),
+        };
+
+        let bytes = rkyv::to_bytes::<Error>(&asset).unwrap();
+
+        let archived = rkyv::access::<ArchivedAsset, Error>(&bytes).unwrap();
+        assert_eq!(archived.id, 3);
+        assert_eq!(archived.small.get().1, 2);
+        assert_eq!(archived.large[7], 9);
+        assert_eq!(archived.name.as_str(), "tree");
+
+        let back = rkyv::deserialize::<Asset, Error>(archived).unwrap();
+        assert_eq!(back, asset);
+    }
+}
@@ -0,0 +1,178 @@
+use crate::{Erased, Trident};
+
+/**
+ * An `Erased` whose payload is known to be `Send`, so which is itself `Send`.
+ *
+ * For example, to pass erased payloads through a channel. The payload is only reachable through
+ * `&self`, or unsafely, so it can't be swapped for one which isn't `Send`.
+ */
+pub struct ErasedSend(Erased);
+
+/**
+ * An `Erased` whose payload is known to be `Send` and `Sync`, so which is itself both.
+ *
+ * As with `ErasedSend`, the payload can't be safely swapped for another.
+ */
+pub struct ErasedSync(Erased);
+
+// SAFETY: constructed only with Send payloads
+unsafe impl Send for ErasedSend {}
+
+// SAFETY: constructed only with Send + Sync payloads
+unsafe impl Send for ErasedSync {}
+unsafe impl Sync for ErasedSync {}
+
+impl ErasedSend {
+    /**
+     * Create an `ErasedSend` from a `T`.
+     */
+    pub fn new<T: Send>(t: T) -> Self {
+        Self(Trident::new(t).into_erased())
+    }
+
+    /**
+     * Create an `ErasedSend` from an `Erased`.
+     *
+     * # Safety
+     *
+     * The payload of `erased` must be `Send`.
+     */
+    pub unsafe fn from_erased(erased: Erased) -> Self {
+        Self(erased)
+    }
+
+    /**
+     * Get the `Erased`.
+     */
+    pub fn as_erased(&self) -> &Erased {
+        &self.0
+    }
+
+    /**
+     * Get the `Erased`, mutably.
+     *
+     * # Safety
+     *
+     * The payload must not be replaced with one which isn't `Send`.
+     */
+    pub unsafe fn as_erased_mut(&mut self) -> &mut Erased {
+        &mut self.0
+    }
+
+    /**
+     * Convert to the `Erased`.
+     */
+    pub fn into_erased(self) -> Erased {
+        self.0
+    }
+}
+
+impl ErasedSync {
+    /**
+     * Create an `ErasedSync` from a `T`.
+     */
+    pub fn new<T: Send + Sync>(t: T) -> Self {
+        Self(Trident::new(t).into_erased())
+    }
+
+    /**
+     * Create an `ErasedSync` from an `Erased`.
+     *
+     * # Safety
+     *
+     * The payload of `erased` must be `Send` and `Sync`.
+     */
+    pub unsafe fn from_erased(erased: Erased) -> Self {
+        Self(erased)
+    }
+
+    /**
+     * Get the `Erased`.
+     */
+    pub fn as_erased(&self) -> &Erased {
+        &self.0
+    }
+
+    /**
+     * Get the `Erased`, mutably.
+     *
+     * # Safety
+     *
+     * The payload must not be replaced with one which isn't `Send` and `Sync`.
+     */
+    pub unsafe fn as_erased_mut(&mut self) -> &mut Erased {
+        &mut self.0
+    }
+
+    /**
+     * Convert to the `Erased`.
+     */
+    pub fn into_erased(self) -> Erased {
+        self.0
+    }
+}
+
+impl From<ErasedSync> for ErasedSend {
+    fn from(erased: ErasedSync) -> Self {
+        Self(erased.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::mpsc, thread};
+
+    use super::*;
+
+    fn assert_send<T: Send>() {}
+    fn assert_sync<T: Sync>() {}
+
+    #[test]
+    fn thread_safety() {
+        assert_send::<ErasedSend>();
+        assert_send::<ErasedSync>();
+        assert_sync::<ErasedSync>();
+
+        assert_send::<Trident<String>>();
+        assert_sync::<Trident<[u64; 8]>>();
+    }
+
+    #[test]
+    fn sends_through_channel() {
+        let (tx, rx) = mpsc::channel();
+
+        thread::spawn(move || {
+            tx.send(ErasedSend::new(String::from("hello"))).unwrap();
+            tx.send(ErasedSend::new([1u64; 8])).unwrap();
+        });
+
+        unsafe {
+            assert_eq!(
+                rx.recv().unwrap().into_erased().into_inner::<String>(),
+                "hello"
+            );
+            assert_eq!(
+                rx.recv().unwrap().into_erased().into_inner::<[u64; 8]>(),
+                [1; 8]
+            );
+        }
+    }
+
+    #[test]
+    fn shares_between_threads() {
+        let erased = ErasedSync::new(vec![1, 2, 3]);
+
+        let sum = thread::scope(|s| {
+            s.spawn(|| {
+                unsafe { erased.as_erased().as_ref::<Vec<i32>>() }
+                    .iter()
+                    .sum::<i32>()
+            })
+            .join()
+            .unwrap()
+        });
+
+        assert_eq!(sum, 6);
+        drop(unsafe { erased.into_erased().into_inner::<Vec<i32>>() });
+    }
+}
@@ -0,0 +1,52 @@
+/**
+ * Transparent `serde` support: a `Trident<T>` serializes exactly as its `T` does.
+ */
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::Trident;
+
+impl<T> Serialize for Trident<T>
+where
+    T: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.as_ref().serialize(serializer)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Trident<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Trident::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Trident;
+
+    #[test]
+    fn round_trips_inline() {
+        const { assert!(Trident::<(u32, bool)>::IS_INLINE) }
+
+        let json = serde_json::to_string(&Trident::new((7u32, true))).unwrap();
+        assert_eq!(json, serde_json::to_string(&(7u32, true)).unwrap());
+
+        let t: Trident<(u32, bool)> = serde_json::from_str(&json).unwrap();
+        assert_eq!(t, (7, true));
+    }
+
+    #[test]
+    fn round_trips_spilled() {
+        const { assert!(!Trident::<[u64; 6]>::IS_INLINE) }
+
+        let value = [1u64, 2, 3, 4, 5, 6];
+        let json = serde_json::to_string(&Trident::new(value)).unwrap();
+        assert_eq!(json, serde_json::to_string(&value).unwrap());
+
+        let t: Trident<[u64; 6]> = serde_json::from_str(&json).unwrap();
+        assert_eq!(t, value);
+    }
+}
@@ -0,0 +1,217 @@
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    fmt,
+};
+
+use crate::{AnyMap, AnyTrident};
+
+/**
+ * A registry of services, keyed by type, holding singleton instances and factories which construct
+ * a new instance each time, as a dependency-injection container.
+ *
+ * Registries can be scoped: a child registry, from `scope`, resolves services it doesn't have from
+ * its parent, and factories are called with the registry they're resolved from, so a child's
+ * registrations override its parent's for the services they construct.
+ *
+ * Singletons are stored as `AnyTrident`s, as are factories, so small services and factories with
+ * small captures are inline rather than boxed.
+ */
+#[derive(Default)]
+pub struct ServiceRegistry<'p> {
+    parent: Option<&'p ServiceRegistry<'p>>,
+    singletons: AnyMap,
+    factories: HashMap<TypeId, Factory>,
+}
+
+struct Factory {
+    // the `F` registered
+    f: AnyTrident,
+    // calls the `F`, returning an `AnyTrident` holding its `T`
+    call: fn(&AnyTrident, &ServiceRegistry<'_>) -> AnyTrident,
+}
+
+/**
+ * Call the `F` in `f`, returning its result as an `AnyTrident`.
+ */
+fn call_factory<T: Any, F: Fn(&ServiceRegistry<'_>) -> T + 'static>(
+    f: &AnyTrident,
+    registry: &ServiceRegistry<'_>,
+) -> AnyTrident {
+    let f = f.downcast_ref::<F>().expect("registered with F");
+    AnyTrident::new(f(registry))
+}
+
+impl<'p> ServiceRegistry<'p> {
+    /**
+     * Create an empty registry.
+     */
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /**
+     * Create an empty child registry, which resolves services it doesn't have from `self`.
+     */
+    pub fn scope(&self) -> ServiceRegistry<'_> {
+        ServiceRegistry {
+            parent: Some(self),
+            ..ServiceRegistry::default()
+        }
+    }
+
+    /**
+     * Register `t` as the `T` singleton, returning the `T` it replaces in this registry, if any.
+     */
+    pub fn register<T: Any>(&mut self, t: T) -> Option<T> {
+        self.singletons.insert(t)
+    }
+
+    /**
+     * Register `f` to construct each `T` requested from `create`, replacing any previous factory
+     * for `T` in this registry.
+     */
+    pub fn register_factory<T, F>(&mut self, f: F)
+    where
+        T: Any,
+        F: Fn(&ServiceRegistry<'_>) -> T + 'static,
+    {
+        self.factories.insert(
+            TypeId::of::<T>(),
+            Factory {
+                f: AnyTrident::new(f),
+                call: call_factory::<T, F>,
+            },
+        );
+    }
+
+    /**
+     * Get a reference to the `T` singleton, from this registry or the nearest ancestor which has
+     * one.
+     */
+    pub fn resolve<T: Any>(&self) -> Option<&T> {
+        self.singletons
+            .get()
+            .or_else(|| self.parent.and_then(ServiceRegistry::resolve))
+    }
+
+    /**
+     * Construct a `T` with the factory from this registry or the nearest ancestor which has one.
+     */
+    pub fn create<T: Any>(&self) -> Option<T> {
+        let factory = self.factory(TypeId::of::<T>())?;
+
+        let t = (factory.call)(&factory.f, self);
+        Some(t.downcast::<T>().expect("factory returns T").into_inner())
+    }
+
+    /**
+     * Whether a `T` singleton or factory is registered, in this registry or an ancestor.
+     */
+    pub fn contains<T: Any>(&self) -> bool {
+        self.resolve::<T>().is_some() || self.factory(TypeId::of::<T>()).is_some()
+    }
+
+    /**
+     * Remove the `T` singleton and factory from this registry, returning the singleton, if any.
+     * Ancestors are unchanged.
+     */
+    pub fn remove<T: Any>(&mut self) -> Option<T> {
+        self.factories.remove(&TypeId::of::<T>());
+        self.singletons.remove()
+    }
+
+    fn factory(&self, id: TypeId) -> Option<&Factory> {
+        self.factories.get(&id).or_else(|| self.parent?.factory(id))
+    }
+}
+
+impl fmt::Debug for ServiceRegistry<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ServiceRegistry")
+            .field("singletons", &self.singletons.len())
+            .field("factories", &self.factories.len())
+            .field("parent", &self.parent)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use super::*;
+
+    struct Config {
+        url: &'static str,
+    }
+
+    struct Client {
+        url: &'static str,
+        id: u32,
+    }
+
+    fn client_factory() -> impl Fn(&ServiceRegistry<'_>) -> Client {
+        let next_id = Rc::new(Cell::new(0));
+
+        move |registry| {
+            next_id.set(next_id.get() + 1);
+            Client {
+                url: registry.resolve::<Config>().unwrap().url,
+                id: next_id.get(),
+            }
+        }
+    }
+
+    #[test]
+    fn resolves_singletons_and_factories() {
+        let mut registry = ServiceRegistry::new();
+        registry.register(Config { url: "prod" });
+        registry.register_factory(client_factory());
+
+        assert_eq!(registry.resolve::<Config>().unwrap().url, "prod");
+        assert_eq!(registry.create::<Client>().unwrap().id, 1);
+        assert_eq!(registry.create::<Client>().unwrap().id, 2);
+        assert!(registry.contains::<Client>());
+
+        assert!(registry.resolve::<Client>().is_none());
+        assert!(registry.create::<Config>().is_none());
+        assert!(!registry.contains::<u8>());
+    }
+
+    #[test]
+    fn scopes_override_parents() {
+        let mut root = ServiceRegistry::new();
+        root.register(Config { url: "prod" });
+        root.register(7u8);
+        root.register_factory(client_factory());
+
+        {
+            let mut test = root.scope();
+            test.register(Config { url: "test" });
+
+            let client = test.create::<Client>().unwrap();
+            assert_eq!((client.url, client.id), ("test", 1));
+            assert_eq!(test.resolve::<u8>(), Some(&7));
+
+            assert!(test.remove::<Config>().is_some());
+            assert_eq!(test.resolve::<Config>().unwrap().url, "prod");
+        }
+
+        assert_eq!(root.create::<Client>().unwrap().url, "prod");
+    }
+
+    #[test]
+    fn drops_services() {
+        let rc = Rc::new(());
+        let mut registry = ServiceRegistry::new();
+
+        registry.register(rc.clone());
+        let captured = rc.clone();
+        registry.register_factory(move |_| Rc::strong_count(&captured));
+        assert_eq!(registry.create::<usize>(), Some(3));
+
+        drop(registry);
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+}
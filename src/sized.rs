@@ -0,0 +1,180 @@
+use std::{
+    alloc::{self, Layout},
+    mem::ManuallyDrop,
+    ptr,
+};
+
+use crate::{Erased, Trident};
+
+/**
+ * A type-erased `T`, as `Erased`, which also records `T`'s `Layout`.
+ *
+ * Knowing the layout, a `SizedErased` frees any allocation when dropped, without knowing `T`.
+ * As with `Erased`, `T`'s destructor isn't run, so this suits payloads which don't need dropping,
+ * or have already been dropped or moved out. This costs two words for the layout.
+ */
+pub struct SizedErased {
+    erased: Erased,
+    layout: Layout,
+}
+
+impl SizedErased {
+    /**
+     * Create a `SizedErased` from a `T`.
+     */
+    pub fn new<T>(t: T) -> Self {
+        Self::from_trident(Trident::new(t))
+    }
+
+    /**
+     * Create a `SizedErased` from a `Trident<T>`, keeping its storage.
+     */
+    pub fn from_trident<T>(t: Trident<T>) -> Self {
+        Self {
+            erased: t.into_erased(),
+            layout: Layout::new::<T>(),
+        }
+    }
+
+    /**
+     * Get the layout of the contained `T`.
+     */
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    /**
+     * Whether the contained `T` is stored inline.
+     */
+    pub fn is_inline(&self) -> bool {
+        // SAFETY: the layout is that of our payload
+        unsafe { self.erased.is_inline_layout(self.layout) }
+    }
+
+    /**
+     * Get a reference to the contained `T`.
+     *
+     * # Safety
+     *
+     * Unsafe because we don't know that this is the same `T` that this `SizedErased` was created
+     * with.
+     */
+    pub unsafe fn as_ref<T>(&self) -> &T {
+        self.erased.as_ref()
+    }
+
+    /**
+     * Get a mutable reference to the contained `T`.
+     *
+     * # Safety
+     *
+     * Unsafe because we don't know that this is the same `T` that this `SizedErased` was created
+     * with.
+     */
+    pub unsafe fn as_mut_ref<T>(&mut self) -> &mut T {
+        self.erased.as_mut_ref()
+    }
+
+    /**
+     * Get the contained `T`.
+     *
+     * # Safety
+     *
+     * Unsafe because we don't know that this is the same `T` that this `SizedErased` was created
+     * with.
+     */
+    pub unsafe fn into_inner<T>(self) -> T {
+        self.into_erased().into_inner()
+    }
+
+    /**
+     * Drop the contained `T` in place, then free any allocation.
+     *
+     * # Safety
+     *
+     * Unsafe because we don't know that this is the same `T` that this `SizedErased` was created
+     * with.
+     */
+    pub unsafe fn drop_as<T>(mut self) {
+        ptr::drop_in_place(self.as_mut_ref::<T>());
+    }
+
+    /**
+     * Convert to an `Erased`, which won't free any allocation.
+     */
+    pub fn into_erased(self) -> Erased {
+        let this = ManuallyDrop::new(self);
+
+        // SAFETY: `this` is never dropped, so ownership of the storage moves to the returned Erased
+        unsafe { ptr::read(&this.erased) }
+    }
+}
+
+/**
+ * Frees any allocation, without dropping the `T`.
+ */
+impl Drop for SizedErased {
+    fn drop(&mut self) {
+        if !self.is_inline() && self.layout.size() != 0 {
+            // SAFETY: our payload is boxed, allocated with its layout
+            unsafe { alloc::dealloc(self.erased.spilled_ptr(), self.layout) }
+        }
+    }
+}
+
+impl<T> From<Trident<T>> for SizedErased {
+    fn from(t: Trident<T>) -> Self {
+        Self::from_trident(t)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+
+    #[test]
+    fn records_layout() {
+        let small = SizedErased::new(1u16);
+        let large = SizedErased::new([0u64; 8]);
+
+        assert_eq!(small.layout(), Layout::new::<u16>());
+        assert_eq!(large.layout(), Layout::new::<[u64; 8]>());
+        assert!(small.is_inline());
+        assert!(!large.is_inline());
+    }
+
+    #[test]
+    fn frees_without_type() {
+        let mut spilled = Trident::new(7u32);
+        spilled.spill_to_heap();
+
+        #[repr(align(64))]
+        struct Aligned;
+
+        let entries = vec![
+            SizedErased::new([1u64; 8]),
+            SizedErased::new(Aligned),
+            SizedErased::from(spilled),
+            SizedErased::new(2u8),
+        ];
+
+        // checked for leaks under miri
+        drop(entries);
+    }
+
+    #[test]
+    fn drops_as_type() {
+        let rc = Rc::new(());
+
+        unsafe {
+            SizedErased::new([rc.clone(), rc.clone(), rc.clone(), rc.clone()])
+                .drop_as::<[Rc<()>; 4]>()
+        };
+        assert_eq!(Rc::strong_count(&rc), 1);
+
+        let erased = SizedErased::new(rc.clone());
+        assert_eq!(unsafe { erased.into_inner::<Rc<()>>() }, rc);
+    }
+}
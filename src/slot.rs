@@ -0,0 +1,148 @@
+use crate::OwnedErased;
+
+/**
+ * A reusable slot, which is either vacant or holds a type-erased value, as `OwnedErased`.
+ *
+ * The same slot can hold values of different types over time. Whatever it holds is dropped when
+ * it's cleared, refilled or dropped itself, so a container of slots needn't track how to drop
+ * each one.
+ */
+#[derive(Default)]
+pub struct ErasedSlot(Option<OwnedErased>);
+
+impl ErasedSlot {
+    /**
+     * Create a vacant slot.
+     */
+    pub const fn new() -> Self {
+        Self(None)
+    }
+
+    /**
+     * Whether the slot is vacant.
+     */
+    pub fn is_vacant(&self) -> bool {
+        self.0.is_none()
+    }
+
+    /**
+     * Fill the slot with `t`, dropping any value it already held.
+     *
+     * `T` must be `'static`, as the slot has no lifetime to stop it outliving anything `t`
+     * borrows.
+     */
+    pub fn fill<T: 'static>(&mut self, t: T) {
+        self.fill_owned(OwnedErased::new(t));
+    }
+
+    /**
+     * Fill the slot with an `OwnedErased`, dropping any value it already held.
+     */
+    pub fn fill_owned(&mut self, erased: OwnedErased) {
+        self.0 = Some(erased);
+    }
+
+    /**
+     * Take the value out of the slot, leaving it vacant.
+     *
+     * # Safety
+     *
+     * Unsafe because we don't know that the slot was last filled with a `T`.
+     */
+    pub unsafe fn take<T>(&mut self) -> Option<T> {
+        self.take_owned().map(|erased| erased.into_inner())
+    }
+
+    /**
+     * Take the value out of the slot as an `OwnedErased`, leaving it vacant.
+     */
+    pub fn take_owned(&mut self) -> Option<OwnedErased> {
+        self.0.take()
+    }
+
+    /**
+     * Drop any value in the slot, leaving it vacant.
+     */
+    pub fn clear(&mut self) {
+        self.0 = None;
+    }
+
+    /**
+     * Get a reference to the value in the slot, if any.
+     *
+     * # Safety
+     *
+     * Unsafe because we don't know that the slot was last filled with a `T`.
+     */
+    pub unsafe fn get<T>(&self) -> Option<&T> {
+        self.0.as_ref().map(|erased| erased.as_ref())
+    }
+
+    /**
+     * Get a mutable reference to the value in the slot, if any.
+     *
+     * # Safety
+     *
+     * Unsafe because we don't know that the slot was last filled with a `T`.
+     */
+    pub unsafe fn get_mut<T>(&mut self) -> Option<&mut T> {
+        self.0.as_mut().map(|erased| erased.as_mut_ref())
+    }
+}
+
+impl From<OwnedErased> for ErasedSlot {
+    fn from(erased: OwnedErased) -> Self {
+        Self(Some(erased))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+
+    #[test]
+    fn fills_and_takes() {
+        let mut slot = ErasedSlot::new();
+        assert!(slot.is_vacant());
+
+        slot.fill(1u8);
+        assert!(!slot.is_vacant());
+        unsafe {
+            *slot.get_mut::<u8>().unwrap() += 1;
+            assert_eq!(slot.get::<u8>(), Some(&2));
+            assert_eq!(slot.take::<u8>(), Some(2));
+            assert_eq!(slot.take::<u8>(), None);
+        }
+        assert!(slot.is_vacant());
+
+        slot.fill([3u64; 8]);
+        assert_eq!(unsafe { slot.take::<[u64; 8]>() }, Some([3; 8]));
+    }
+
+    #[test]
+    fn drops_on_clear_and_refill() {
+        let rc = Rc::new(());
+        let mut slot = ErasedSlot::from(OwnedErased::new(rc.clone()));
+
+        slot.fill([rc.clone(), rc.clone(), rc.clone(), rc.clone()]);
+        assert_eq!(Rc::strong_count(&rc), 5);
+
+        slot.fill(String::from("reused"));
+        assert_eq!(Rc::strong_count(&rc), 1);
+
+        slot.fill(rc.clone());
+        slot.clear();
+        assert_eq!(Rc::strong_count(&rc), 1);
+
+        slot.fill(rc.clone());
+        let owned = slot.take_owned().unwrap();
+        assert!(slot.is_vacant());
+        drop(owned);
+
+        slot.fill(rc.clone());
+        drop(slot);
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+}
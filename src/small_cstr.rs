@@ -0,0 +1,226 @@
+use std::{
+    error::Error,
+    ffi::{c_char, CStr, CString},
+    fmt,
+    hash::{Hash, Hasher},
+    iter,
+    ops::Deref,
+};
+
+use crate::SmallSlice;
+
+/**
+ * A NUL-terminated string, stored within three words if it's short enough, otherwise boxed, as
+ * `CString`.
+ *
+ * Up to 22 bytes (10 on 32-bit), plus the NUL, are stored inline, so short strings passed to C
+ * don't allocate.
+ */
+#[derive(Clone)]
+pub struct SmallCString {
+    // including the NUL
+    bytes: SmallSlice<u8>,
+}
+
+/**
+ * The error returned when creating a `SmallCString` from bytes which contain a NUL.
+ */
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct InteriorNulError(usize);
+
+impl SmallCString {
+    /**
+     * Create a `SmallCString` from bytes without a NUL, copying them and appending one.
+     */
+    pub fn new(bytes: &[u8]) -> Result<Self, InteriorNulError> {
+        match bytes.iter().position(|&b| b == 0) {
+            Some(i) => Err(InteriorNulError(i)),
+            None => Ok(Self {
+                bytes: bytes.iter().copied().chain(iter::once(0)).collect(),
+            }),
+        }
+    }
+
+    /**
+     * The longest string, excluding the NUL, which is stored inline.
+     */
+    pub const INLINE_CAPACITY: usize = SmallSlice::<u8>::INLINE_CAPACITY - 1;
+
+    /**
+     * Whether the string is stored inline.
+     */
+    pub fn is_inline(&self) -> bool {
+        self.bytes.is_inline()
+    }
+
+    /**
+     * Get the string as a `&CStr`.
+     */
+    pub fn as_c_str(&self) -> &CStr {
+        // SAFETY: created with a single, trailing, NUL
+        unsafe { CStr::from_bytes_with_nul_unchecked(&self.bytes) }
+    }
+
+    /**
+     * Get a pointer to the NUL-terminated string, valid for as long as `self` isn't moved or
+     * dropped.
+     *
+     * An inline string moves with `self`, so, unlike `CString::as_ptr`, the pointer is
+     * invalidated by moving the `SmallCString`, as well as by dropping it.
+     */
+    pub fn as_ptr(&self) -> *const c_char {
+        self.bytes.as_ptr() as *const c_char
+    }
+
+    /**
+     * Convert to a `CString`, which allocates if the string is inline.
+     */
+    pub fn into_c_string(self) -> CString {
+        // SAFETY: created with a single, trailing, NUL
+        unsafe { CString::from_vec_with_nul_unchecked(self.bytes.into()) }
+    }
+}
+
+impl Deref for SmallCString {
+    type Target = CStr;
+
+    fn deref(&self) -> &CStr {
+        self.as_c_str()
+    }
+}
+
+impl AsRef<CStr> for SmallCString {
+    fn as_ref(&self) -> &CStr {
+        self.as_c_str()
+    }
+}
+
+impl From<&CStr> for SmallCString {
+    fn from(s: &CStr) -> Self {
+        Self {
+            bytes: s.to_bytes_with_nul().into(),
+        }
+    }
+}
+
+/**
+ * Reuses the `CString`'s allocation, if the string is too long to be inline.
+ */
+impl From<CString> for SmallCString {
+    fn from(s: CString) -> Self {
+        Self {
+            bytes: s.into_bytes_with_nul().into(),
+        }
+    }
+}
+
+impl From<SmallCString> for CString {
+    fn from(s: SmallCString) -> Self {
+        s.into_c_string()
+    }
+}
+
+impl TryFrom<&str> for SmallCString {
+    type Error = InteriorNulError;
+
+    fn try_from(s: &str) -> Result<Self, InteriorNulError> {
+        Self::new(s.as_bytes())
+    }
+}
+
+impl PartialEq for SmallCString {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_c_str() == other.as_c_str()
+    }
+}
+
+impl Eq for SmallCString {}
+
+impl Hash for SmallCString {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_c_str().hash(state)
+    }
+}
+
+impl fmt::Debug for SmallCString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_c_str(), f)
+    }
+}
+
+impl InteriorNulError {
+    /**
+     * The position of the NUL in the bytes given.
+     */
+    pub fn nul_position(&self) -> usize {
+        self.0
+    }
+}
+
+impl fmt::Display for InteriorNulError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "nul byte found in provided data at position: {}", self.0)
+    }
+}
+
+impl Error for InteriorNulError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    extern "C" {
+        fn strlen(s: *const c_char) -> usize;
+    }
+
+    #[test]
+    fn stores_short_strings_inline() {
+        let short = SmallCString::try_from("short").unwrap();
+        let full = SmallCString::new(&[b'x'; SmallCString::INLINE_CAPACITY]).unwrap();
+        let long = SmallCString::new(&[b'y'; SmallCString::INLINE_CAPACITY + 1]).unwrap();
+
+        assert!(short.is_inline());
+        assert!(full.is_inline());
+        assert!(!long.is_inline());
+
+        assert_eq!(short.to_bytes(), b"short");
+        assert_eq!(full.to_bytes().len(), SmallCString::INLINE_CAPACITY);
+        assert_eq!(long.to_bytes().len(), SmallCString::INLINE_CAPACITY + 1);
+    }
+
+    #[test]
+    fn passes_to_c() {
+        for s in ["", "tiny", "a string which is too long to be inline"] {
+            let c = SmallCString::try_from(s).unwrap();
+            assert_eq!(unsafe { strlen(c.as_ptr()) }, s.len());
+        }
+    }
+
+    #[test]
+    fn rejects_interior_nul() {
+        let err = SmallCString::new(b"a\0b").unwrap_err();
+
+        assert_eq!(err.nul_position(), 1);
+        assert_eq!(
+            err.to_string(),
+            "nul byte found in provided data at position: 1"
+        );
+    }
+
+    #[test]
+    fn converts() {
+        let long = CString::new("a string which is too long to be inline").unwrap();
+        let copy = long.clone();
+        let ptr = copy.as_ptr();
+
+        let s = SmallCString::from(copy);
+        assert_eq!(s.as_ptr(), ptr);
+        assert_eq!(s.clone(), s);
+        assert_eq!(CString::from(s), long);
+
+        let s = SmallCString::from(c"short");
+        assert!(s.is_inline());
+        assert_eq!(format!("{:?}", s), r#""short""#);
+        assert_eq!(s.into_c_string().as_c_str(), c"short");
+    }
+}
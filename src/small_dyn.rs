@@ -0,0 +1,286 @@
+/**
+ * Define a "small dyn" wrapper for a trait, which holds any `T` implementing the trait, inline if
+ * it fits, and implements the trait itself by forwarding through a generated vtable.
+ *
+ * Unlike `Box<dyn Trait>`, a small `T` isn't allocated. The wrapper is three words: two holding
+ * the `T` if it fits, otherwise a pointer to it, as in `SmallFn`, and a pointer to the vtable,
+ * which also drops the `T`.
+ *
+ * For example, given `trait Handler { fn handle(&mut self, event: u32) -> bool; }`,
+ * `small_dyn! { pub struct SmallHandler: Handler { fn handle(&mut self, event: u32) -> bool; } }`
+ * defines `SmallHandler`, with `SmallHandler::new` taking any `T: Handler`.
+ *
+ * Each of the trait's methods must be listed, taking `&self` or `&mut self`, and must not be
+ * generic. `T` must be `'static`, as the wrapper borrows nothing. As the `T` is erased, the
+ * wrapper is neither `Send` nor `Sync`.
+ */
+#[macro_export]
+macro_rules! small_dyn {
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident: $trait:path {
+            $($methods:tt)*
+        }
+    ) => {
+        $crate::small_dyn!(@munch [$(#[$attr])*] [$vis] $name [$trait] [] $($methods)*);
+    };
+
+    (@munch $attrs:tt $vis:tt $name:ident $trait:tt [$($done:tt)*]
+        fn $method:ident(&self $(, $arg:ident: $ty:ty)* $(,)?) $(-> $ret:ty)?;
+        $($rest:tt)*
+    ) => {
+        $crate::small_dyn!(
+            @munch $attrs $vis $name $trait
+            [$($done)* {[] $method ($($arg: $ty),*) ($($ret)?)}]
+            $($rest)*
+        );
+    };
+
+    (@munch $attrs:tt $vis:tt $name:ident $trait:tt [$($done:tt)*]
+        fn $method:ident(&mut self $(, $arg:ident: $ty:ty)* $(,)?) $(-> $ret:ty)?;
+        $($rest:tt)*
+    ) => {
+        $crate::small_dyn!(
+            @munch $attrs $vis $name $trait
+            [$($done)* {[mut] $method ($($arg: $ty),*) ($($ret)?)}]
+            $($rest)*
+        );
+    };
+
+    (@munch [$($attr:tt)*] [$vis:vis] $name:ident [$trait:path] [$(
+        {[$($mut:tt)?] $method:ident ($($arg:ident: $ty:ty),*) ($($ret:ty)?)}
+    )*]) => {
+        $($attr)*
+        $vis struct $name {
+            payload: $crate::__private::Payload,
+            // points to the VTable below, which can't be named here
+            vtable: *const (),
+        }
+
+        const _: () = {
+            use $crate::__private::Payload;
+
+            struct VTable {
+                drop: unsafe fn(*mut Payload),
+                $($method: unsafe fn(*mut Payload $(, $ty)*) $(-> $ret)?,)*
+            }
+
+            struct VTableFor<T>(::std::marker::PhantomData<T>);
+
+            impl<T: $trait + 'static> VTableFor<T> {
+                const VTABLE: VTable = VTable {
+                    drop: Payload::drop_in_place::<T>,
+                    $($method: $method::<T>,)*
+                };
+            }
+
+            $(
+                unsafe fn $method<T: $trait>(
+                    payload: *mut Payload
+                    $(, $arg: $ty)*
+                ) $(-> $ret)? {
+                    <T as $trait>::$method(
+                        &$($mut)? *Payload::resolve::<T>(payload)
+                        $(, $arg)*
+                    )
+                }
+            )*
+
+            impl $name {
+                /**
+                 * Create a wrapper from a `T`.
+                 */
+                $vis fn new<T: $trait + 'static>(t: T) -> Self {
+                    Self::from_payload::<T>(Payload::new(t))
+                }
+
+                /**
+                 * Create a wrapper from a `Trident<T>`, reusing its allocation if the `T` is
+                 * boxed in both.
+                 */
+                $vis fn from_trident<T: $trait + 'static>(t: $crate::Trident<T>) -> Self {
+                    Self::from_payload::<T>(Payload::from_trident(t))
+                }
+
+                fn from_payload<T: $trait + 'static>(payload: Payload) -> Self {
+                    let vtable: &'static VTable = &VTableFor::<T>::VTABLE;
+
+                    Self {
+                        payload,
+                        vtable: vtable as *const VTable as *const (),
+                    }
+                }
+
+                fn vtable(&self) -> &'static VTable {
+                    // SAFETY: set from a &'static VTable on construction
+                    unsafe { &*(self.vtable as *const VTable) }
+                }
+            }
+
+            impl $trait for $name {
+                $(
+                    fn $method(&$($mut)? self $(, $arg: $ty)*) $(-> $ret)? {
+                        let vtable = self.vtable();
+                        // SAFETY: the vtable was created for the T we hold
+                        unsafe {
+                            (vtable.$method)(
+                                $crate::small_dyn!(@payload [$($mut)?] self)
+                                $(, $arg)*
+                            )
+                        }
+                    }
+                )*
+            }
+
+            impl Drop for $name {
+                fn drop(&mut self) {
+                    let vtable = self.vtable();
+                    // SAFETY: the vtable was created for the T we hold
+                    unsafe { (vtable.drop)(&mut self.payload) }
+                }
+            }
+        };
+    };
+
+    // a `&self` method may only mutate the payload through an UnsafeCell, which `Payload` is
+    (@payload [] $self:ident) => {
+        &$self.payload as *const $crate::__private::Payload as *mut $crate::__private::Payload
+    };
+
+    (@payload [mut] $self:ident) => {
+        &mut $self.payload as *mut $crate::__private::Payload
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    pub trait Handler {
+        fn handle(&mut self, event: u32) -> bool;
+        fn name(&self) -> String;
+        fn reset(&mut self);
+    }
+
+    small_dyn! {
+        /**
+         * A `Handler`, stored inline if small.
+         */
+        pub struct SmallHandler: Handler {
+            fn handle(&mut self, event: u32) -> bool;
+            fn name(&self) -> String;
+            fn reset(&mut self);
+        }
+    }
+
+    struct Counter(u32);
+
+    impl Handler for Counter {
+        fn handle(&mut self, event: u32) -> bool {
+            self.0 += event;
+            self.0 < 10
+        }
+
+        fn name(&self) -> String {
+            format!("counter at {}", self.0)
+        }
+
+        fn reset(&mut self) {
+            self.0 = 0;
+        }
+    }
+
+    struct Logger {
+        log: Vec<u32>,
+        prefix: [u8; 32],
+        drops: Rc<Cell<u32>>,
+    }
+
+    impl Handler for Logger {
+        fn handle(&mut self, event: u32) -> bool {
+            self.log.push(event);
+            true
+        }
+
+        fn name(&self) -> String {
+            format!("{} logged {:?}", self.prefix[0], self.log)
+        }
+
+        fn reset(&mut self) {
+            self.log.clear();
+        }
+    }
+
+    impl Drop for Logger {
+        fn drop(&mut self) {
+            self.drops.set(self.drops.get() + 1);
+        }
+    }
+
+    #[test]
+    fn dispatches() {
+        let drops = Rc::new(Cell::new(0));
+        let mut handlers = vec![
+            SmallHandler::new(Counter(0)),
+            SmallHandler::new(Logger {
+                log: Vec::new(),
+                prefix: [7; 32],
+                drops: drops.clone(),
+            }),
+        ];
+
+        for event in [4, 8] {
+            for handler in &mut handlers {
+                handler.handle(event);
+            }
+        }
+        assert!(!handlers[0].handle(0));
+
+        let names = handlers.iter().map(|h| h.name()).collect::<Vec<_>>();
+        assert_eq!(names, ["counter at 12", "7 logged [4, 8]"]);
+
+        handlers[1].reset();
+        assert_eq!(handlers[1].name(), "7 logged []");
+
+        drop(handlers);
+        assert_eq!(drops.get(), 1);
+    }
+
+    #[test]
+    fn is_three_words() {
+        struct Calls(Cell<u32>);
+
+        impl Handler for Calls {
+            fn handle(&mut self, _: u32) -> bool {
+                true
+            }
+
+            fn name(&self) -> String {
+                self.0.set(self.0.get() + 1);
+                format!("named {} times", self.0.get())
+            }
+
+            fn reset(&mut self) {}
+        }
+
+        assert_eq!(
+            std::mem::size_of::<SmallHandler>(),
+            3 * std::mem::size_of::<usize>()
+        );
+
+        let handler = SmallHandler::new(Calls(Cell::new(0)));
+        let (a, b) = (&handler, &handler);
+        a.name();
+        assert_eq!(b.name(), "named 2 times");
+    }
+
+    #[test]
+    fn is_generic_over_handlers() {
+        fn run(handler: &mut impl Handler) -> bool {
+            handler.handle(1)
+        }
+
+        let mut handler = SmallHandler::from_trident(crate::Trident::new(Counter(9)));
+        assert!(!run(&mut handler));
+    }
+}
@@ -0,0 +1,299 @@
+use std::{
+    any::TypeId,
+    error::Error,
+    fmt,
+    marker::PhantomData,
+    mem::ManuallyDrop,
+    ops::{Deref, DerefMut},
+};
+
+use crate::payload::{self, Payload};
+
+type DynError = dyn Error + Send + Sync + 'static;
+
+/**
+ * An error, as `Box<dyn Error + Send + Sync>`, stored inline if it fits in two words, next to a
+ * vtable pointer. Larger errors are boxed, so propagating a small error enum with `?` doesn't
+ * allocate.
+ *
+ * Like `anyhow::Error`, any error converts into a `SmallError`, so it doesn't implement `Error`
+ * itself, but dereferences to `dyn Error` for its `source`, and forwards `Display` and `Debug`.
+ */
+pub struct SmallError {
+    payload: Payload,
+    vtable: &'static VTable,
+}
+
+// SAFETY: created only from `Send + Sync` errors
+unsafe impl Send for SmallError {}
+unsafe impl Sync for SmallError {}
+
+struct VTable {
+    type_id: fn() -> TypeId,
+    as_dyn: unsafe fn(*mut Payload) -> *mut DynError,
+    into_boxed: unsafe fn(*mut Payload) -> Box<DynError>,
+    drop: unsafe fn(*mut Payload),
+    inline: bool,
+}
+
+/**
+ * Find the `E` in the `Payload` at `payload`, as a `dyn Error`.
+ *
+ * Unsafe because `payload` must point to a `Payload` holding an `E`.
+ */
+unsafe fn as_dyn<E: Error + Send + Sync + 'static>(payload: *mut Payload) -> *mut DynError {
+    Payload::resolve::<E>(payload)
+}
+
+/**
+ * Move the `E` in the `Payload` at `payload` to a box, as a `dyn Error`.
+ *
+ * Unsafe because `payload` must point to a `Payload` holding an `E`, which isn't used again.
+ */
+unsafe fn into_boxed<E: Error + Send + Sync + 'static>(payload: *mut Payload) -> Box<DynError> {
+    Box::new(Payload::take::<E>(payload))
+}
+
+struct VTableFor<E>(PhantomData<E>);
+
+impl<E: Error + Send + Sync + 'static> VTableFor<E> {
+    const VTABLE: VTable = VTable {
+        type_id: TypeId::of::<E>,
+        as_dyn: as_dyn::<E>,
+        into_boxed: into_boxed::<E>,
+        drop: Payload::drop_in_place::<E>,
+        inline: payload::inlines::<E>(),
+    };
+}
+
+impl SmallError {
+    /**
+     * Wrap `e`, storing it inline if it fits in two words.
+     */
+    pub fn new<E: Error + Send + Sync + 'static>(e: E) -> Self {
+        Self {
+            payload: Payload::new(e),
+            vtable: &VTableFor::<E>::VTABLE,
+        }
+    }
+
+    /**
+     * Whether the error is stored inline.
+     */
+    pub fn is_inline(&self) -> bool {
+        self.vtable.inline
+    }
+
+    /**
+     * Whether the error is an `E`.
+     */
+    pub fn is<E: Error + 'static>(&self) -> bool {
+        (self.vtable.type_id)() == TypeId::of::<E>()
+    }
+
+    /**
+     * Get a reference to the error, if it's an `E`.
+     */
+    pub fn downcast_ref<E: Error + 'static>(&self) -> Option<&E> {
+        self.as_dyn().downcast_ref()
+    }
+
+    /**
+     * Get a mutable reference to the error, if it's an `E`.
+     */
+    pub fn downcast_mut<E: Error + 'static>(&mut self) -> Option<&mut E> {
+        self.as_dyn_mut().downcast_mut()
+    }
+
+    /**
+     * Move the error out, if it's an `E`, otherwise return `self`.
+     */
+    pub fn downcast<E: Error + 'static>(self) -> Result<E, Self> {
+        if self.is::<E>() {
+            let mut this = ManuallyDrop::new(self);
+
+            // SAFETY: we hold an E, which is moved out and not dropped
+            Ok(unsafe { Payload::take::<E>(&mut this.payload) })
+        } else {
+            Err(self)
+        }
+    }
+
+    /**
+     * Move the error to a box, which allocates if it's inline.
+     */
+    pub fn into_boxed(self) -> Box<dyn Error + Send + Sync> {
+        let mut this = ManuallyDrop::new(self);
+
+        // SAFETY: the vtable was created for our payload, which is moved out and not dropped
+        unsafe { (this.vtable.into_boxed)(&mut this.payload) }
+    }
+
+    fn as_dyn(&self) -> &DynError {
+        let payload = &self.payload as *const Payload as *mut Payload;
+
+        // SAFETY: the vtable was created for our payload, which is only read through
+        unsafe { &*(self.vtable.as_dyn)(payload) }
+    }
+
+    fn as_dyn_mut(&mut self) -> &mut DynError {
+        // SAFETY: the vtable was created for our payload
+        unsafe { &mut *(self.vtable.as_dyn)(&mut self.payload) }
+    }
+}
+
+/**
+ * Allows `?` to convert any error into a `SmallError`.
+ */
+impl<E: Error + Send + Sync + 'static> From<E> for SmallError {
+    fn from(e: E) -> Self {
+        Self::new(e)
+    }
+}
+
+impl From<SmallError> for Box<dyn Error + Send + Sync> {
+    fn from(e: SmallError) -> Self {
+        e.into_boxed()
+    }
+}
+
+impl Deref for SmallError {
+    type Target = dyn Error + Send + Sync + 'static;
+
+    fn deref(&self) -> &DynError {
+        self.as_dyn()
+    }
+}
+
+impl DerefMut for SmallError {
+    fn deref_mut(&mut self) -> &mut DynError {
+        self.as_dyn_mut()
+    }
+}
+
+impl AsRef<dyn Error + Send + Sync> for SmallError {
+    fn as_ref(&self) -> &(dyn Error + Send + Sync + 'static) {
+        self.as_dyn()
+    }
+}
+
+impl Drop for SmallError {
+    fn drop(&mut self) {
+        // SAFETY: the vtable was created for our payload, which isn't used again
+        unsafe { (self.vtable.drop)(&mut self.payload) }
+    }
+}
+
+impl fmt::Display for SmallError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_dyn(), f)
+    }
+}
+
+impl fmt::Debug for SmallError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_dyn(), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io, num::ParseIntError};
+
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    enum ConfigError {
+        Missing(&'static str),
+        Invalid { line: u32 },
+    }
+
+    impl fmt::Display for ConfigError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::Missing(key) => write!(f, "missing key {}", key),
+                Self::Invalid { line } => write!(f, "invalid config on line {}", line),
+            }
+        }
+    }
+
+    impl Error for ConfigError {}
+
+    #[derive(Debug)]
+    struct Wrapped {
+        context: [u64; 4],
+        source: ParseIntError,
+    }
+
+    impl fmt::Display for Wrapped {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "parsing {:?}", self.context)
+        }
+    }
+
+    impl Error for Wrapped {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&self.source)
+        }
+    }
+
+    fn lookup(key: &'static str) -> Result<u32, SmallError> {
+        match key {
+            "port" => Ok("80".parse()?),
+            "line" => Err(ConfigError::Invalid { line: 3 })?,
+            "nested" => Err(Wrapped {
+                context: [1; 4],
+                source: "x".parse::<u32>().unwrap_err(),
+            })?,
+            _ => Err(ConfigError::Missing(key))?,
+        }
+    }
+
+    #[test]
+    fn propagates_with_question_mark() {
+        assert_eq!(lookup("port").unwrap(), 80);
+
+        let err = lookup("host").unwrap_err();
+        assert!(err.is_inline());
+        assert!(err.is::<ConfigError>());
+        assert_eq!(err.to_string(), "missing key host");
+        assert_eq!(format!("{:?}", err), r#"Missing("host")"#);
+
+        let err = lookup("line").unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<ConfigError>(),
+            Some(&ConfigError::Invalid { line: 3 })
+        );
+        assert!(err.downcast_ref::<io::Error>().is_none());
+    }
+
+    #[test]
+    fn keeps_sources() {
+        let err = lookup("nested").unwrap_err();
+
+        assert!(!err.is_inline());
+        assert_eq!(err.to_string(), "parsing [1, 1, 1, 1]");
+        assert_eq!(
+            err.source().unwrap().to_string(),
+            "invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn downcasts_by_value() {
+        let mut err = SmallError::from(ConfigError::Invalid { line: 1 });
+        if let Some(ConfigError::Invalid { line }) = err.downcast_mut() {
+            *line += 1;
+        }
+
+        let err = err.downcast::<io::Error>().unwrap_err();
+        assert_eq!(
+            err.downcast::<ConfigError>().unwrap(),
+            ConfigError::Invalid { line: 2 }
+        );
+
+        let err = SmallError::new(io::Error::other("disk full"));
+        let boxed: Box<dyn Error + Send + Sync> = err.into();
+        assert_eq!(boxed.to_string(), "disk full");
+    }
+}
@@ -0,0 +1,284 @@
+use std::{fmt, marker::PhantomData, mem::ManuallyDrop};
+
+use crate::payload::{self, Payload};
+
+struct VTable<C, A, R> {
+    call: C,
+    drop: unsafe fn(*mut Payload),
+    inline: bool,
+    _phantom: PhantomData<fn(A) -> R>,
+}
+
+type FnVTable<A, R> = VTable<unsafe fn(*const Payload, A) -> R, A, R>;
+type FnMutVTable<A, R> = VTable<unsafe fn(*mut Payload, A) -> R, A, R>;
+type FnOnceVTable<A, R> = VTable<unsafe fn(*mut Payload, A) -> R, A, R>;
+
+/**
+ * Call the `F` in the `Payload` at `captures`.
+ *
+ * Unsafe because `captures` must point to a `Payload` holding an `F`.
+ */
+unsafe fn call_fn<F: Fn(A) -> R, A, R>(captures: *const Payload, a: A) -> R {
+    (*Payload::resolve::<F>(captures as *mut Payload))(a)
+}
+
+/**
+ * As `call_fn`, for an `F` which may mutate its captures.
+ */
+unsafe fn call_fn_mut<F: FnMut(A) -> R, A, R>(captures: *mut Payload, a: A) -> R {
+    (*Payload::resolve::<F>(captures))(a)
+}
+
+/**
+ * As `call_fn`, moving the `F` out, so it mustn't be used again.
+ */
+unsafe fn call_fn_once<F: FnOnce(A) -> R, A, R>(captures: *mut Payload, a: A) -> R {
+    Payload::take::<F>(captures)(a)
+}
+
+struct VTableFor<F, A, R>(PhantomData<(F, A, R)>);
+
+impl<F: Fn(A) -> R, A, R> VTableFor<F, A, R> {
+    const FN: FnVTable<A, R> = VTable {
+        call: call_fn::<F, A, R>,
+        drop: Payload::drop_in_place::<F>,
+        inline: payload::inlines::<F>(),
+        _phantom: PhantomData,
+    };
+}
+
+impl<F: FnMut(A) -> R, A, R> VTableFor<F, A, R> {
+    const FN_MUT: FnMutVTable<A, R> = VTable {
+        call: call_fn_mut::<F, A, R>,
+        drop: Payload::drop_in_place::<F>,
+        inline: payload::inlines::<F>(),
+        _phantom: PhantomData,
+    };
+}
+
+impl<F: FnOnce(A) -> R, A, R> VTableFor<F, A, R> {
+    const FN_ONCE: FnOnceVTable<A, R> = VTable {
+        call: call_fn_once::<F, A, R>,
+        drop: Payload::drop_in_place::<F>,
+        inline: payload::inlines::<F>(),
+        _phantom: PhantomData,
+    };
+}
+
+macro_rules! small_fn {
+    ($(#[$meta:meta])* $name:ident, $vtable:ident, $const:ident, $bound:ident) => {
+        $(#[$meta])*
+        pub struct $name<A, R = ()> {
+            captures: Payload,
+            // a reference would require A and R to be 'static
+            vtable: *const $vtable<A, R>,
+            _not_send_sync: PhantomData<*mut ()>,
+        }
+
+        impl<A, R> $name<A, R> {
+            /**
+             * Wrap `f`, storing its captures inline if they fit in two words.
+             */
+            pub fn new<F: $bound(A) -> R + 'static>(f: F) -> Self {
+                Self {
+                    captures: Payload::new(f),
+                    vtable: &VTableFor::<F, A, R>::$const,
+                    _not_send_sync: PhantomData,
+                }
+            }
+
+            /**
+             * Whether the closure's captures are stored inline.
+             */
+            pub fn is_inline(&self) -> bool {
+                self.vtable().inline
+            }
+
+            fn vtable(&self) -> &$vtable<A, R> {
+                // SAFETY: points to a promoted constant
+                unsafe { &*self.vtable }
+            }
+        }
+
+        impl<A, R> Drop for $name<A, R> {
+            fn drop(&mut self) {
+                // SAFETY: the vtable was created for our captures, which aren't used again
+                unsafe { (self.vtable().drop)(&mut self.captures) }
+            }
+        }
+
+        impl<A, R> fmt::Debug for $name<A, R> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_struct(stringify!($name)).finish_non_exhaustive()
+            }
+        }
+    };
+}
+
+small_fn!(
+    /**
+     * A `Fn(A) -> R` closure, as `Box<dyn Fn(A) -> R>`, whose captures are stored inline if they
+     * fit in two words, next to a vtable pointer. Larger captures are boxed.
+     *
+     * Take multiple arguments as a tuple. Like `Erased`, a `SmallFn` is neither `Send` nor `Sync`.
+     */
+    SmallFn,
+    FnVTable,
+    FN,
+    Fn
+);
+
+small_fn!(
+    /**
+     * A `FnMut(A) -> R` closure, stored as `SmallFn` is.
+     */
+    SmallFnMut,
+    FnMutVTable,
+    FN_MUT,
+    FnMut
+);
+
+small_fn!(
+    /**
+     * A `FnOnce(A) -> R` closure, stored as `SmallFn` is.
+     */
+    SmallFnOnce,
+    FnOnceVTable,
+    FN_ONCE,
+    FnOnce
+);
+
+impl<A, R> SmallFn<A, R> {
+    /**
+     * Call the closure.
+     */
+    pub fn call(&self, a: A) -> R {
+        // SAFETY: the vtable was created for our captures
+        unsafe { (self.vtable().call)(&self.captures, a) }
+    }
+}
+
+impl<A, R> SmallFnMut<A, R> {
+    /**
+     * Call the closure.
+     */
+    pub fn call(&mut self, a: A) -> R {
+        // SAFETY: the vtable was created for our captures
+        unsafe { (self.vtable().call)(&mut self.captures, a) }
+    }
+}
+
+impl<A, R> SmallFnOnce<A, R> {
+    /**
+     * Call the closure, consuming it.
+     */
+    pub fn call(self, a: A) -> R {
+        let mut this = ManuallyDrop::new(self);
+
+        // SAFETY: the vtable was created for our captures, which are moved out and not dropped
+        unsafe { (this.vtable().call)(&mut this.captures, a) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use super::*;
+
+    #[test]
+    fn stores_small_captures_inline() {
+        let offset = 3;
+        let add = SmallFn::new(move |x: i32| x + offset);
+        let mul = SmallFn::new(|(x, y): (i32, i32)| x * y);
+        let table = [7i64; 4];
+        let large = SmallFn::new(move |i: usize| table[i]);
+
+        assert!(add.is_inline());
+        assert!(mul.is_inline());
+        assert!(!large.is_inline());
+
+        assert_eq!(add.call(1), 4);
+        assert_eq!(mul.call((2, 5)), 10);
+        assert_eq!(large.call(2), 7);
+        assert_eq!(
+            std::mem::size_of::<SmallFn<i32, i32>>(),
+            3 * std::mem::size_of::<usize>()
+        );
+    }
+
+    #[test]
+    fn mutates_captures() {
+        let mut total = 0;
+        let mut handlers: Vec<SmallFnMut<u32, u32>> = vec![
+            SmallFnMut::new(move |n| {
+                total += n;
+                total
+            }),
+            SmallFnMut::new({
+                let mut seen = [0u32; 8];
+                move |n| {
+                    seen[n as usize % 8] += 1;
+                    seen.iter().sum()
+                }
+            }),
+        ];
+
+        for n in 1..=3 {
+            for handler in &mut handlers {
+                handler.call(n);
+            }
+        }
+        assert_eq!(handlers[0].call(4), 10);
+        assert_eq!(handlers[1].call(4), 4);
+    }
+
+    #[test]
+    fn mutates_inline_cells() {
+        let count = Cell::new(0u32);
+        let f = SmallFn::new(move |n: u32| {
+            count.set(count.get() + n);
+            count.get()
+        });
+        assert!(f.is_inline());
+
+        let (a, b) = (&f, &f);
+        assert_eq!(a.call(1), 1);
+        assert_eq!(b.call(2), 3);
+    }
+
+    #[test]
+    fn calls_once() {
+        let drops = Rc::new(Cell::new(0));
+
+        struct Counted(Rc<Cell<u32>>);
+
+        impl Drop for Counted {
+            fn drop(&mut self) {
+                self.0.set(self.0.get() + 1);
+            }
+        }
+
+        let small = Counted(drops.clone());
+        let f = SmallFnOnce::new(move |()| small);
+        assert!(f.is_inline());
+        let small = f.call(());
+        assert_eq!(drops.get(), 0);
+        drop(small);
+        assert_eq!(drops.get(), 1);
+
+        let large = [
+            Counted(drops.clone()),
+            Counted(drops.clone()),
+            Counted(drops.clone()),
+        ];
+        let f = SmallFnOnce::new(move |n: usize| large.len() + n);
+        assert!(!f.is_inline());
+        assert_eq!(f.call(1), 4);
+        assert_eq!(drops.get(), 4);
+
+        let uncalled = Counted(drops.clone());
+        drop(SmallFnOnce::new(move |()| drop(uncalled)));
+        assert_eq!(drops.get(), 5);
+    }
+}
@@ -0,0 +1,248 @@
+use std::{
+    fmt,
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use crate::payload::{self, Payload};
+
+/**
+ * A future, as `Pin<Box<dyn Future<Output = T> + 'a>>`, stored inline if it's `Unpin` and fits
+ * in two words, next to a vtable pointer.
+ *
+ * A `SmallFuture` is itself `Unpin`, so can be polled without pinning it in place. That's only
+ * sound for an inline future that's `Unpin` too, so any other future is boxed, with `new_pinned`.
+ * An `async` block is never `Unpin`. Like `Erased`, a `SmallFuture` is neither `Send` nor
+ * `Sync`.
+ */
+pub struct SmallFuture<'a, T> {
+    payload: Payload,
+    // a reference would require T to be 'static
+    vtable: *const VTable<T>,
+    _phantom: PhantomData<(&'a (), *mut ())>,
+}
+
+struct VTable<T> {
+    poll: unsafe fn(*mut Payload, &mut Context<'_>) -> Poll<T>,
+    drop: unsafe fn(*mut Payload),
+    inline: bool,
+}
+
+struct VTableFor<F>(PhantomData<F>);
+
+impl<F: Future + Unpin> VTableFor<F> {
+    const VTABLE: VTable<F::Output> = VTable {
+        poll: poll::<F>,
+        drop: Payload::drop_in_place::<F>,
+        inline: payload::inlines::<F>(),
+    };
+
+    /**
+     * For an `F` that is itself a pinned box, which is inline, but the future it points to isn't.
+     */
+    const BOXED: VTable<F::Output> = VTable {
+        inline: false,
+        ..Self::VTABLE
+    };
+}
+
+/**
+ * Poll the `F` in the `Payload` at `payload`.
+ *
+ * Unsafe because `payload` must point to a `Payload` holding an `F`.
+ */
+unsafe fn poll<F: Future + Unpin>(payload: *mut Payload, cx: &mut Context<'_>) -> Poll<F::Output> {
+    Pin::new(&mut *Payload::resolve::<F>(payload)).poll(cx)
+}
+
+impl<'a, T> SmallFuture<'a, T> {
+    /**
+     * Wrap `f`, storing it inline if it fits in two words.
+     */
+    pub fn new<F>(f: F) -> Self
+    where
+        F: Future<Output = T> + Unpin + 'a,
+    {
+        Self {
+            payload: Payload::new(f),
+            vtable: &VTableFor::<F>::VTABLE,
+            _phantom: PhantomData,
+        }
+    }
+
+    /**
+     * Wrap `f`, which needn't be `Unpin`, pinning it in a box.
+     */
+    pub fn new_pinned<F>(f: F) -> Self
+    where
+        F: Future<Output = T> + 'a,
+    {
+        Self {
+            payload: Payload::new(Box::pin(f)),
+            vtable: &VTableFor::<Pin<Box<F>>>::BOXED,
+            _phantom: PhantomData,
+        }
+    }
+
+    /**
+     * Whether the future is stored inline.
+     */
+    pub fn is_inline(&self) -> bool {
+        self.vtable().inline
+    }
+
+    fn vtable(&self) -> &VTable<T> {
+        // SAFETY: points to a promoted constant
+        unsafe { &*self.vtable }
+    }
+}
+
+impl<T> Future for SmallFuture<'_, T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let this = self.get_mut();
+
+        // SAFETY: the vtable was created for our payload
+        unsafe { (this.vtable().poll)(&mut this.payload, cx) }
+    }
+}
+
+impl<T> Drop for SmallFuture<'_, T> {
+    fn drop(&mut self) {
+        // SAFETY: the vtable was created for our payload, which isn't used again
+        unsafe { (self.vtable().drop)(&mut self.payload) }
+    }
+}
+
+impl<T> fmt::Debug for SmallFuture<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SmallFuture").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::Cell,
+        future::{self, Future},
+        pin::pin,
+        task::{Context, Poll, Waker},
+    };
+
+    use super::*;
+
+    fn block_on<F: Future>(f: F) -> F::Output {
+        let mut f = pin!(f);
+        let mut cx = Context::from_waker(Waker::noop());
+
+        loop {
+            if let Poll::Ready(x) = f.as_mut().poll(&mut cx) {
+                return x;
+            }
+        }
+    }
+
+    /// Completes after returning `Pending` the given number of times.
+    struct Countdown(u32);
+
+    impl Future for Countdown {
+        type Output = &'static str;
+
+        fn poll(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<&'static str> {
+            if self.0 == 0 {
+                Poll::Ready("done")
+            } else {
+                self.0 -= 1;
+                Poll::Pending
+            }
+        }
+    }
+
+    trait Source {
+        fn fetch(&self) -> SmallFuture<'_, u32>;
+    }
+
+    struct Cached(u32);
+
+    struct Counted<'a>(&'a Cell<u32>);
+
+    impl Source for Cached {
+        fn fetch(&self) -> SmallFuture<'_, u32> {
+            SmallFuture::new(future::ready(self.0))
+        }
+    }
+
+    impl Source for Counted<'_> {
+        fn fetch(&self) -> SmallFuture<'_, u32> {
+            SmallFuture::new_pinned(async {
+                self.0.set(self.0.get() + 1);
+                self.0.get()
+            })
+        }
+    }
+
+    #[test]
+    fn polls_inline_futures() {
+        let mut f = SmallFuture::new(Countdown(2));
+        let mut cx = Context::from_waker(Waker::noop());
+
+        assert!(f.is_inline());
+        assert_eq!(Pin::new(&mut f).poll(&mut cx), Poll::Pending);
+
+        // `SmallFuture` is `Unpin`, so may move between polls
+        let mut moved = f;
+        assert_eq!(Pin::new(&mut moved).poll(&mut cx), Poll::Pending);
+        assert_eq!(Pin::new(&mut moved).poll(&mut cx), Poll::Ready("done"));
+    }
+
+    #[test]
+    fn boxes_unpinned_futures() {
+        let f = SmallFuture::new_pinned(async { 1 });
+        assert!(!f.is_inline());
+        assert_eq!(block_on(f), 1);
+
+        let large = [3u64; 4];
+        let f = SmallFuture::new(future::ready(large));
+        assert!(!f.is_inline());
+        assert_eq!(block_on(f), [3; 4]);
+    }
+
+    #[test]
+    fn returns_from_trait_methods() {
+        let count = Cell::new(0);
+        let sources: [&dyn Source; 2] = [&Cached(10), &Counted(&count)];
+
+        let total = block_on(async {
+            let mut total = 0;
+            for source in sources {
+                total += source.fetch().await;
+            }
+            total
+        });
+
+        assert_eq!(total, 11);
+        assert_eq!(count.get(), 1);
+    }
+
+    #[test]
+    fn drops_unpolled() {
+        let dropped = Cell::new(false);
+
+        struct SetOnDrop<'a>(&'a Cell<bool>);
+
+        impl Drop for SetOnDrop<'_> {
+            fn drop(&mut self) {
+                self.0.set(true);
+            }
+        }
+
+        let guard = SetOnDrop(&dropped);
+        drop(SmallFuture::new_pinned(async move {
+            let _guard = guard;
+        }));
+        assert!(dropped.get());
+    }
+}
@@ -0,0 +1,290 @@
+use std::{fmt, marker::PhantomData};
+
+use crate::payload::{self, Payload};
+
+/**
+ * An iterator, as `Box<dyn Iterator<Item = T> + 'a>`, stored inline if it fits in two words,
+ * next to a vtable pointer. Larger iterators are boxed.
+ *
+ * `DOUBLE_ENDED` and `EXACT_SIZE` record whether the wrapped iterator is a `DoubleEndedIterator`
+ * or an `ExactSizeIterator`, in which case so is the `SmallIter`. `new` sets neither, see
+ * `new_double_ended`, `new_exact_size` and `new_double_ended_exact_size`. Like `Erased`, a
+ * `SmallIter` is neither `Send` nor `Sync`.
+ */
+pub struct SmallIter<'a, T, const DOUBLE_ENDED: bool = false, const EXACT_SIZE: bool = false> {
+    payload: Payload,
+    // a reference would require T to be 'static
+    vtable: *const VTable<T>,
+    _phantom: PhantomData<(&'a (), *mut ())>,
+}
+
+struct VTable<T> {
+    next: unsafe fn(*mut Payload) -> Option<T>,
+    size_hint: unsafe fn(*mut Payload) -> (usize, Option<usize>),
+    // present when DOUBLE_ENDED
+    next_back: Option<unsafe fn(*mut Payload) -> Option<T>>,
+    // present when EXACT_SIZE
+    len: Option<unsafe fn(*mut Payload) -> usize>,
+    drop: unsafe fn(*mut Payload),
+    inline: bool,
+}
+
+/**
+ * Advance the `I` in the `Payload` at `payload`.
+ *
+ * Unsafe because `payload` must point to a `Payload` holding an `I`, as for the functions below.
+ */
+unsafe fn next<I: Iterator>(payload: *mut Payload) -> Option<I::Item> {
+    (*Payload::resolve::<I>(payload)).next()
+}
+
+unsafe fn size_hint<I: Iterator>(payload: *mut Payload) -> (usize, Option<usize>) {
+    (*Payload::resolve::<I>(payload)).size_hint()
+}
+
+unsafe fn next_back<I: DoubleEndedIterator>(payload: *mut Payload) -> Option<I::Item> {
+    (*Payload::resolve::<I>(payload)).next_back()
+}
+
+unsafe fn len<I: ExactSizeIterator>(payload: *mut Payload) -> usize {
+    (*Payload::resolve::<I>(payload)).len()
+}
+
+struct VTableFor<I>(PhantomData<I>);
+
+impl<I: Iterator> VTableFor<I> {
+    const VTABLE: VTable<I::Item> = VTable {
+        next: next::<I>,
+        size_hint: size_hint::<I>,
+        next_back: None,
+        len: None,
+        drop: Payload::drop_in_place::<I>,
+        inline: payload::inlines::<I>(),
+    };
+}
+
+impl<I: DoubleEndedIterator> VTableFor<I> {
+    const DOUBLE_ENDED: VTable<I::Item> = VTable {
+        next_back: Some(next_back::<I>),
+        ..Self::VTABLE
+    };
+}
+
+impl<I: ExactSizeIterator> VTableFor<I> {
+    const EXACT_SIZE: VTable<I::Item> = VTable {
+        len: Some(len::<I>),
+        ..Self::VTABLE
+    };
+}
+
+impl<I: DoubleEndedIterator + ExactSizeIterator> VTableFor<I> {
+    const DOUBLE_ENDED_EXACT_SIZE: VTable<I::Item> = VTable {
+        len: Self::EXACT_SIZE.len,
+        ..Self::DOUBLE_ENDED
+    };
+}
+
+impl<'a, T, const DOUBLE_ENDED: bool, const EXACT_SIZE: bool>
+    SmallIter<'a, T, DOUBLE_ENDED, EXACT_SIZE>
+{
+    /**
+     * Create a `SmallIter` for `payload`, holding an iterator described by `vtable`.
+     *
+     * Unsafe because `vtable` must point to a constant for the payload's iterator, with the
+     * entries our flags require.
+     */
+    unsafe fn from_parts(payload: Payload, vtable: *const VTable<T>) -> Self {
+        Self {
+            payload,
+            vtable,
+            _phantom: PhantomData,
+        }
+    }
+
+    /**
+     * Whether the iterator is stored inline.
+     */
+    pub fn is_inline(&self) -> bool {
+        self.vtable().inline
+    }
+
+    fn vtable(&self) -> &VTable<T> {
+        // SAFETY: points to a promoted constant
+        unsafe { &*self.vtable }
+    }
+
+    fn payload_ptr(&self) -> *mut Payload {
+        // only read through, by the vtable's `size_hint` and `len`
+        &self.payload as *const Payload as *mut Payload
+    }
+}
+
+impl<'a, T> SmallIter<'a, T> {
+    /**
+     * Wrap `iter`, storing it inline if it fits in two words.
+     */
+    pub fn new<I: Iterator<Item = T> + 'a>(iter: I) -> Self {
+        // SAFETY: the vtable is for I
+        unsafe { Self::from_parts(Payload::new(iter), &VTableFor::<I>::VTABLE) }
+    }
+}
+
+impl<'a, T> SmallIter<'a, T, true, false> {
+    /**
+     * As `new`, also forwarding `DoubleEndedIterator`.
+     */
+    pub fn new_double_ended<I>(iter: I) -> Self
+    where
+        I: DoubleEndedIterator<Item = T> + 'a,
+    {
+        // SAFETY: the vtable is for I, with `next_back`
+        unsafe { Self::from_parts(Payload::new(iter), &VTableFor::<I>::DOUBLE_ENDED) }
+    }
+}
+
+impl<'a, T> SmallIter<'a, T, false, true> {
+    /**
+     * As `new`, also forwarding `ExactSizeIterator`.
+     */
+    pub fn new_exact_size<I>(iter: I) -> Self
+    where
+        I: ExactSizeIterator<Item = T> + 'a,
+    {
+        // SAFETY: the vtable is for I, with `len`
+        unsafe { Self::from_parts(Payload::new(iter), &VTableFor::<I>::EXACT_SIZE) }
+    }
+}
+
+impl<'a, T> SmallIter<'a, T, true, true> {
+    /**
+     * As `new`, also forwarding `DoubleEndedIterator` and `ExactSizeIterator`.
+     */
+    pub fn new_double_ended_exact_size<I>(iter: I) -> Self
+    where
+        I: DoubleEndedIterator<Item = T> + ExactSizeIterator + 'a,
+    {
+        // SAFETY: the vtable is for I, with `next_back` and `len`
+        unsafe { Self::from_parts(Payload::new(iter), &VTableFor::<I>::DOUBLE_ENDED_EXACT_SIZE) }
+    }
+}
+
+impl<T, const DOUBLE_ENDED: bool, const EXACT_SIZE: bool> Iterator
+    for SmallIter<'_, T, DOUBLE_ENDED, EXACT_SIZE>
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        // SAFETY: the vtable was created for our payload
+        unsafe { (self.vtable().next)(&mut self.payload) }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // SAFETY: the vtable was created for our payload
+        unsafe { (self.vtable().size_hint)(self.payload_ptr()) }
+    }
+}
+
+impl<T, const EXACT_SIZE: bool> DoubleEndedIterator for SmallIter<'_, T, true, EXACT_SIZE> {
+    fn next_back(&mut self) -> Option<T> {
+        let next_back = self.vtable().next_back.expect("created with next_back");
+
+        // SAFETY: the vtable was created for our payload
+        unsafe { next_back(&mut self.payload) }
+    }
+}
+
+impl<T, const DOUBLE_ENDED: bool> ExactSizeIterator for SmallIter<'_, T, DOUBLE_ENDED, true> {
+    fn len(&self) -> usize {
+        let len = self.vtable().len.expect("created with len");
+
+        // SAFETY: the vtable was created for our payload
+        unsafe { len(self.payload_ptr()) }
+    }
+}
+
+impl<T, const DOUBLE_ENDED: bool, const EXACT_SIZE: bool> Drop
+    for SmallIter<'_, T, DOUBLE_ENDED, EXACT_SIZE>
+{
+    fn drop(&mut self) {
+        // SAFETY: the vtable was created for our payload, which isn't used again
+        unsafe { (self.vtable().drop)(&mut self.payload) }
+    }
+}
+
+impl<T, const DOUBLE_ENDED: bool, const EXACT_SIZE: bool> fmt::Debug
+    for SmallIter<'_, T, DOUBLE_ENDED, EXACT_SIZE>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SmallIter").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn evens(v: &[u32]) -> SmallIter<'_, u32> {
+        SmallIter::new(v.iter().copied().filter(|x| x % 2 == 0))
+    }
+
+    fn digits(n: u32) -> SmallIter<'static, u32, true, true> {
+        if n < 10 {
+            SmallIter::new_double_ended_exact_size(n..n + 1)
+        } else {
+            SmallIter::new_double_ended_exact_size(
+                n.to_string()
+                    .into_bytes()
+                    .into_iter()
+                    .map(|b| u32::from(b - b'0')),
+            )
+        }
+    }
+
+    #[test]
+    fn iterates() {
+        let v = [1, 2, 3, 4];
+        let iter = evens(&v);
+
+        assert!(iter.is_inline());
+        assert_eq!(iter.collect::<Vec<_>>(), [2, 4]);
+        assert_eq!(
+            std::mem::size_of::<SmallIter<u32>>(),
+            3 * std::mem::size_of::<usize>()
+        );
+    }
+
+    #[test]
+    fn forwards_double_ended_and_exact_size() {
+        let mut one = digits(7);
+        let mut many = digits(1234);
+
+        assert!(one.is_inline());
+        assert!(!many.is_inline());
+
+        assert_eq!(many.len(), 4);
+        assert_eq!(many.next_back(), Some(4));
+        assert_eq!(many.next(), Some(1));
+        assert_eq!(many.size_hint(), (2, Some(2)));
+        assert_eq!(many.rev().collect::<Vec<_>>(), [3, 2]);
+
+        assert_eq!(one.len(), 1);
+        assert_eq!(one.next_back(), Some(7));
+        assert_eq!(one.next(), None);
+
+        let mut rev = SmallIter::new_double_ended("abc".chars());
+        assert_eq!(rev.next_back(), Some('c'));
+        assert_eq!(rev.collect::<String>(), "ab");
+
+        let exact = SmallIter::new_exact_size(0..5);
+        assert_eq!(exact.len(), 5);
+    }
+
+    #[test]
+    fn drops_remaining_items() {
+        let items = vec![String::from("a"), String::from("b"), String::from("c")];
+        let mut iter = SmallIter::new(items.into_iter());
+
+        assert_eq!(iter.next().as_deref(), Some("a"));
+        drop(iter);
+    }
+}
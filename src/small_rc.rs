@@ -0,0 +1,263 @@
+use std::{
+    borrow::Borrow,
+    cell::Cell,
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    mem,
+    ops::Deref,
+    ptr::{self, NonNull},
+};
+
+/**
+ * A single-threaded reference-counted pointer, as `Rc<T>`, but without weak references.
+ *
+ * The count and the value share one allocation, as with `Rc<T>`, but with no weak count it's a
+ * word smaller, so a `SmallRc<u32>` allocates 16 bytes rather than 24, and the pointer is a single
+ * word.
+ */
+pub struct SmallRc<T> {
+    ptr: NonNull<RcBox<T>>,
+    _phantom: PhantomData<RcBox<T>>,
+}
+
+struct RcBox<T> {
+    count: Cell<usize>,
+    value: T,
+}
+
+impl<T> SmallRc<T> {
+    /**
+     * Create a `SmallRc<T>` containing `t`, with a count of one.
+     */
+    pub fn new(t: T) -> Self {
+        let rc_box = Box::new(RcBox {
+            count: Cell::new(1),
+            value: t,
+        });
+
+        Self {
+            ptr: NonNull::from(Box::leak(rc_box)),
+            _phantom: PhantomData,
+        }
+    }
+
+    /**
+     * The number of `SmallRc`s pointing to this value.
+     */
+    pub fn strong_count(this: &Self) -> usize {
+        this.rc_box().count.get()
+    }
+
+    /**
+     * Convert to the contained `T`, if this is the only `SmallRc` pointing to it, otherwise return
+     * `this`.
+     */
+    pub fn try_unwrap(this: Self) -> Result<T, Self> {
+        if Self::strong_count(&this) != 1 {
+            return Err(this);
+        }
+
+        let ptr = this.ptr;
+        mem::forget(this);
+
+        // SAFETY: we were the only reference, so own the box
+        let rc_box = unsafe { Box::from_raw(ptr.as_ptr()) };
+        Ok(rc_box.value)
+    }
+
+    /**
+     * Convert to the contained `T`, if this is the only `SmallRc` pointing to it, otherwise
+     * decrement the count and return `None`.
+     */
+    pub fn into_inner(this: Self) -> Option<T> {
+        Self::try_unwrap(this).ok()
+    }
+
+    /**
+     * Get a mutable reference to the value, if this is the only `SmallRc` pointing to it.
+     */
+    pub fn get_mut(this: &mut Self) -> Option<&mut T> {
+        if Self::strong_count(this) != 1 {
+            return None;
+        }
+
+        // SAFETY: we're the only reference, and are mutably borrowed
+        Some(unsafe { &mut (*this.ptr.as_ptr()).value })
+    }
+
+    /**
+     * Get a mutable reference to the value, cloning it first if other `SmallRc`s point to it.
+     */
+    pub fn make_mut(this: &mut Self) -> &mut T
+    where
+        T: Clone,
+    {
+        if Self::strong_count(this) != 1 {
+            *this = Self::new((**this).clone());
+        }
+
+        Self::get_mut(this).unwrap()
+    }
+
+    /**
+     * Whether two `SmallRc`s point to the same value.
+     */
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        ptr::eq(this.ptr.as_ptr(), other.ptr.as_ptr())
+    }
+
+    fn rc_box(&self) -> &RcBox<T> {
+        // SAFETY: the box is live while any SmallRc points to it
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl<T> Clone for SmallRc<T> {
+    fn clone(&self) -> Self {
+        let count = &self.rc_box().count;
+        count.set(count.get().checked_add(1).expect("SmallRc count overflow"));
+
+        Self {
+            ptr: self.ptr,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T> Drop for SmallRc<T> {
+    fn drop(&mut self) {
+        let count = &self.rc_box().count;
+        count.set(count.get() - 1);
+
+        if count.get() == 0 {
+            // SAFETY: we were the last reference
+            drop(unsafe { Box::from_raw(self.ptr.as_ptr()) });
+        }
+    }
+}
+
+impl<T> Deref for SmallRc<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.rc_box().value
+    }
+}
+
+impl<T> AsRef<T> for SmallRc<T> {
+    fn as_ref(&self) -> &T {
+        self
+    }
+}
+
+impl<T> Borrow<T> for SmallRc<T> {
+    fn borrow(&self) -> &T {
+        self
+    }
+}
+
+impl<T: Default> Default for SmallRc<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T> From<T> for SmallRc<T> {
+    fn from(t: T) -> Self {
+        Self::new(t)
+    }
+}
+
+impl<T: PartialEq> PartialEq for SmallRc<T> {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl<T: Eq> Eq for SmallRc<T> {}
+
+impl<T: PartialOrd> PartialOrd for SmallRc<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        (**self).partial_cmp(&**other)
+    }
+}
+
+impl<T: Ord> Ord for SmallRc<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (**self).cmp(&**other)
+    }
+}
+
+impl<T: Hash> Hash for SmallRc<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for SmallRc<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for SmallRc<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+
+    #[test]
+    fn counts_references() {
+        let a = SmallRc::new(5u32);
+        let b = a.clone();
+
+        assert_eq!(SmallRc::strong_count(&a), 2);
+        assert!(SmallRc::ptr_eq(&a, &b));
+        assert_eq!(*b + 1, 6);
+
+        let a = SmallRc::try_unwrap(a).unwrap_err();
+        drop(b);
+        assert_eq!(SmallRc::strong_count(&a), 1);
+        assert_eq!(SmallRc::try_unwrap(a), Ok(5));
+
+        assert_eq!(mem::size_of::<SmallRc<u32>>(), mem::size_of::<usize>());
+        assert_eq!(mem::size_of::<RcBox<u32>>(), 2 * mem::size_of::<usize>());
+    }
+
+    #[test]
+    fn drops_with_the_last_reference() {
+        let rc = Rc::new(());
+        let a = SmallRc::new(rc.clone());
+        let b = a.clone();
+
+        drop(a);
+        assert_eq!(Rc::strong_count(&rc), 2);
+        assert_eq!(
+            SmallRc::into_inner(b).map(|rc| Rc::strong_count(&rc)),
+            Some(2)
+        );
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+
+    #[test]
+    fn clones_on_write() {
+        let mut a = SmallRc::new(vec![1]);
+        let b = a.clone();
+
+        assert!(SmallRc::get_mut(&mut a).is_none());
+        SmallRc::make_mut(&mut a).push(2);
+        assert!(!SmallRc::ptr_eq(&a, &b));
+        assert_eq!((&*a, &*b), (&vec![1, 2], &vec![1]));
+
+        SmallRc::get_mut(&mut a).unwrap().push(3);
+        assert_eq!(format!("{a:?}"), "[1, 2, 3]");
+    }
+}
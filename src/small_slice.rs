@@ -0,0 +1,401 @@
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+    iter::FromIterator,
+    marker::PhantomData,
+    mem::{self, ManuallyDrop, MaybeUninit},
+    ops::{Deref, DerefMut},
+    ptr::{self, NonNull},
+};
+
+use crate::erased::Words;
+use crate::limits::{NWORDS, SIZE_LIMIT};
+use crate::Erased;
+
+/**
+ * The byte which tags the storage: for an inline slice, `INLINE_TAG` with the length, and
+ * otherwise zero, the last byte of `Spilled::tag`.
+ */
+const TAG: usize = SIZE_LIMIT - 1;
+
+/**
+ * Set in the tag of an inline slice, alongside its length.
+ */
+const INLINE_TAG: u8 = 0x80;
+
+/**
+ * A slice, stored within three words if it's short enough, otherwise as a `Box<[T]>`.
+ *
+ * As many `T`s as fit before the last byte are stored inline, with that byte recording the
+ * length: 23 `u8`s, 5 `u32`s, or 2 `u64`s on 64-bit. Zero-sized `T`s, and `T`s more aligned than
+ * a word, are always boxed, which doesn't allocate for the former.
+ */
+pub struct SmallSlice<T> {
+    erased: Erased,
+    _phantom: PhantomData<T>,
+}
+
+#[repr(C)]
+struct Spilled<T> {
+    ptr: NonNull<T>,
+    len: usize,
+    tag: usize,
+}
+
+// SAFETY: owns its Ts
+unsafe impl<T: Send> Send for SmallSlice<T> {}
+unsafe impl<T: Sync> Sync for SmallSlice<T> {}
+
+impl<T> SmallSlice<T> {
+    /**
+     * The most `T`s which are stored inline.
+     */
+    pub const INLINE_CAPACITY: usize =
+        if mem::size_of::<T>() == 0 || mem::align_of::<T>() > mem::align_of::<usize>() {
+            0
+        } else {
+            TAG / mem::size_of::<T>()
+        };
+
+    /**
+     * Create an empty `SmallSlice`.
+     */
+    pub fn new() -> Self {
+        if Self::INLINE_CAPACITY == 0 {
+            Self::spill(Box::new([]))
+        } else {
+            let mut this = Self {
+                erased: Erased::new_inline([MaybeUninit::<usize>::uninit(); NWORDS]),
+                _phantom: PhantomData,
+            };
+            // SAFETY: no Ts are initialised yet
+            unsafe { this.set_inline_len(0) };
+            this
+        }
+    }
+
+    fn spill(slice: Box<[T]>) -> Self {
+        let len = slice.len();
+        // SAFETY: from a Box, so non-null
+        let ptr = unsafe { NonNull::new_unchecked(Box::into_raw(slice) as *mut T) };
+
+        Self {
+            erased: Erased::new_inline(Spilled { ptr, len, tag: 0 }),
+            _phantom: PhantomData,
+        }
+    }
+
+    fn words(&self) -> *const u8 {
+        // SAFETY: always created inline, via new_inline, at the size of the whole storage
+        unsafe { self.erased.as_ptr::<Words>() as *const u8 }
+    }
+
+    fn words_mut(&mut self) -> *mut u8 {
+        // SAFETY: as words()
+        unsafe { self.erased.as_mut_ptr::<Words>() as *mut u8 }
+    }
+
+    fn tag(&self) -> u8 {
+        // SAFETY: the tag byte is always initialised
+        unsafe { *self.words().add(TAG) }
+    }
+
+    /**
+     * Record the number of inline `T`s.
+     *
+     * Unsafe because the slice must be inline, and the first `len` `T`s initialised.
+     */
+    unsafe fn set_inline_len(&mut self, len: usize) {
+        *self.words_mut().add(TAG) = INLINE_TAG | len as u8;
+    }
+
+    /**
+     * Whether the slice is stored inline.
+     */
+    pub fn is_inline(&self) -> bool {
+        self.tag() & INLINE_TAG != 0
+    }
+
+    /**
+     * Get the slice.
+     */
+    pub fn as_slice(&self) -> &[T] {
+        // SAFETY: inline, the words start with the tagged number of Ts. Otherwise, they're a
+        // Spilled
+        unsafe {
+            if self.is_inline() {
+                let len = (self.tag() & !INLINE_TAG) as usize;
+                &*ptr::slice_from_raw_parts(self.words() as *const T, len)
+            } else {
+                let spilled = &*self.erased.as_ptr::<Spilled<T>>();
+                &*ptr::slice_from_raw_parts(spilled.ptr.as_ptr(), spilled.len)
+            }
+        }
+    }
+
+    /**
+     * Get the slice, mutably.
+     */
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        // SAFETY: as as_slice()
+        unsafe {
+            if self.is_inline() {
+                let len = (self.tag() & !INLINE_TAG) as usize;
+                &mut *ptr::slice_from_raw_parts_mut(self.words_mut() as *mut T, len)
+            } else {
+                let spilled = &*self.erased.as_ptr::<Spilled<T>>();
+                &mut *ptr::slice_from_raw_parts_mut(spilled.ptr.as_ptr(), spilled.len)
+            }
+        }
+    }
+
+    /**
+     * Create a `SmallSlice` from a `Box<[T]>`, moving short slices inline and freeing the box.
+     */
+    pub fn from_boxed_slice(slice: Box<[T]>) -> Self {
+        if slice.len() > Self::INLINE_CAPACITY {
+            return Self::spill(slice);
+        }
+
+        let mut this = Self::new();
+        let len = slice.len();
+        // moving the Ts out, so the box mustn't drop them
+        let slice = Box::into_raw(slice) as *mut [ManuallyDrop<T>];
+
+        // SAFETY: the Ts fit, and are moved from the box, which is then freed
+        unsafe {
+            ptr::copy_nonoverlapping(slice as *const T, this.words_mut() as *mut T, len);
+            this.set_inline_len(len);
+            drop(Box::from_raw(slice));
+        }
+        this
+    }
+
+    /**
+     * Convert to a `Box<[T]>`, which allocates if the slice is inline.
+     */
+    pub fn into_boxed_slice(self) -> Box<[T]> {
+        let mut this = ManuallyDrop::new(self);
+
+        if this.is_inline() {
+            let slice = this.as_mut_slice();
+            let mut v = Vec::with_capacity(slice.len());
+
+            // SAFETY: `this` is never dropped, so ownership of the Ts moves to the Vec
+            unsafe {
+                ptr::copy_nonoverlapping(slice.as_ptr(), v.as_mut_ptr(), slice.len());
+                v.set_len(slice.len());
+            }
+            v.into_boxed_slice()
+        } else {
+            // SAFETY: spilled from a Box, and `this` is never dropped, so ownership moves here
+            unsafe {
+                let spilled = ptr::read(this.erased.as_ptr::<Spilled<T>>());
+                Box::from_raw(ptr::slice_from_raw_parts_mut(
+                    spilled.ptr.as_ptr(),
+                    spilled.len,
+                ))
+            }
+        }
+    }
+}
+
+impl<T> Drop for SmallSlice<T> {
+    fn drop(&mut self) {
+        if self.is_inline() {
+            // SAFETY: the inline Ts aren't used again
+            unsafe { ptr::drop_in_place(self.as_mut_slice()) }
+        } else {
+            // SAFETY: spilled from a Box, and not used again
+            unsafe {
+                let spilled = &*self.erased.as_ptr::<Spilled<T>>();
+                drop(Box::from_raw(ptr::slice_from_raw_parts_mut(
+                    spilled.ptr.as_ptr(),
+                    spilled.len,
+                )));
+            }
+        }
+    }
+}
+
+impl<T> Default for SmallSlice<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Deref for SmallSlice<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T> DerefMut for SmallSlice<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+/**
+ * Fills the inline storage first, only allocating once it's full.
+ */
+impl<T> FromIterator<T> for SmallSlice<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut iter = iter.into_iter();
+        let mut this = Self::new();
+
+        for len in 0..Self::INLINE_CAPACITY {
+            let Some(t) = iter.next() else {
+                return this;
+            };
+
+            // SAFETY: within capacity, and the length is updated as each T is written, so a
+            // panicking iterator drops them
+            unsafe {
+                ptr::write((this.words_mut() as *mut T).add(len), t);
+                this.set_inline_len(len + 1);
+            }
+        }
+
+        match iter.next() {
+            None => this,
+            Some(t) => {
+                let mut v = Vec::from(this.into_boxed_slice());
+                v.push(t);
+                v.extend(iter);
+                Self::spill(v.into_boxed_slice())
+            }
+        }
+    }
+}
+
+/**
+ * Keeps the `Vec`'s allocation, shrunk to fit, if the slice is too long to be inline.
+ */
+impl<T> From<Vec<T>> for SmallSlice<T> {
+    fn from(v: Vec<T>) -> Self {
+        Self::from_boxed_slice(v.into_boxed_slice())
+    }
+}
+
+impl<T> From<Box<[T]>> for SmallSlice<T> {
+    fn from(slice: Box<[T]>) -> Self {
+        Self::from_boxed_slice(slice)
+    }
+}
+
+impl<T: Clone> From<&[T]> for SmallSlice<T> {
+    fn from(slice: &[T]) -> Self {
+        slice.iter().cloned().collect()
+    }
+}
+
+impl<T> From<SmallSlice<T>> for Vec<T> {
+    fn from(slice: SmallSlice<T>) -> Self {
+        slice.into_boxed_slice().into()
+    }
+}
+
+impl<T: Clone> Clone for SmallSlice<T> {
+    fn clone(&self) -> Self {
+        self.as_slice().into()
+    }
+}
+
+impl<T: PartialEq> PartialEq for SmallSlice<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<T: Eq> Eq for SmallSlice<T> {}
+
+impl<T: Hash> Hash for SmallSlice<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for SmallSlice<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_slice(), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{panic, rc::Rc};
+
+    use super::*;
+
+    #[test]
+    fn stores_short_slices_inline() {
+        let bytes = (0..SmallSlice::<u8>::INLINE_CAPACITY as u8).collect::<SmallSlice<_>>();
+        let words = SmallSlice::from(vec![1u64, 2]);
+        let long = (0..10u32).collect::<SmallSlice<_>>();
+
+        assert!(bytes.is_inline());
+        assert!(words.is_inline());
+        assert!(!long.is_inline());
+
+        assert_eq!(bytes.len(), SmallSlice::<u8>::INLINE_CAPACITY);
+        assert_eq!(*words, [1, 2]);
+        assert_eq!(long.iter().sum::<u32>(), 45);
+        assert!(SmallSlice::<u32>::new().is_empty());
+    }
+
+    #[test]
+    fn handles_unusual_types() {
+        let units = SmallSlice::from(vec![(); 100]);
+        assert!(!units.is_inline());
+        assert_eq!(units.len(), 100);
+
+        #[derive(Clone, Copy, PartialEq, Debug)]
+        #[repr(align(32))]
+        struct Aligned(u8);
+
+        let aligned = SmallSlice::from(&[Aligned(1)][..]);
+        assert!(!aligned.is_inline());
+        assert_eq!(*aligned, [Aligned(1)]);
+    }
+
+    #[test]
+    fn converts() {
+        let v = vec![String::from("a"); 4];
+        let ptr = v.as_ptr();
+
+        let mut slice = SmallSlice::from(v);
+        assert_eq!(slice.as_ptr(), ptr);
+        slice[0].push('!');
+
+        let short = SmallSlice::from(vec![Box::<str>::from("b")]);
+        assert!(short.is_inline());
+        assert_eq!(short.clone(), short);
+
+        assert_eq!(Vec::from(slice), ["a!", "a", "a", "a"]);
+        assert_eq!(&*short.into_boxed_slice(), [Box::from("b")]);
+    }
+
+    #[test]
+    fn drops() {
+        let rc = Rc::new(());
+
+        let inline = SmallSlice::from(vec![rc.clone(); 2]);
+        let spilled = SmallSlice::from(vec![rc.clone(); 8]);
+        assert_eq!(Rc::strong_count(&rc), 11);
+
+        drop(inline);
+        drop(spilled);
+        assert_eq!(Rc::strong_count(&rc), 1);
+
+        let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            (0..3)
+                .map(|i| if i < 2 { rc.clone() } else { panic!() })
+                .collect::<SmallSlice<_>>()
+        }));
+        assert!(result.is_err());
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+}
@@ -0,0 +1,234 @@
+use std::{
+    borrow::Borrow,
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    ops::Deref,
+    str,
+};
+
+use crate::SmallSlice;
+
+/**
+ * A string, stored within three words if it's short enough, otherwise as a `Box<str>`.
+ *
+ * Up to 23 bytes (11 on 32-bit) are stored inline, with the last byte recording the length, so
+ * short strings such as identifiers don't allocate.
+ */
+#[derive(Clone)]
+pub struct SmallStr {
+    bytes: SmallSlice<u8>,
+}
+
+impl SmallStr {
+    /**
+     * Create a `SmallStr` from a `&str`, copying it.
+     */
+    pub fn new(s: &str) -> Self {
+        Self {
+            bytes: s.as_bytes().into(),
+        }
+    }
+
+    /**
+     * The longest string which is stored inline.
+     */
+    pub const INLINE_CAPACITY: usize = SmallSlice::<u8>::INLINE_CAPACITY;
+
+    /**
+     * Whether the string is stored inline.
+     */
+    pub fn is_inline(&self) -> bool {
+        self.bytes.is_inline()
+    }
+
+    /**
+     * Get the string.
+     */
+    pub fn as_str(&self) -> &str {
+        // SAFETY: created from a str
+        unsafe { str::from_utf8_unchecked(&self.bytes) }
+    }
+
+    /**
+     * Convert to a `Box<str>`, which allocates if the string is inline.
+     */
+    pub fn into_boxed_str(self) -> Box<str> {
+        // SAFETY: created from a str
+        unsafe { str::from_boxed_utf8_unchecked(self.bytes.into_boxed_slice()) }
+    }
+}
+
+impl Deref for SmallStr {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for SmallStr {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Borrow<str> for SmallStr {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Default for SmallStr {
+    fn default() -> Self {
+        Self::new("")
+    }
+}
+
+impl From<&str> for SmallStr {
+    fn from(s: &str) -> Self {
+        Self::new(s)
+    }
+}
+
+/**
+ * Reuses the `String`'s allocation, if the string is too long to be inline.
+ */
+impl From<String> for SmallStr {
+    fn from(s: String) -> Self {
+        s.into_boxed_str().into()
+    }
+}
+
+/**
+ * Reuses the allocation, if the string is too long to be inline.
+ */
+impl From<Box<str>> for SmallStr {
+    fn from(s: Box<str>) -> Self {
+        Self {
+            bytes: s.into_boxed_bytes().into(),
+        }
+    }
+}
+
+impl From<SmallStr> for String {
+    fn from(s: SmallStr) -> Self {
+        s.into_boxed_str().into()
+    }
+}
+
+impl PartialEq for SmallStr {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for SmallStr {}
+
+impl PartialEq<str> for SmallStr {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for SmallStr {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialOrd for SmallStr {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SmallStr {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+/**
+ * Hashes as a `str`, so a `SmallStr` key can be looked up by `&str`.
+ */
+impl Hash for SmallStr {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+impl fmt::Debug for SmallStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for SmallStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn stores_short_strings_inline() {
+        let empty = SmallStr::default();
+        let ident = SmallStr::from("ident");
+        let full = SmallStr::new(&"x".repeat(SmallStr::INLINE_CAPACITY));
+        let long = SmallStr::new(&"y".repeat(SmallStr::INLINE_CAPACITY + 1));
+
+        assert!(empty.is_inline());
+        assert!(ident.is_inline());
+        assert!(full.is_inline());
+        assert!(!long.is_inline());
+
+        assert_eq!(empty, "");
+        assert_eq!(ident, "ident");
+        assert_eq!(full.len(), SmallStr::INLINE_CAPACITY);
+        assert_eq!(long.len(), SmallStr::INLINE_CAPACITY + 1);
+        assert_eq!(
+            std::mem::size_of::<SmallStr>(),
+            std::mem::size_of::<crate::Erased>()
+        );
+    }
+
+    #[test]
+    fn converts() {
+        let long = "a string too long to be stored inline";
+        let owned = String::from(long);
+        let ptr = owned.as_ptr();
+
+        let s = SmallStr::from(owned);
+        assert_eq!(s.as_ptr(), ptr);
+        assert_eq!(s.clone(), long);
+        assert_eq!(String::from(s), long);
+
+        let s = SmallStr::from(String::from("short"));
+        assert!(s.is_inline());
+        assert_eq!(&*s.into_boxed_str(), "short");
+    }
+
+    #[test]
+    fn keys_maps() {
+        let mut symbols = HashMap::new();
+        symbols.insert(SmallStr::from("x"), 1);
+        symbols.insert(SmallStr::from("a_rather_long_identifier_name"), 2);
+
+        assert_eq!(symbols["x"], 1);
+        assert_eq!(symbols["a_rather_long_identifier_name"], 2);
+        assert_eq!(
+            format!("{:?} {}", SmallStr::from("q\""), SmallStr::from("d")),
+            r#""q\"" d"#
+        );
+
+        let mut sorted = ["b", "c", "a"].map(SmallStr::from);
+        sorted.sort();
+        assert_eq!(sorted, ["a", "b", "c"].map(SmallStr::from));
+    }
+}
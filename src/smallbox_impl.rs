@@ -0,0 +1,146 @@
+/**
+ * `smallbox` support, for moving values between `Trident`s and `SmallBox`es.
+ */
+use std::mem;
+
+use smallbox::SmallBox;
+
+use crate::{Erased, Trident};
+
+/**
+ * Whether a heap `T` can be handed between a `SmallBox` and a `Box` without being moved.
+ *
+ * `SmallBox` allocates with an alignment of at least 2, so a byte-aligned `T` is freed with a
+ * different layout than `Box` would use, and must be moved instead. A zero-sized `T` has no
+ * allocation to share.
+ */
+const fn shares_allocation<T>() -> bool {
+    mem::align_of::<T>() >= 2 && mem::size_of::<T>() != 0
+}
+
+/**
+ * Adopts the `SmallBox`'s allocation if the `T` is on the heap and too large to be stored inline.
+ * Otherwise, the `T` is moved.
+ */
+impl<T, Space> From<SmallBox<T, Space>> for Trident<T> {
+    fn from(small: SmallBox<T, Space>) -> Self {
+        if small.is_heap() && !Self::IS_INLINE && shares_allocation::<T>() {
+            Trident::from(SmallBox::into_box(small))
+        } else {
+            Trident::new(small.into_inner())
+        }
+    }
+}
+
+/**
+ * Hands over our allocation if the `T` is boxed and too large for `Space`. Otherwise, the `T` is
+ * moved.
+ */
+impl<T, Space> From<Trident<T>> for SmallBox<T, Space> {
+    fn from(t: Trident<T>) -> Self {
+        let fits = mem::size_of::<T>() <= mem::size_of::<Space>()
+            && mem::align_of::<T>() <= mem::align_of::<Space>();
+
+        if !t.is_inline() && !fits && shares_allocation::<T>() {
+            SmallBox::from_box(t.into_box())
+        } else {
+            SmallBox::new(t.into_inner())
+        }
+    }
+}
+
+impl Erased {
+    /**
+     * Create an `Erased` from a `SmallBox<T, _>`, adopting its allocation as `Trident::from` does.
+     *
+     * T's destructor cannot be run, as the type is erased.
+     */
+    pub fn from_smallbox<T, Space>(small: SmallBox<T, Space>) -> Self {
+        Trident::<T>::from(small).into_erased()
+    }
+
+    /**
+     * Convert to a `SmallBox<T, _>`, handing over our allocation as `SmallBox::from` does.
+     *
+     * # Safety
+     *
+     * Unsafe because we don't know that this is the same `T` that this `Erased` was created with.
+     */
+    pub unsafe fn into_smallbox<T, Space>(self) -> SmallBox<T, Space> {
+        self.into_trident::<T>().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use smallbox::space::{S1, S4};
+
+    use super::*;
+
+    struct Counted(Rc<Cell<u32>>, #[allow(dead_code)] [u64; 4]);
+
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    #[test]
+    fn reuses_heap_allocations() {
+        let small: SmallBox<[u64; 8], S1> = SmallBox::new([1; 8]);
+        assert!(small.is_heap());
+        let addr = &*small as *const [u64; 8];
+
+        let t = Trident::<[u64; 8]>::from(small);
+        assert!(!t.is_inline());
+        assert_eq!(t.as_ptr(), addr);
+
+        let small: SmallBox<[u64; 8], S1> = t.into();
+        assert!(small.is_heap());
+        assert_eq!(&*small as *const [u64; 8], addr);
+        assert_eq!(*small, [1; 8]);
+    }
+
+    #[test]
+    fn moves_inline_values() {
+        let small: SmallBox<[u64; 2], S1> = SmallBox::new([2; 2]);
+        assert!(small.is_heap());
+
+        let t = Trident::<[u64; 2]>::from(small);
+        assert!(t.is_inline());
+
+        let small: SmallBox<[u64; 2], S4> = t.into();
+        assert!(!small.is_heap());
+        assert_eq!(small.into_inner(), [2; 2]);
+    }
+
+    #[test]
+    fn moves_byte_aligned_values() {
+        let small: SmallBox<[u8; 64], S1> = SmallBox::new([3; 64]);
+
+        let t = Trident::<[u8; 64]>::from(small);
+        assert!(!t.is_inline());
+
+        let small: SmallBox<[u8; 64], S1> = t.into();
+        assert!(small.is_heap());
+        assert_eq!(*small, [3; 64]);
+    }
+
+    #[test]
+    fn converts_erased() {
+        let drops = Rc::new(Cell::new(0));
+
+        let small: SmallBox<Counted, S1> = SmallBox::new(Counted(drops.clone(), [4; 4]));
+        let erased = Erased::from_smallbox(small);
+
+        // SAFETY: erased holds a Counted
+        let small: SmallBox<Counted, S1> = unsafe { erased.into_smallbox() };
+        assert_eq!(small.1, [4; 4]);
+        assert_eq!(drops.get(), 0);
+
+        drop(small);
+        assert_eq!(drops.get(), 1);
+    }
+}
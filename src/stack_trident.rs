@@ -0,0 +1,208 @@
+use std::{
+    error::Error,
+    fmt,
+    marker::PhantomData,
+    mem::{self, ManuallyDrop, MaybeUninit},
+    ops::{Deref, DerefMut},
+    ptr,
+};
+
+use crate::{limits, Erased};
+
+/**
+ * A struct that stores a `T`, either inline or, if `T` is larger than 3 words, in a buffer
+ * borrowed from the caller, so never allocates.
+ *
+ * This allows erasure where the heap is unavailable, such as in signal handlers and interrupt
+ * contexts, with the buffer typically on the stack. Creation fails if the `T` doesn't fit the
+ * buffer.
+ */
+pub struct StackTrident<'a, T> {
+    erased: Erased,
+    _phantom: PhantomData<(T, &'a mut [MaybeUninit<u8>])>,
+}
+
+/**
+ * The error returned when a `StackTrident`'s buffer is too small for the value, holding the value.
+ */
+pub struct BufferTooSmall<T>(T);
+
+impl<'a, T> StackTrident<'a, T> {
+    /**
+     * Create a `StackTrident<T>` from a `T`, moving it into `buffer` if it's too large to be stored
+     * inline, or returning it if it doesn't fit, including alignment.
+     */
+    pub fn new(t: T, buffer: &'a mut [MaybeUninit<u8>]) -> Result<Self, BufferTooSmall<T>> {
+        if limits::should_inline::<T>() {
+            return Ok(Self {
+                erased: Erased::new(t),
+                _phantom: PhantomData,
+            });
+        }
+
+        let offset = buffer.as_mut_ptr().align_offset(mem::align_of::<T>());
+        let fits = offset
+            .checked_add(mem::size_of::<T>())
+            .is_some_and(|end| end <= buffer.len());
+        if !fits {
+            return Err(BufferTooSmall(t));
+        }
+
+        // SAFETY: the T fits the buffer at `offset`, which is aligned for it
+        unsafe {
+            let ptr = buffer.as_mut_ptr().add(offset) as *mut T;
+            ptr::write(ptr, t);
+
+            Ok(Self {
+                // T is too large to be inline, so isn't dropped or freed by the Erased
+                erased: Erased::from_spilled(ptr),
+                _phantom: PhantomData,
+            })
+        }
+    }
+
+    /**
+     * Whether the `T` is stored inline, rather than in the buffer.
+     */
+    pub fn is_inline(&self) -> bool {
+        limits::should_inline::<T>()
+    }
+
+    /**
+     * Get a pointer to the contained `T`.
+     */
+    pub fn as_ptr(&self) -> *const T {
+        // SAFETY: we were created with the same T we request
+        unsafe { self.erased.as_ptr() }
+    }
+
+    /**
+     * Get a mutable pointer to the contained `T`.
+     */
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        // SAFETY: we were created with the same T we request
+        unsafe { self.erased.as_mut_ptr() }
+    }
+
+    /**
+     * Convert to the contained `T`, releasing the buffer.
+     */
+    pub fn into_inner(self) -> T {
+        let mut this = ManuallyDrop::new(self);
+
+        // SAFETY: `this` is never dropped, so the T is moved out once
+        unsafe { ptr::read(this.as_mut_ptr()) }
+    }
+}
+
+impl<T> BufferTooSmall<T> {
+    /**
+     * Get the value which didn't fit.
+     */
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+// SAFETY: we own a T, and exclusively borrow bytes, so are as thread-safe as T is
+unsafe impl<T: Send> Send for StackTrident<'_, T> {}
+unsafe impl<T: Sync> Sync for StackTrident<'_, T> {}
+
+impl<T> Drop for StackTrident<'_, T> {
+    fn drop(&mut self) {
+        // SAFETY: the T is dropped once, and the buffer needn't be freed
+        unsafe { ptr::drop_in_place(self.as_mut_ptr()) }
+    }
+}
+
+impl<T> Deref for StackTrident<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: we were created with the same T we request
+        unsafe { self.erased.as_ref() }
+    }
+}
+
+impl<T> DerefMut for StackTrident<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // SAFETY: we were created with the same T we request
+        unsafe { self.erased.as_mut_ref() }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for StackTrident<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T> fmt::Debug for BufferTooSmall<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BufferTooSmall").finish_non_exhaustive()
+    }
+}
+
+impl<T> fmt::Display for BufferTooSmall<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "buffer too small for a value of {} bytes",
+            mem::size_of::<T>()
+        )
+    }
+}
+
+impl<T> Error for BufferTooSmall<T> {}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+
+    #[test]
+    fn spills_into_buffer() {
+        let mut buffer = [MaybeUninit::uninit(); 64];
+        // not necessarily aligned for u64s
+        let buffer = &mut buffer[1..];
+        let start = buffer.as_ptr() as usize;
+
+        let mut t = StackTrident::new([1u64; 6], buffer).unwrap();
+        assert!(!t.is_inline());
+        assert_eq!(t.as_ptr() as usize % mem::align_of::<u64>(), 0);
+        assert!((start..start + 63).contains(&(t.as_ptr() as usize)));
+
+        t[5] = 2;
+        assert_eq!(t.into_inner(), [1, 1, 1, 1, 1, 2]);
+    }
+
+    #[test]
+    fn keeps_small_values_inline() {
+        let t = StackTrident::new(5u32, &mut []).unwrap();
+        assert!(t.is_inline());
+        assert_eq!(*t, 5);
+    }
+
+    #[test]
+    fn rejects_small_buffers() {
+        let mut buffer = [MaybeUninit::uninit(); 48];
+
+        let err = StackTrident::new([2u64; 7], &mut buffer).unwrap_err();
+        assert_eq!(err.into_inner(), [2; 7]);
+
+        let err = StackTrident::new([0u8; 100], &mut []).unwrap_err();
+        assert_eq!(err.to_string(), "buffer too small for a value of 100 bytes");
+    }
+
+    #[test]
+    fn drops_in_buffer() {
+        let rc = Rc::new(());
+        let mut buffer = [MaybeUninit::uninit(); 40];
+
+        let t = StackTrident::new((rc.clone(), [0u8; 20]), &mut buffer).unwrap();
+        assert_eq!(Rc::strong_count(&rc), 2);
+        drop(t);
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+}
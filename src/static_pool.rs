@@ -0,0 +1,184 @@
+/**
+ * A fixed-capacity pool of slots which `TridentIn`s can spill into, without a heap.
+ */
+use core::{
+    alloc::Layout,
+    cell::UnsafeCell,
+    fmt,
+    mem::{self, MaybeUninit},
+    ptr::NonNull,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use allocator_api2::alloc::{AllocError, Allocator};
+
+use crate::TridentIn;
+
+/**
+ * A pool of `SLOTS` slots of `SLOT_SIZE` bytes, which can be a `static`, for values spilled from
+ * `TridentIn`s.
+ *
+ * `&StaticPool` is an allocator, so `Trident::new_in(t, &POOL)` or `POOL.try_alloc(t)` creates a
+ * `TridentIn<T, &StaticPool<..>>`. Values larger than 3 words take a slot, returned when they're
+ * dropped, so nothing is allocated at runtime. Once all slots are taken, or if the value is larger
+ * than a slot or aligned to more than 16 bytes, allocation fails, so `TridentIn::try_new_in`
+ * returns an error.
+ *
+ * Slots are claimed atomically, so the pool can be shared between threads.
+ */
+pub struct StaticPool<const SLOTS: usize, const SLOT_SIZE: usize> {
+    slots: UnsafeCell<[Slot<SLOT_SIZE>; SLOTS]>,
+    taken: [AtomicBool; SLOTS],
+}
+
+#[derive(Clone, Copy)]
+#[repr(C, align(16))]
+struct Slot<const SIZE: usize>([MaybeUninit<u8>; SIZE]);
+
+// SAFETY: a slot's contents are only accessed by whoever claimed it
+unsafe impl<const SLOTS: usize, const SLOT_SIZE: usize> Sync for StaticPool<SLOTS, SLOT_SIZE> {}
+
+impl<const SLOTS: usize, const SLOT_SIZE: usize> StaticPool<SLOTS, SLOT_SIZE> {
+    /**
+     * Create a pool with all slots free.
+     */
+    pub const fn new() -> Self {
+        Self {
+            slots: UnsafeCell::new([Slot([MaybeUninit::uninit(); SLOT_SIZE]); SLOTS]),
+            taken: [const { AtomicBool::new(false) }; SLOTS],
+        }
+    }
+
+    /**
+     * Create a `TridentIn`, taking a slot if `T` is too large to be stored inline, or return an
+     * error if none are free or `T` doesn't fit one. On failure, `t` is dropped.
+     */
+    pub fn try_alloc<T>(&self, t: T) -> Result<TridentIn<T, &Self>, crate::AllocError> {
+        TridentIn::try_new_in(t, self)
+    }
+
+    /**
+     * The number of slots.
+     */
+    pub const fn capacity(&self) -> usize {
+        SLOTS
+    }
+
+    /**
+     * The number of free slots.
+     */
+    pub fn available(&self) -> usize {
+        self.taken
+            .iter()
+            .filter(|taken| !taken.load(Ordering::Relaxed))
+            .count()
+    }
+
+    fn fits(layout: Layout) -> bool {
+        layout.size() <= SLOT_SIZE && layout.align() <= mem::align_of::<Slot<SLOT_SIZE>>()
+    }
+}
+
+unsafe impl<const SLOTS: usize, const SLOT_SIZE: usize> Allocator
+    for &StaticPool<SLOTS, SLOT_SIZE>
+{
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        if !StaticPool::<SLOTS, SLOT_SIZE>::fits(layout) {
+            return Err(AllocError);
+        }
+
+        let index = self
+            .taken
+            .iter()
+            .position(|taken| {
+                taken
+                    .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+                    .is_ok()
+            })
+            .ok_or(AllocError)?;
+
+        // SAFETY: `index` is in bounds, and we've claimed the slot
+        let slot = unsafe { (self.slots.get() as *mut Slot<SLOT_SIZE>).add(index) };
+        Ok(NonNull::slice_from_raw_parts(
+            NonNull::new(slot as *mut u8).unwrap(),
+            SLOT_SIZE,
+        ))
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, _layout: Layout) {
+        let offset = ptr.as_ptr() as usize - self.slots.get() as usize;
+        let index = offset / mem::size_of::<Slot<SLOT_SIZE>>();
+
+        self.taken[index].store(false, Ordering::Release);
+    }
+}
+
+impl<const SLOTS: usize, const SLOT_SIZE: usize> Default for StaticPool<SLOTS, SLOT_SIZE> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const SLOTS: usize, const SLOT_SIZE: usize> fmt::Debug for StaticPool<SLOTS, SLOT_SIZE> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StaticPool")
+            .field("capacity", &SLOTS)
+            .field("slot_size", &SLOT_SIZE)
+            .field("available", &self.available())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{rc::Rc, thread};
+
+    use super::*;
+    use crate::Trident;
+
+    static POOL: StaticPool<4, 64> = StaticPool::new();
+
+    #[test]
+    fn spills_into_slots() {
+        let pool = StaticPool::<2, 64>::new();
+        let rc = Rc::new(());
+
+        let a = pool.try_alloc([1u64; 8]).unwrap();
+        let b = Trident::new_in((rc.clone(), [2u32; 8]), &pool);
+        let small = pool.try_alloc(3u16).unwrap();
+        assert_eq!(pool.available(), 0);
+        assert!(small.allocator().is_none());
+
+        assert_eq!(pool.try_alloc([4u64; 5]).err(), Some(crate::AllocError));
+        assert_eq!(a[7], 1);
+        assert_eq!(b.1[7], 2);
+
+        drop(b);
+        assert_eq!(Rc::strong_count(&rc), 1);
+        assert_eq!(pool.available(), 1);
+
+        let c = pool.try_alloc([5u64; 4]).unwrap();
+        assert_eq!(c.into_inner(), [5; 4]);
+        assert_eq!(pool.available(), 1);
+    }
+
+    #[test]
+    fn rejects_oversized_values() {
+        let pool = StaticPool::<1, 32>::new();
+
+        assert!(pool.try_alloc([0u8; 33]).is_err());
+        assert_eq!(pool.available(), 1);
+    }
+
+    #[test]
+    fn shares_between_threads() {
+        let threads = (0..4)
+            .map(|i| thread::spawn(move || POOL.try_alloc([i; 8]).map(|t| t.into_inner())))
+            .collect::<Vec<_>>();
+
+        for (i, thread) in threads.into_iter().enumerate() {
+            assert_eq!(thread.join().unwrap(), Ok([i; 8]));
+        }
+        assert_eq!(POOL.available(), POOL.capacity());
+    }
+}
@@ -0,0 +1,181 @@
+use std::marker::PhantomData;
+
+use crate::limits;
+use crate::Erased;
+
+/**
+ * Where a `Trident<T, S>` may store its `T`, selected by `S`.
+ *
+ * - `InlineOrHeap`, the default, stores small `T`s inline and boxes larger ones.
+ * - `AlwaysInline` never allocates, failing to compile for `T`s which are too large.
+ * - `AlwaysHeap` always boxes, so the `T` has a stable address.
+ *
+ * A `Trident<T, S>` is 3 words for any `S`, and is created with `Trident::with_storage`.
+ *
+ * This trait is sealed. Strategies which need a handle, such as a caller's buffer, an arena or a
+ * pool, aren't yet covered; until they are, `StackTrident` borrows a caller's buffer, and
+ * `TridentIn` spills into an allocator such as `TridentArena` or `StaticPool`.
+ */
+pub trait Storage: sealed::Sealed {
+    /**
+     * Store `t` as this strategy dictates.
+     */
+    fn store<T>(t: T) -> Erased;
+}
+
+mod sealed {
+    use crate::Erased;
+
+    pub trait Sealed {
+        /**
+         * Whether the `T` in `erased` is inline.
+         *
+         * Unsafe because `erased` must hold a `T` stored by this strategy.
+         */
+        unsafe fn is_inline<T>(erased: &Erased) -> bool;
+    }
+}
+
+/**
+ * Store small `T`s inline and box larger ones. The default for `Trident<T>`.
+ */
+#[derive(Debug)]
+pub enum InlineOrHeap {}
+
+/**
+ * Always store the `T` inline, failing to compile if it's too large.
+ */
+#[derive(Debug)]
+pub enum AlwaysInline {}
+
+/**
+ * Always box the `T`, so it has a stable address.
+ *
+ * The storage is just the pointer, as the strategy records that the `T` is boxed, so this works
+ * for any `T`, unlike `Trident::spill_to_heap`.
+ */
+#[derive(Debug)]
+pub enum AlwaysHeap {}
+
+struct Fits<T>(PhantomData<T>);
+
+impl<T> Fits<T> {
+    const INLINE: () = assert!(
+        limits::should_inline::<T>(),
+        "T is too large to be stored inline"
+    );
+}
+
+impl sealed::Sealed for InlineOrHeap {
+    unsafe fn is_inline<T>(erased: &Erased) -> bool {
+        erased.is_inline::<T>()
+    }
+}
+
+impl sealed::Sealed for AlwaysInline {
+    unsafe fn is_inline<T>(_: &Erased) -> bool {
+        true
+    }
+}
+
+impl sealed::Sealed for AlwaysHeap {
+    unsafe fn is_inline<T>(_: &Erased) -> bool {
+        false
+    }
+}
+
+impl Storage for InlineOrHeap {
+    fn store<T>(t: T) -> Erased {
+        Erased::new(t)
+    }
+}
+
+impl Storage for AlwaysInline {
+    fn store<T>(t: T) -> Erased {
+        #[allow(clippy::let_unit_value)]
+        let () = Fits::<T>::INLINE;
+
+        Erased::new(t)
+    }
+}
+
+impl Storage for AlwaysHeap {
+    fn store<T>(t: T) -> Erased {
+        Erased::new_boxed(t)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{mem, rc::Rc};
+
+    use super::*;
+    use crate::Trident;
+
+    #[test]
+    fn selects_storage() {
+        let default: Trident<[u64; 2]> = Trident::new([1, 2]);
+        let inline = Trident::<[u64; 2], AlwaysInline>::with_storage([3, 4]);
+        let mut heap = Trident::<[u64; 2], AlwaysHeap>::with_storage([5, 6]);
+        let large = Trident::<[u64; 4], InlineOrHeap>::with_storage([7; 4]);
+
+        assert!(default.is_inline());
+        assert!(inline.is_inline());
+        assert!(!heap.is_inline());
+        assert!(!large.is_inline());
+
+        heap[1] += 1;
+        assert_eq!(*inline, [3, 4]);
+        assert_eq!(heap.into_inner(), [5, 7]);
+        assert_eq!(large.into_inner(), [7; 4]);
+
+        assert_eq!(
+            mem::size_of::<Trident<u8, AlwaysHeap>>(),
+            mem::size_of::<Trident<u8>>()
+        );
+    }
+
+    #[test]
+    fn implements_traits_for_any_storage() {
+        use std::collections::HashSet;
+
+        let inline = Trident::<u32, AlwaysInline>::with_storage(1);
+        let heap = Trident::<u32, AlwaysHeap>::with_storage(2);
+
+        let copy = inline.clone();
+        assert!(copy.is_inline());
+        assert_eq!(copy, inline);
+        assert!(heap.clone() > Trident::with_storage(1));
+        assert_eq!(Trident::<u32, AlwaysHeap>::default(), 0);
+        assert_eq!(format!("{} {}", inline, heap), "1 2");
+
+        let set: HashSet<_> = [heap.clone(), heap].into_iter().collect();
+        assert_eq!(set.len(), 1);
+        assert!(set.contains(&2));
+    }
+
+    #[test]
+    fn boxes_any_type() {
+        #[derive(Debug, PartialEq)]
+        struct Zst;
+
+        let full = Trident::<[u64; 3], AlwaysHeap>::with_storage([1, 2, 3]);
+        let zst = Trident::<Zst, AlwaysHeap>::with_storage(Zst);
+
+        assert!(!full.is_inline());
+        assert!(!zst.is_inline());
+        assert_eq!(*full, [1, 2, 3]);
+        assert_eq!(zst.into_inner(), Zst);
+        assert_eq!(full.into_inner(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn drops_heap_storage() {
+        let rc = Rc::new(());
+
+        let t = Trident::<_, AlwaysHeap>::with_storage(rc.clone());
+        assert_eq!(Rc::strong_count(&rc), 2);
+        drop(t);
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+}
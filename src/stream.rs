@@ -0,0 +1,162 @@
+/**
+ * `Stream` and `Sink` forwarding for `Trident<S>`, following the same pinning rules as the
+ * `Future` implementation.
+ */
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_core::{FusedStream, Stream};
+use futures_sink::Sink;
+
+use crate::Trident;
+
+impl<S> Stream for Trident<S>
+where
+    S: Stream,
+{
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<S::Item>> {
+        self.as_pin_mut().poll_next(cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.as_ref().size_hint()
+    }
+}
+
+impl<S> FusedStream for Trident<S>
+where
+    S: FusedStream,
+{
+    fn is_terminated(&self) -> bool {
+        self.as_ref().is_terminated()
+    }
+}
+
+impl<S, Item> Sink<Item> for Trident<S>
+where
+    S: Sink<Item>,
+{
+    type Error = S::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), S::Error>> {
+        self.as_pin_mut().poll_ready(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: Item) -> Result<(), S::Error> {
+        self.as_pin_mut().start_send(item)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), S::Error>> {
+        self.as_pin_mut().poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), S::Error>> {
+        self.as_pin_mut().poll_close(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        convert::Infallible,
+        marker::PhantomPinned,
+        pin::{pin, Pin},
+        task::{Context, Poll, Waker},
+    };
+
+    use futures_core::Stream;
+    use futures_sink::Sink;
+
+    use crate::Trident;
+
+    struct Counter<Payload> {
+        next: u32,
+        end: u32,
+        sent: Vec<u32>,
+        _payload: Payload,
+        _pinned: PhantomPinned,
+    }
+
+    impl<Payload> Counter<Payload> {
+        fn new(end: u32, payload: Payload) -> Self {
+            Self {
+                next: 0,
+                end,
+                sent: Vec::new(),
+                _payload: payload,
+                _pinned: PhantomPinned,
+            }
+        }
+    }
+
+    impl<Payload> Stream for Counter<Payload> {
+        type Item = u32;
+
+        fn poll_next(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<u32>> {
+            // SAFETY: we don't move out of `this`
+            let this = unsafe { self.get_unchecked_mut() };
+
+            if this.next == this.end {
+                return Poll::Ready(None);
+            }
+            this.next += 1;
+            Poll::Ready(Some(this.next))
+        }
+    }
+
+    impl<Payload> Sink<u32> for Counter<Payload> {
+        type Error = Infallible;
+
+        fn poll_ready(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn start_send(self: Pin<&mut Self>, item: u32) -> Result<(), Infallible> {
+            // SAFETY: we don't move out of `this`
+            unsafe { self.get_unchecked_mut() }.sent.push(item);
+            Ok(())
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    fn collect<S: Stream>(s: S) -> Vec<S::Item> {
+        let mut s = pin!(s);
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut items = Vec::new();
+
+        while let Poll::Ready(Some(item)) = s.as_mut().poll_next(&mut cx) {
+            items.push(item);
+        }
+        items
+    }
+
+    #[test]
+    fn streams_small_and_large() {
+        assert_eq!(collect(Trident::new(Counter::new(3, ()))), [1, 2, 3]);
+        assert_eq!(collect(Trident::new(Counter::new(2, [0u64; 8]))), [1, 2]);
+    }
+
+    #[test]
+    fn sinks() {
+        let mut t = pin!(Trident::new(Counter::new(0, ())));
+        let mut cx = Context::from_waker(Waker::noop());
+
+        assert_eq!(t.as_mut().poll_ready(&mut cx), Poll::Ready(Ok(())));
+        t.as_mut().start_send(5).unwrap();
+        t.as_mut().start_send(6).unwrap();
+        assert_eq!(t.as_mut().poll_close(&mut cx), Poll::Ready(Ok(())));
+
+        assert_eq!(t.sent, [5, 6]);
+    }
+}
@@ -0,0 +1,320 @@
+use std::{
+    alloc::{self, Layout},
+    cell::UnsafeCell,
+    fmt,
+    marker::{PhantomData, Unsize},
+    mem::{self, ManuallyDrop, MaybeUninit},
+    ops::{Deref, DerefMut},
+    ptr::{self, Pointee},
+};
+
+use crate::limits::NWORDS;
+
+/**
+ * The words left for the payload, after the metadata.
+ */
+const PAYLOAD_WORDS: usize = NWORDS - 1;
+
+/**
+ * An unsized `U`, such as a slice or `dyn Trait`, stored in three words, as `Box<U>`.
+ *
+ * `U`'s pointer metadata, a length or vtable, is kept in the last word, rather than alongside
+ * the data pointer. The other two words hold the value inline, if it fits, or otherwise a thin
+ * pointer to it on the heap. Whether the value is inline is recovered from the layout the
+ * metadata describes, so no flag is needed.
+ *
+ * Unlike `TridentDyn`, which spends its third word on a function resolving the metadata, a
+ * `ThinDyn` keeps the metadata itself, so can also hold values unsized at runtime, such as a
+ * `Box<[T]>`.
+ */
+pub struct ThinDyn<U: ?Sized> {
+    // an UnsafeCell, as an inline value may be mutated through a shared `ThinDyn`, e.g. a Cell
+    payload: UnsafeCell<[MaybeUninit<usize>; PAYLOAD_WORDS]>,
+    metadata: <U as Pointee>::Metadata,
+    _phantom: PhantomData<U>,
+}
+
+// SAFETY: the payload is only reachable as a `U`, so is as `Sync` as `U`
+unsafe impl<U: ?Sized + Sync> Sync for ThinDyn<U> {}
+
+const fn inlines(layout: Layout) -> bool {
+    layout.size() <= mem::size_of::<[usize; PAYLOAD_WORDS]>()
+        && layout.align() <= mem::align_of::<usize>()
+}
+
+impl<U: ?Sized> ThinDyn<U> {
+    /**
+     * Create a `ThinDyn<U>` from a `T` which can be unsized to `U`, storing it inline if it fits.
+     */
+    pub fn new<T: Unsize<U>>(t: T) -> Self {
+        let metadata = ptr::metadata(&t as &U as *const U);
+        let mut payload = [MaybeUninit::uninit(); PAYLOAD_WORDS];
+
+        if inlines(Layout::new::<T>()) {
+            // SAFETY: T fits, and the words are aligned for it
+            unsafe { ptr::write(payload.as_mut_ptr() as *mut T, t) };
+        } else {
+            // SAFETY: a pointer fits in the words
+            unsafe {
+                ptr::write(
+                    payload.as_mut_ptr() as *mut *mut T,
+                    Box::into_raw(Box::new(t)),
+                )
+            };
+        }
+
+        Self {
+            payload: UnsafeCell::new(payload),
+            metadata,
+            _phantom: PhantomData,
+        }
+    }
+
+    /**
+     * Whether the value is stored inline.
+     */
+    pub fn is_inline(&self) -> bool {
+        inlines(self.layout())
+    }
+
+    fn layout(&self) -> Layout {
+        // SAFETY: the metadata describes a live value, so its layout is valid
+        unsafe { Layout::for_value_raw(ptr::from_raw_parts::<U>(ptr::null::<()>(), self.metadata)) }
+    }
+
+    fn data_ptr(&self) -> *const () {
+        if self.is_inline() {
+            self.payload.get() as *const ()
+        } else {
+            // SAFETY: a non-inline payload always holds the heap pointer
+            unsafe { *(self.payload.get() as *const *const ()) }
+        }
+    }
+
+    fn data_mut_ptr(&mut self) -> *mut () {
+        if self.is_inline() {
+            self.payload.get_mut().as_mut_ptr() as *mut ()
+        } else {
+            // SAFETY: a non-inline payload always holds the heap pointer
+            unsafe { *(self.payload.get() as *const *mut ()) }
+        }
+    }
+
+    fn as_ptr(&self) -> *const U {
+        ptr::from_raw_parts(self.data_ptr(), self.metadata)
+    }
+
+    fn as_mut_ptr(&mut self) -> *mut U {
+        ptr::from_raw_parts_mut(self.data_mut_ptr(), self.metadata)
+    }
+
+    /**
+     * Convert to a `Box<U>`, which allocates if the value is inline.
+     */
+    pub fn into_boxed(self) -> Box<U> {
+        let mut this = ManuallyDrop::new(self);
+        let layout = this.layout();
+
+        let data = if !this.is_inline() {
+            this.data_mut_ptr()
+        } else if layout.size() == 0 {
+            ptr::without_provenance_mut(layout.align())
+        } else {
+            // SAFETY: the layout isn't zero-sized
+            let data = unsafe { alloc::alloc(layout) };
+            if data.is_null() {
+                alloc::handle_alloc_error(layout);
+            }
+
+            // SAFETY: the allocation is for our payload's layout, and our copy is forgotten
+            unsafe {
+                ptr::copy_nonoverlapping(this.payload.get() as *const u8, data, layout.size())
+            };
+            data as *mut ()
+        };
+
+        // SAFETY: `data` is a heap value of our payload's layout, allocated as `Box` would
+        unsafe { Box::from_raw(ptr::from_raw_parts_mut(data, this.metadata)) }
+    }
+}
+
+/**
+ * Moves the value inline, and frees the box, if it fits. Otherwise the allocation is kept.
+ */
+impl<U: ?Sized> From<Box<U>> for ThinDyn<U> {
+    fn from(b: Box<U>) -> Self {
+        let layout = Layout::for_value(&*b);
+        let raw = Box::into_raw(b);
+        let mut payload = [MaybeUninit::uninit(); PAYLOAD_WORDS];
+
+        if inlines(layout) {
+            // SAFETY: the value fits, and is moved out before its allocation is freed
+            unsafe {
+                ptr::copy_nonoverlapping(
+                    raw as *const u8,
+                    payload.as_mut_ptr() as *mut u8,
+                    layout.size(),
+                );
+                if layout.size() != 0 {
+                    alloc::dealloc(raw as *mut u8, layout);
+                }
+            }
+        } else {
+            // SAFETY: a pointer fits in the words
+            unsafe { ptr::write(payload.as_mut_ptr() as *mut *mut (), raw as *mut ()) };
+        }
+
+        Self {
+            payload: UnsafeCell::new(payload),
+            metadata: ptr::metadata(raw),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<U: ?Sized> Deref for ThinDyn<U> {
+    type Target = U;
+
+    fn deref(&self) -> &U {
+        // SAFETY: we hold a valid payload
+        unsafe { &*self.as_ptr() }
+    }
+}
+
+impl<U: ?Sized> DerefMut for ThinDyn<U> {
+    fn deref_mut(&mut self) -> &mut U {
+        // SAFETY: we hold a valid payload
+        unsafe { &mut *self.as_mut_ptr() }
+    }
+}
+
+impl<U: ?Sized> Drop for ThinDyn<U> {
+    fn drop(&mut self) {
+        let inline = self.is_inline();
+        let layout = self.layout();
+        let ptr = self.as_mut_ptr();
+
+        // SAFETY: we hold a valid payload, which isn't used again. Freed as allocated for its
+        // layout, if not inline
+        unsafe {
+            ptr.drop_in_place();
+            if !inline && layout.size() != 0 {
+                alloc::dealloc(ptr as *mut u8, layout);
+            }
+        }
+    }
+}
+
+impl<U: ?Sized + fmt::Debug> fmt::Debug for ThinDyn<U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<U: ?Sized + fmt::Display> fmt::Display for ThinDyn<U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, fmt::Display, rc::Rc};
+
+    use super::*;
+
+    struct Counted(Rc<Cell<u32>>);
+
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    #[test]
+    fn is_three_words() {
+        assert_eq!(
+            mem::size_of::<ThinDyn<dyn Display>>(),
+            3 * mem::size_of::<usize>()
+        );
+        assert_eq!(
+            mem::size_of::<ThinDyn<[u64]>>(),
+            3 * mem::size_of::<usize>()
+        );
+    }
+
+    #[test]
+    fn stores_trait_objects() {
+        let small: ThinDyn<dyn Display> = ThinDyn::new(12u32);
+        let large: ThinDyn<dyn Display> = ThinDyn::new(String::from("large"));
+
+        assert!(small.is_inline());
+        assert!(!large.is_inline());
+        assert_eq!(format!("{} {}", small, large), "12 large");
+    }
+
+    #[test]
+    fn stores_slices() {
+        let mut short: ThinDyn<[u32]> = ThinDyn::new([1, 2, 3]);
+        let long: ThinDyn<[u32]> = ThinDyn::from(vec![1; 20].into_boxed_slice());
+        let empty: ThinDyn<[u32]> = ThinDyn::new([]);
+
+        assert!(short.is_inline());
+        assert!(!long.is_inline());
+        assert!(empty.is_inline());
+
+        short[1] = 5;
+        assert_eq!(&*short, [1, 5, 3]);
+        assert_eq!(long.len(), 20);
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn converts_boxes() {
+        let drops = Rc::new(Cell::new(0));
+
+        let boxed: Box<[Counted]> = vec![Counted(drops.clone())].into_boxed_slice();
+        let thin = ThinDyn::from(boxed);
+        assert!(thin.is_inline());
+
+        let boxed = thin.into_boxed();
+        assert_eq!(drops.get(), 0);
+        drop(boxed);
+        assert_eq!(drops.get(), 1);
+
+        let long: Box<[Counted]> = (0..4).map(|_| Counted(drops.clone())).collect();
+        let ptr = long.as_ptr();
+        let thin = ThinDyn::from(long);
+        assert!(!thin.is_inline());
+        assert_eq!(thin.as_ptr() as *const Counted, ptr);
+
+        let boxed = thin.into_boxed();
+        assert_eq!(boxed.as_ptr(), ptr);
+        drop(boxed);
+        assert_eq!(drops.get(), 5);
+    }
+
+    #[test]
+    fn mutates_inline_cells() {
+        let cells: ThinDyn<[Cell<u8>]> = ThinDyn::new([Cell::new(1), Cell::new(2)]);
+        assert!(cells.is_inline());
+
+        let (a, b) = (&cells, &cells);
+        a[0].set(b[1].get() + 1);
+        assert_eq!(cells[0].get(), 3);
+    }
+
+    #[test]
+    fn drops() {
+        let drops = Rc::new(Cell::new(0));
+
+        let _inline: ThinDyn<dyn Send> = ThinDyn::new(());
+        drop(ThinDyn::<[Counted]>::new([Counted(drops.clone())]));
+        drop(ThinDyn::<[Counted]>::new([
+            Counted(drops.clone()),
+            Counted(drops.clone()),
+            Counted(drops.clone()),
+        ]));
+        assert_eq!(drops.get(), 4);
+    }
+}
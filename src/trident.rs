@@ -1,43 +1,58 @@
-use std::{marker::PhantomData, ptr};
+use std::{
+    alloc::Layout,
+    borrow::{Borrow, BorrowMut},
+    cmp::Ordering,
+    convert::Infallible,
+    error::Error,
+    fmt,
+    hash::{Hash, Hasher},
+    iter::FusedIterator,
+    marker::PhantomData,
+    mem::{self, ManuallyDrop, MaybeUninit},
+    ops::{Deref, DerefMut},
+    pin::Pin,
+    ptr,
+    rc::Rc,
+    sync::Arc,
+};
 
+use crate::erased::DeallocGuard;
 use crate::into;
 use crate::limits;
-use crate::Erased;
+use crate::{AllocError, AlwaysHeap, Erased, InlineOrHeap, Storage};
 
 /**
  * A struct that stores a `T`, either inline or, if `T` is larger than 3 words, allocated.
+ *
+ * `S` selects where the `T` may be stored, see `Storage`. Most of the API is for the default,
+ * `InlineOrHeap`.
  */
 #[repr(C)]
-pub struct Trident<T> {
+pub struct Trident<T, S: Storage = InlineOrHeap> {
     erased: Erased,
-    _phantom: PhantomData<T>,
+    _phantom: PhantomData<(T, S)>,
 }
 
-impl<T> Trident<T> {
-    fn should_inline() -> bool {
-        limits::should_inline::<T>()
-    }
-
+impl<T, S: Storage> Trident<T, S> {
     /**
-     * Create a `Trident<T>` from a `T`.
+     * Create a `Trident<T, S>` from a `T`, stored as `S` dictates.
      */
-    pub fn new(t: T) -> Self {
+    pub fn with_storage(t: T) -> Self {
         Self {
-            erased: Erased::new(t),
+            erased: S::store(t),
             _phantom: PhantomData,
         }
     }
 
     /**
-     * Create a `Trident<T>` from an `Erased`.
+     * Whether this `T` is stored inline.
      *
-     * Unsafe because we don't know that `erased` contains a `T`.
+     * For `InlineOrHeap`, this is `IS_INLINE`, unless the `T` has been moved to the heap by
+     * `spill_to_heap`.
      */
-    pub unsafe fn from_erased(erased: Erased) -> Self {
-        Self {
-            erased,
-            _phantom: PhantomData,
-        }
+    pub fn is_inline(&self) -> bool {
+        // SAFETY: we were created with the same T we request, stored by S
+        unsafe { S::is_inline::<T>(&self.erased) }
     }
 
     /**
@@ -45,223 +60,1948 @@ impl<T> Trident<T> {
      */
     pub fn as_ptr(&self) -> *const T {
         // SAFETY: we were created with the same T we request
-        unsafe { self.erased.as_ptr() }
-    }
-
-    /**
-     * Get a reference to the contained `T`.
-     */
-    pub fn as_ref(&self) -> &T {
-        // SAFETY: we were created with the same T we request
-        unsafe { self.erased.as_ref() }
+        unsafe { self.erased.as_ptr_with(self.is_inline()) }
     }
 
     /**
      * Get a mutable pointer to the contained `T`.
      */
     pub fn as_mut_ptr(&mut self) -> *mut T {
+        let inline = self.is_inline();
+
         // SAFETY: we were created with the same T we request
-        unsafe { self.erased.as_mut_ptr() }
+        unsafe { self.erased.as_mut_ptr_with(inline) }
     }
 
     /**
      * Get a mutable reference to the contained `T`.
+     *
+     * Equivalent to `AsMut::as_mut`. A reference to the contained `T` is available via `AsRef::as_ref`.
      */
     pub fn as_mut_ref(&mut self) -> &mut T {
-        // SAFETY: we were created with the same T we request
-        unsafe { self.erased.as_mut_ref() }
+        // SAFETY: the pointer is to our T, borrowed as we are
+        unsafe { &mut *self.as_mut_ptr() }
     }
 
     /**
      * Convert to the contained `T`
      */
     pub fn into_inner(mut self) -> T {
-        into::into_inner(self.as_mut_ptr(), self)
+        let inline = self.is_inline();
+        into::into_inner(self.as_mut_ptr(), inline, self)
     }
 }
 
-impl<T> Trident<T>
-where
-    T: Copy,
-{
+impl<T, S: Storage> Trident<T, S> {
     /**
-     * Copy out the contained `T`
+     * Project a pinned `Trident<T>` to a pinned reference to the contained `T`.
      */
-    pub fn get(&self) -> T {
-        *self.as_ref()
+    pub fn as_pin_ref(self: Pin<&Self>) -> Pin<&T> {
+        // SAFETY: see `as_pin_mut`
+        unsafe { self.map_unchecked(|t| t.as_ref()) }
     }
-}
 
-impl<T> Trident<T> {
     /**
-     * Convert to an `Erased`.
+     * Project a pinned `Trident<T>` to a pinned mutable reference to the contained `T`.
      *
-     * T's destructor cannot be run, as the type is erased.
+     * Pinning is structural: a `Trident<T>` is only `Unpin` if `T` is, so while pinned, neither
+     * an inline nor a boxed `T` can be moved out. Dropping a `Trident<T>` drops `T` in place.
      */
-    pub fn into_erased(self) -> Erased {
-        Erased::new(self.into_inner())
+    pub fn as_pin_mut(self: Pin<&mut Self>) -> Pin<&mut T> {
+        // SAFETY: the contained T is never moved while we're pinned, see above
+        unsafe { self.map_unchecked_mut(|t| t.as_mut_ref()) }
     }
 }
 
-impl<T> Drop for Trident<T> {
-    fn drop(&mut self) {
-        let ptr = self.as_mut_ref();
-
-        if Self::should_inline() {
-            unsafe {
-                ptr::drop_in_place(ptr);
-            }
-        } else {
-            unsafe {
-                Box::from_raw(ptr);
-            }
-        }
+impl<T> Trident<T, AlwaysHeap> {
+    /**
+     * Convert to a pinned `Trident<T, AlwaysHeap>`, which is always possible as the `T` is boxed.
+     */
+    pub fn into_pin(self) -> Pin<Self> {
+        // SAFETY: T is boxed, so its address is stable for as long as the Trident lives
+        unsafe { Pin::new_unchecked(self) }
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::Trident;
+impl<T> Trident<T> {
+    /**
+     * Whether a `T` is small enough to be stored inline, rather than boxed.
+     *
+     * An individual `Trident<T>` may still have been moved to the heap, see `is_inline`.
+     */
+    pub const IS_INLINE: bool = limits::should_inline::<T>();
 
-    #[derive(Clone, PartialEq, Eq, Debug)]
-    struct SmallCopy {
-        i: i32,
-        j: u32,
+    /**
+     * Create a `Trident<T>` from a `T`.
+     */
+    #[inline]
+    pub fn new(t: T) -> Self {
+        Self {
+            erased: Erased::new(t),
+            _phantom: PhantomData,
+        }
     }
-    impl Copy for SmallCopy {}
 
-    #[derive(PartialEq, Eq, Debug)]
-    struct Large([i32; 20]);
+    /**
+     * Create a `Trident<T>` from a `T`, always on the heap, for example to give it a stable
+     * address. `is_inline()` reports `false`.
+     *
+     * As for `spill_to_heap`, the last byte of the inline storage records the move, so this fails
+     * to compile for `T`s which would be inline but fill all 3 words, or are zero-sized. A
+     * `Trident<T, AlwaysHeap>` can box any `T`.
+     */
+    pub fn new_boxed(t: T) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::SPILLABLE;
 
-    #[derive(Clone, PartialEq, Eq, Debug)]
-    struct LargeCopy([i32; 20]);
-    impl Copy for LargeCopy {}
+        Self {
+            // SAFETY: T is too large to be inline, or can be spilled, per the assertion above
+            erased: unsafe { Erased::new_spilled(t) },
+            _phantom: PhantomData,
+        }
+    }
 
-    /// Small Types
+    /**
+     * Create a `Trident<T>` from a `T` which is small enough to be stored inline, in a const
+     * context, for example in a `static` table.
+     *
+     * # Panics
+     *
+     * Panics (at compile time, if evaluated in a const context) if `T` is too large to inline.
+     */
+    pub const fn new_inline(t: T) -> Self {
+        Self {
+            erased: Erased::new_inline(t),
+            _phantom: PhantomData,
+        }
+    }
 
-    #[test]
-    fn handles_small_type() {
-        assert!(Trident::<i32>::should_inline());
+    /**
+     * Create a `Trident<T>` from a `T`, returning an error rather than aborting if allocation fails.
+     *
+     * Inline `T`s never fail. On failure, `t` is dropped.
+     */
+    pub fn try_new(t: T) -> Result<Self, AllocError> {
+        Ok(Self {
+            erased: Erased::try_new(t)?,
+            _phantom: PhantomData,
+        })
+    }
 
-        let t = Trident::new(3);
+    /**
+     * Create a `Trident<T>` by initialising a `T` in place, in its final inline or heap location.
+     *
+     * See `Erased::new_with`.
+     */
+    pub fn new_with<F>(init: F) -> Self
+    where
+        F: FnOnce(&mut MaybeUninit<T>) -> &mut T,
+    {
+        Self {
+            erased: Erased::new_with(init),
+            _phantom: PhantomData,
+        }
+    }
 
-        assert_eq!(t.as_ref(), &3);
+    /**
+     * Create a `Trident<T>` from an `Erased`.
+     *
+     * # Safety
+     *
+     * Unsafe because we don't know that `erased` contains a `T`.
+     */
+    pub unsafe fn from_erased(erased: Erased) -> Self {
+        Self {
+            erased,
+            _phantom: PhantomData,
+        }
     }
 
-    #[test]
-    fn handles_small_copy_type() {
-        assert!(Trident::<SmallCopy>::should_inline());
+    /**
+     * Create a pinned `Trident`, always on the heap.
+     *
+     * A boxed `T` doesn't move when its `Trident` does, so can always be pinned. If the `T` is
+     * small and `Unpin`, `Pin::new(Trident::new(t))` avoids the allocation.
+     */
+    pub fn pin(t: T) -> Pin<Trident<T, AlwaysHeap>> {
+        Trident::<T, AlwaysHeap>::with_storage(t).into_pin()
+    }
 
-        let t = Trident::new(SmallCopy { i: 1, j: 2 });
+    /**
+     * Convert to a pinned `Trident`, moving an inline `T` to the heap so it doesn't move when the
+     * `Trident` does. A boxed `T` keeps its allocation.
+     */
+    pub fn into_pin(self) -> Pin<Trident<T, AlwaysHeap>> {
+        let boxed = if self.is_inline() {
+            Trident::<T, AlwaysHeap>::with_storage(self.into_inner())
+        } else {
+            let mut this = ManuallyDrop::new(self);
 
-        assert_eq!(t.get(), SmallCopy { i: 1, j: 2 });
+            Trident {
+                // SAFETY: T is boxed, and `this` is never dropped, so the allocation moves over
+                erased: unsafe { Erased::from_boxed(this.as_mut_ptr()) },
+                _phantom: PhantomData,
+            }
+        };
+
+        boxed.into_pin()
     }
 
-    /// Large Types
+    const SPILLABLE: () = assert!(
+        !limits::should_inline::<T>() || limits::can_spill::<T>(),
+        "an inline Trident<T> can only be moved to the heap if T is smaller than 3 words, and not zero-sized"
+    );
 
-    #[test]
-    fn handles_large_type() {
-        assert!(!Trident::<Large>::should_inline());
+    /**
+     * Move an inline `T` to the heap, for example to give it a stable address. Does nothing if
+     * `T` is already boxed.
+     *
+     * The last byte of the inline storage records the move, so this fails to compile for `T`s
+     * which fill all 3 words, or are zero-sized.
+     */
+    pub fn spill_to_heap(&mut self) {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::SPILLABLE;
 
-        let mut large1 = Large(Default::default());
-        let mut large2 = Large(Default::default());
+        if self.is_inline() {
+            // SAFETY: T is inline and can be spilled, per the assertion above
+            unsafe { self.erased.spill_in_place::<T>() }
+        }
+    }
 
-        for (i, (p1, p2)) in large1.0.iter_mut().zip(large2.0.iter_mut()).enumerate() {
-            *p1 = i as i32;
-            *p2 = i as i32;
+    /**
+     * Move a `T` which was moved to the heap by `spill_to_heap` back inline, freeing its
+     * allocation.
+     *
+     * Returns whether `T` is now inline: `true` if it already was, `false` if `T` is too large.
+     */
+    pub fn try_make_inline(&mut self) -> bool {
+        if !Self::IS_INLINE {
+            return false;
         }
 
-        let large1 = large1;
-        let large2 = large2;
+        if !self.is_inline() {
+            // SAFETY: T is small enough to be inline, so was boxed by spill_to_heap
+            unsafe { self.erased.unspill_in_place::<T>() }
+        }
 
-        let t = Trident::new(large1);
+        true
+    }
 
-        assert_eq!(*t.as_ref(), large2);
+    /**
+     * Replace the contained `T` with `t`, returning the old value.
+     *
+     * A boxed `T` is replaced within the existing allocation.
+     */
+    pub fn replace(&mut self, t: T) -> T {
+        mem::replace(self.as_mut_ref(), t)
     }
 
-    #[test]
-    fn handles_large_copy_type() {
-        assert!(!Trident::<LargeCopy>::should_inline());
+    /**
+     * Replace the contained `T` with `t`, dropping the old value.
+     *
+     * A boxed `T` is replaced within the existing allocation.
+     */
+    pub fn set(&mut self, t: T) {
+        *self.as_mut_ref() = t;
+    }
 
-        let mut large = LargeCopy(Default::default());
+    /**
+     * Replace the contained `T` with the result of applying `f` to it, in the same inline slot or
+     * heap allocation.
+     *
+     * # Aborts
+     *
+     * While `f` runs, the storage holds no `T`, so if `f` panics there's nothing left to drop.
+     * Rather than unwind into a double drop, the process is aborted.
+     */
+    pub fn map_in_place<F>(&mut self, f: F)
+    where
+        F: FnOnce(T) -> T,
+    {
+        struct AbortOnUnwind;
 
-        for (i, p) in large.0.iter_mut().enumerate() {
-            *p = i as i32;
+        impl Drop for AbortOnUnwind {
+            fn drop(&mut self) {
+                std::process::abort();
+            }
         }
 
-        let t = Trident::new(large);
+        let ptr = self.as_mut_ptr();
+        let guard = AbortOnUnwind;
 
-        assert_eq!(t.get(), large);
+        // SAFETY: the T is moved out and a replacement written back before anything can observe
+        // the slot, or the process aborts
+        unsafe {
+            ptr::write(ptr, f(ptr::read(ptr)));
+        }
+
+        mem::forget(guard);
     }
 
-    /// Drop Implementation
+    /**
+     * Swap the contained `T` with `other`'s.
+     *
+     * Only the three words are exchanged, so a boxed `T` is swapped by its pointer rather than
+     * moved.
+     */
+    pub fn swap(&mut self, other: &mut Self) {
+        self.erased.swap(&mut other.erased);
+    }
 
-    #[test]
-    fn handles_small_dtor_type() {
-        assert!(Trident::<Dtor>::should_inline());
+    /**
+     * Take the contained `T`, leaving `T::default()` in its place.
+     */
+    pub fn take(&mut self) -> T
+    where
+        T: Default,
+    {
+        mem::take(self.as_mut_ref())
+    }
 
-        struct Dtor<'a> {
-            x: i32,
-            y: i32,
-            drops: &'a mut u32,
+    /**
+     * Convert to a `Trident<U>` by applying `f` to the contained `T`.
+     *
+     * If both `T` and `U` are boxed and have the same size and alignment, the allocation is
+     * reused for the `U`.
+     */
+    pub fn map<U, F>(self, f: F) -> Trident<U>
+    where
+        F: FnOnce(T) -> U,
+    {
+        match self.try_map(|t| Ok::<_, Infallible>(f(t))) {
+            Ok(mapped) => mapped,
+            Err(never) => match never {},
         }
+    }
 
-        impl Drop for Dtor<'_> {
-            fn drop(&mut self) {
-                assert_eq!(self.x, 8217);
-                assert_eq!(self.y, 924);
-
-                *self.drops += 1;
-            }
+    /**
+     * Convert to a `Trident<U>` by applying the fallible `f` to the contained `T`.
+     *
+     * As with `map`, a compatible allocation is reused for the `U`. If `f` fails, the allocation
+     * is freed and its error returned.
+     */
+    pub fn try_map<U, E, F>(self, f: F) -> Result<Trident<U>, E>
+    where
+        F: FnOnce(T) -> Result<U, E>,
+    {
+        if !self.reuses_allocation_for::<U>() {
+            return f(self.into_inner()).map(Trident::new);
         }
 
-        let mut drops = 0;
-        let dtor = Dtor {
-            drops: &mut drops,
-            x: 8217,
-            y: 924,
-        };
+        let mut this = ManuallyDrop::new(self);
 
-        let t = Trident::new(dtor);
+        // SAFETY: `this` is never dropped, so we own the T and its allocation. The guard frees
+        // the allocation if `f` fails or unwinds
+        unsafe {
+            let guard = DeallocGuard(this.as_mut_ptr());
+            let u = f(ptr::read(guard.0))?;
 
-        drop(t);
+            let ptr = ManuallyDrop::new(guard).0 as *mut U;
+            ptr::write(ptr, u);
 
-        assert_eq!(drops, 1);
+            Ok(Trident::from_erased(Erased::from_spilled(ptr)))
+        }
     }
 
-    #[test]
-    fn handles_large_dtor_type() {
-        assert!(!Trident::<Dtor>::should_inline());
-
-        struct Dtor<'a> {
-            ents: [usize; 12],
-            drops: &'a mut u32,
+    /**
+     * Drop the contained `T` and store `u` in its place, converting to a `Trident<U>`.
+     *
+     * As with `map`, if both `T` and `U` are boxed with the same size and alignment, the
+     * allocation is reused. Use `map` to read the `T` rather than dropping it.
+     */
+    pub fn recycle<U>(self, u: U) -> Trident<U> {
+        if !self.reuses_allocation_for::<U>() {
+            drop(self);
+            return Trident::new(u);
         }
 
-        impl Drop for Dtor<'_> {
-            fn drop(&mut self) {
-                for (i, &ent) in self.ents.iter().enumerate() {
-                    assert_eq!(i, ent);
-                }
+        let mut this = ManuallyDrop::new(self);
 
-                *self.drops += 1;
-            }
-        }
+        // SAFETY: `this` is never dropped, so we own the T and its allocation. The guard frees
+        // the allocation if T's destructor unwinds
+        unsafe {
+            let guard = DeallocGuard(this.as_mut_ptr());
+            ptr::drop_in_place(guard.0);
 
-        let mut drops = 0;
-        let mut dtor = Dtor {
-            ents: Default::default(),
-            drops: &mut drops,
-        };
-        for (i, p) in dtor.ents.iter_mut().enumerate() {
-            *p = i;
+            let ptr = ManuallyDrop::new(guard).0 as *mut U;
+            ptr::write(ptr, u);
+
+            Trident::from_erased(Erased::from_spilled(ptr))
         }
-        let dtor = dtor;
+    }
 
-        let t = Trident::new(dtor);
+    /**
+     * Whether our boxed `T`'s allocation can be reused to store a `U`.
+     */
+    fn reuses_allocation_for<U>(&self) -> bool {
+        !self.is_inline()
+            && !limits::should_inline::<U>()
+            && Layout::new::<T>() == Layout::new::<U>()
+    }
+}
 
-        drop(t);
+impl<T> Trident<T> {
+    /**
+     * Create a `Trident` with space for an uninitialised `T`, in its final inline or heap location.
+     */
+    pub fn uninit() -> Trident<MaybeUninit<T>> {
+        Trident::new_with(|slot| slot.write(MaybeUninit::uninit()))
+    }
+}
 
-        assert_eq!(drops, 1);
+impl<T> Trident<MaybeUninit<T>> {
+    /**
+     * Initialise the contained `T` with `t`, converting to a `Trident<T>`.
+     */
+    pub fn write(mut self, t: T) -> Trident<T> {
+        self.as_mut_ref().write(t);
+
+        // SAFETY: initialised just above
+        unsafe { self.assume_init() }
+    }
+
+    /**
+     * Convert to a `Trident<T>`, without moving the `T`.
+     *
+     * # Safety
+     *
+     * The contained `T` must have been initialised, as with `MaybeUninit::assume_init`.
+     */
+    pub unsafe fn assume_init(self) -> Trident<T> {
+        // MaybeUninit<T> has T's layout, so is stored in the same way
+        Trident::from_erased(self.into_erased().retype::<T>())
+    }
+}
+
+impl<T, S> Trident<T, S>
+where
+    T: Copy,
+    S: Storage,
+{
+    /**
+     * Copy out the contained `T`
+     */
+    pub fn get(&self) -> T {
+        *self.as_ref()
+    }
+}
+
+impl<T> From<T> for Trident<T> {
+    fn from(t: T) -> Self {
+        Self::new(t)
+    }
+}
+
+/**
+ * Extension trait allowing `value.into_trident()` on any type.
+ */
+pub trait IntoTrident: Sized {
+    /**
+     * Move `self` into a `Trident<Self>`.
+     */
+    fn into_trident(self) -> Trident<Self> {
+        Trident::new(self)
+    }
+}
+
+impl<T> IntoTrident for T {}
+
+/**
+ * Adopts the box's allocation if `T` is too large to be stored inline.
+ */
+impl<T> From<Box<T>> for Trident<T> {
+    fn from(boxed: Box<T>) -> Self {
+        if Self::IS_INLINE {
+            Self::new(*boxed)
+        } else {
+            Self {
+                // SAFETY: T isn't inline, and Box allocates as we do
+                erased: unsafe { Erased::from_spilled(Box::into_raw(boxed)) },
+                _phantom: PhantomData,
+            }
+        }
+    }
+}
+
+/**
+ * Moves the `T` straight into the `Rc`'s allocation, freeing any of our own.
+ */
+impl<T> From<Trident<T>> for Rc<T> {
+    fn from(t: Trident<T>) -> Self {
+        let mut rc = Rc::new_uninit();
+        t.move_to(Rc::get_mut(&mut rc).expect("newly created Rc is unique"));
+
+        // SAFETY: initialised just above
+        unsafe { rc.assume_init() }
+    }
+}
+
+/**
+ * Moves the `T` straight into the `Arc`'s allocation, freeing any of our own.
+ */
+impl<T> From<Trident<T>> for Arc<T> {
+    fn from(t: Trident<T>) -> Self {
+        let mut arc = Arc::new_uninit();
+        t.move_to(Arc::get_mut(&mut arc).expect("newly created Arc is unique"));
+
+        // SAFETY: initialised just above
+        unsafe { arc.assume_init() }
+    }
+}
+
+impl<T, S: Storage> Trident<T, S> {
+    /**
+     * Convert to a `Box<T>`, reusing our allocation if the `T` is boxed.
+     */
+    pub fn into_box(self) -> Box<T> {
+        if self.is_inline() {
+            Box::new(self.into_inner())
+        } else {
+            let mut this = ManuallyDrop::new(self);
+
+            // SAFETY: T isn't inline, so was allocated as Box does, and `this` is never dropped
+            unsafe { Box::from_raw(this.as_mut_ptr()) }
+        }
+    }
+}
+
+impl<T> Trident<T> {
+    /**
+     * Move the `T` into `slot`, freeing any allocation, without an intermediate copy on the stack.
+     */
+    fn move_to(self, slot: &mut MaybeUninit<T>) {
+        let inline = self.is_inline();
+        let mut this = ManuallyDrop::new(self);
+        let ptr = this.as_mut_ptr();
+
+        // SAFETY: `this` is never dropped, so the T moves to `slot` and we free the allocation
+        unsafe {
+            ptr::copy_nonoverlapping(ptr, slot.as_mut_ptr(), 1);
+
+            if !inline {
+                drop(DeallocGuard(ptr));
+            }
+        }
+    }
+
+    /**
+     * Consume and leak the `Trident<T>`, returning a reference to the `T` which lives for the
+     * remainder of the program (or `'a`).
+     *
+     * A boxed `T` is leaked in place. An inline `T` is first moved to the heap, as its storage
+     * doesn't outlive the `Trident`.
+     */
+    pub fn leak<'a>(self) -> &'a mut T
+    where
+        T: 'a,
+    {
+        if self.is_inline() {
+            Box::leak(Box::new(self.into_inner()))
+        } else {
+            let mut this = ManuallyDrop::new(self);
+
+            // SAFETY: T is boxed and `this` is never dropped, so the allocation is never freed
+            unsafe { &mut *this.as_mut_ptr() }
+        }
+    }
+
+    /**
+     * Discard the `Trident<T>` without dropping the `T` or freeing its allocation, for example
+     * once ownership has been handed to foreign code via `as_mut_ptr`.
+     *
+     * Equivalent to `mem::forget`.
+     */
+    pub fn forget(self) {
+        mem::forget(self);
+    }
+
+    /**
+     * Decompose into raw words, for example to pass through FFI.
+     *
+     * The words own the `T`: it won't be dropped (nor any allocation freed) until they're passed
+     * back to `from_raw_parts`. They're `MaybeUninit`, as an inline `T` may have padding, and to
+     * keep a boxed `T`'s pointer provenance, see `Erased::into_raw_words`.
+     */
+    pub fn into_raw_parts(self) -> [MaybeUninit<usize>; 3] {
+        self.into_erased().into_raw_words()
+    }
+
+    /**
+     * Reconstruct a `Trident<T>` from words returned by `into_raw_parts`.
+     *
+     * # Safety
+     *
+     * `words` must have been returned by `Trident::<T>::into_raw_parts`, for the same `T`, and
+     * must be passed here at most once, since the returned `Trident<T>` owns the `T`.
+     */
+    pub unsafe fn from_raw_parts(words: [MaybeUninit<usize>; 3]) -> Self {
+        Self {
+            erased: Erased::from_raw_words(words),
+            _phantom: PhantomData,
+        }
+    }
+
+    /**
+     * Convert to an `Erased`.
+     *
+     * T's destructor cannot be run, as the type is erased.
+     */
+    pub fn into_erased(self) -> Erased {
+        let this = ManuallyDrop::new(self);
+
+        // SAFETY: `this` is never dropped, so ownership of the storage moves to the returned Erased
+        unsafe { ptr::read(&this.erased) }
+    }
+}
+
+// SAFETY: we own a T, so are as thread-safe as it is
+unsafe impl<T: Send, S: Storage> Send for Trident<T, S> {}
+unsafe impl<T: Sync, S: Storage> Sync for Trident<T, S> {}
+
+impl<T, S: Storage> Drop for Trident<T, S> {
+    fn drop(&mut self) {
+        let inline = self.is_inline();
+        let ptr = self.as_mut_ref();
+
+        if inline {
+            unsafe {
+                ptr::drop_in_place(ptr);
+            }
+        } else {
+            unsafe {
+                drop(Box::from_raw(ptr));
+            }
+        }
+    }
+}
+
+impl<T, S: Storage> Clone for Trident<T, S>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        Self::with_storage(self.as_ref().clone())
+    }
+
+    /**
+     * Clones into the existing `T` via `T::clone_from`, so a boxed `T` keeps its allocation.
+     */
+    fn clone_from(&mut self, source: &Self) {
+        self.as_mut_ref().clone_from(source.as_ref());
+    }
+}
+
+/**
+ * Forwards to `T`'s `Debug` implementation.
+ *
+ * The alternate form (`{:#?}`) additionally shows whether the `T` is stored inline or boxed.
+ */
+impl<T, S> fmt::Debug for Trident<T, S>
+where
+    T: fmt::Debug,
+    S: Storage,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            let storage = if self.is_inline() { "Inline" } else { "Boxed" };
+
+            f.debug_tuple(storage).field(self.as_ref()).finish()
+        } else {
+            fmt::Debug::fmt(self.as_ref(), f)
+        }
+    }
+}
+
+impl<T, S: Storage> fmt::Display for Trident<T, S>
+where
+    T: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_ref(), f)
+    }
+}
+
+/**
+ * Formats the address of the contained `T`, be it the inline slot or the heap allocation.
+ */
+impl<T, S: Storage> fmt::Pointer for Trident<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Pointer::fmt(&self.as_ptr(), f)
+    }
+}
+
+impl<E, S: Storage> Error for Trident<E, S>
+where
+    E: Error,
+{
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.as_ref().source()
+    }
+}
+
+impl<W, S: Storage> fmt::Write for Trident<W, S>
+where
+    W: fmt::Write,
+{
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.as_mut_ref().write_str(s)
+    }
+
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        self.as_mut_ref().write_char(c)
+    }
+
+    fn write_fmt(&mut self, args: fmt::Arguments<'_>) -> fmt::Result {
+        self.as_mut_ref().write_fmt(args)
+    }
+}
+
+impl<T, S: Storage> PartialEq for Trident<T, S>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.as_ref() == other.as_ref()
+    }
+}
+
+impl<T, S: Storage> Eq for Trident<T, S> where T: Eq {}
+
+/**
+ * Compare against a bare `T`, e.g. `assert_eq!(trident, value)`.
+ */
+impl<T, S: Storage> PartialEq<T> for Trident<T, S>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &T) -> bool {
+        self.as_ref() == other
+    }
+}
+
+impl<T, S: Storage> PartialOrd for Trident<T, S>
+where
+    T: PartialOrd,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.as_ref().partial_cmp(other.as_ref())
+    }
+}
+
+impl<T, S: Storage> Ord for Trident<T, S>
+where
+    T: Ord,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_ref().cmp(other.as_ref())
+    }
+}
+
+/**
+ * Hashes identically to the contained `T`.
+ */
+impl<T, S: Storage> Hash for Trident<T, S>
+where
+    T: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_ref().hash(state)
+    }
+}
+
+impl<T, S: Storage> Default for Trident<T, S>
+where
+    T: Default,
+{
+    fn default() -> Self {
+        Self::with_storage(T::default())
+    }
+}
+
+impl<T, S: Storage> AsRef<T> for Trident<T, S> {
+    /**
+     * Get a reference to the contained `T`.
+     */
+    fn as_ref(&self) -> &T {
+        // SAFETY: the pointer is to our T, borrowed as we are
+        unsafe { &*self.as_ptr() }
+    }
+}
+
+impl<T, S: Storage> AsMut<T> for Trident<T, S> {
+    fn as_mut(&mut self) -> &mut T {
+        self.as_mut_ref()
+    }
+}
+
+impl<T, S: Storage> Borrow<T> for Trident<T, S> {
+    fn borrow(&self) -> &T {
+        self.as_ref()
+    }
+}
+
+impl<T, S: Storage> BorrowMut<T> for Trident<T, S> {
+    fn borrow_mut(&mut self) -> &mut T {
+        self.as_mut_ref()
+    }
+}
+
+/**
+ * Allows `&str` lookups in collections keyed by `Trident<String>`.
+ */
+impl<S: Storage> Borrow<str> for Trident<String, S> {
+    fn borrow(&self) -> &str {
+        self.as_ref()
+    }
+}
+
+impl<S: Storage> BorrowMut<str> for Trident<String, S> {
+    fn borrow_mut(&mut self) -> &mut str {
+        self.as_mut_ref()
+    }
+}
+
+/**
+ * Allows `&[T]` lookups in collections keyed by `Trident<Vec<T>>`.
+ */
+impl<T, S: Storage> Borrow<[T]> for Trident<Vec<T>, S> {
+    fn borrow(&self) -> &[T] {
+        self.as_ref()
+    }
+}
+
+impl<T, S: Storage> BorrowMut<[T]> for Trident<Vec<T>, S> {
+    fn borrow_mut(&mut self) -> &mut [T] {
+        self.as_mut_ref()
+    }
+}
+
+impl<I, S: Storage> Iterator for Trident<I, S>
+where
+    I: Iterator,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        self.as_mut_ref().next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.as_ref().size_hint()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<I::Item> {
+        self.as_mut_ref().nth(n)
+    }
+}
+
+impl<I, S: Storage> DoubleEndedIterator for Trident<I, S>
+where
+    I: DoubleEndedIterator,
+{
+    fn next_back(&mut self) -> Option<I::Item> {
+        self.as_mut_ref().next_back()
+    }
+
+    fn nth_back(&mut self, n: usize) -> Option<I::Item> {
+        self.as_mut_ref().nth_back(n)
+    }
+}
+
+impl<I, S: Storage> ExactSizeIterator for Trident<I, S>
+where
+    I: ExactSizeIterator,
+{
+    fn len(&self) -> usize {
+        self.as_ref().len()
+    }
+}
+
+impl<I, S: Storage> FusedIterator for Trident<I, S> where I: FusedIterator {}
+
+impl<T, S: Storage> Deref for Trident<T, S> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.as_ref()
+    }
+}
+
+impl<T, S: Storage> DerefMut for Trident<T, S> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.as_mut_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::Cell,
+        collections::{hash_map::DefaultHasher, BTreeSet, HashMap, HashSet},
+        hash::{Hash, Hasher},
+        rc::Rc,
+        sync::Arc,
+    };
+
+    use super::Trident;
+
+    #[derive(Clone, PartialEq, Eq, Debug)]
+    struct SmallCopy {
+        i: i32,
+        j: u32,
+    }
+    impl Copy for SmallCopy {}
+
+    #[derive(PartialEq, Eq, Debug)]
+    struct Large([i32; 20]);
+
+    #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+    struct LargeCopy([i32; 20]);
+    impl Copy for LargeCopy {}
+
+    /// Small Types
+
+    #[test]
+    fn handles_small_type() {
+        const { assert!(Trident::<i32>::IS_INLINE) }
+
+        let t = Trident::new(3);
+
+        assert_eq!(t.as_ref(), &3);
+    }
+
+    #[test]
+    fn handles_small_copy_type() {
+        const { assert!(Trident::<SmallCopy>::IS_INLINE) }
+
+        let t = Trident::new(SmallCopy { i: 1, j: 2 });
+
+        assert_eq!(t.get(), SmallCopy { i: 1, j: 2 });
+    }
+
+    /// Large Types
+
+    #[test]
+    fn handles_large_type() {
+        const { assert!(!Trident::<Large>::IS_INLINE) }
+
+        let mut large1 = Large(Default::default());
+        let mut large2 = Large(Default::default());
+
+        for (i, (p1, p2)) in large1.0.iter_mut().zip(large2.0.iter_mut()).enumerate() {
+            *p1 = i as i32;
+            *p2 = i as i32;
+        }
+
+        let large1 = large1;
+        let large2 = large2;
+
+        let t = Trident::new(large1);
+
+        assert_eq!(*t.as_ref(), large2);
+    }
+
+    #[test]
+    fn handles_large_copy_type() {
+        const { assert!(!Trident::<LargeCopy>::IS_INLINE) }
+
+        let mut large = LargeCopy(Default::default());
+
+        for (i, p) in large.0.iter_mut().enumerate() {
+            *p = i as i32;
+        }
+
+        let t = Trident::new(large);
+
+        assert_eq!(t.get(), large);
+    }
+
+    /// Deref Implementation
+
+    #[test]
+    fn derefs_small_type() {
+        let mut t = Trident::new(SmallCopy { i: 1, j: 2 });
+
+        assert_eq!(t.i, 1);
+        t.j = 5;
+        assert_eq!(*t, SmallCopy { i: 1, j: 5 });
+    }
+
+    #[test]
+    fn derefs_large_type() {
+        let mut t = Trident::new(Large([3; 20]));
+
+        t.0[19] = 7;
+        assert_eq!(t.0.iter().sum::<i32>(), 3 * 19 + 7);
+
+        *t = Large([1; 20]);
+        assert_eq!(*t, Large([1; 20]));
+    }
+
+    /// Drop Implementation
+
+    #[test]
+    fn handles_small_dtor_type() {
+        const { assert!(Trident::<Dtor>::IS_INLINE) }
+
+        struct Dtor<'a> {
+            x: i32,
+            y: i32,
+            drops: &'a mut u32,
+        }
+
+        impl Drop for Dtor<'_> {
+            fn drop(&mut self) {
+                assert_eq!(self.x, 8217);
+                assert_eq!(self.y, 924);
+
+                *self.drops += 1;
+            }
+        }
+
+        let mut drops = 0;
+        let dtor = Dtor {
+            drops: &mut drops,
+            x: 8217,
+            y: 924,
+        };
+
+        let t = Trident::new(dtor);
+
+        drop(t);
+
+        assert_eq!(drops, 1);
+    }
+
+    #[test]
+    fn handles_large_dtor_type() {
+        const { assert!(!Trident::<Dtor>::IS_INLINE) }
+
+        struct Dtor<'a> {
+            ents: [usize; 12],
+            drops: &'a mut u32,
+        }
+
+        impl Drop for Dtor<'_> {
+            fn drop(&mut self) {
+                for (i, &ent) in self.ents.iter().enumerate() {
+                    assert_eq!(i, ent);
+                }
+
+                *self.drops += 1;
+            }
+        }
+
+        let mut drops = 0;
+        let mut dtor = Dtor {
+            ents: Default::default(),
+            drops: &mut drops,
+        };
+        for (i, p) in dtor.ents.iter_mut().enumerate() {
+            *p = i;
+        }
+        let dtor = dtor;
+
+        let t = Trident::new(dtor);
+
+        drop(t);
+
+        assert_eq!(drops, 1);
+    }
+
+    /// Clone Implementation
+
+    #[derive(Clone)]
+    struct CloneDtor<'a, Payload> {
+        payload: Payload,
+        drops: &'a Cell<u32>,
+    }
+
+    impl<Payload> Drop for CloneDtor<'_, Payload> {
+        fn drop(&mut self) {
+            self.drops.set(self.drops.get() + 1);
+        }
+    }
+
+    #[test]
+    fn clones_small_type() {
+        const { assert!(Trident::<CloneDtor<u8>>::IS_INLINE) }
+
+        let drops = Cell::new(0);
+        let t = Trident::new(CloneDtor {
+            payload: 3u8,
+            drops: &drops,
+        });
+
+        let mut cloned = t.clone();
+        cloned.payload = 4;
+
+        assert_eq!(t.payload, 3);
+        assert_eq!(cloned.payload, 4);
+
+        drop(t);
+        assert_eq!(drops.get(), 1);
+        drop(cloned);
+        assert_eq!(drops.get(), 2);
+    }
+
+    #[test]
+    fn clones_large_type() {
+        const { assert!(!Trident::<CloneDtor<[usize; 12]>>::IS_INLINE) }
+
+        let drops = Cell::new(0);
+        let t = Trident::new(CloneDtor {
+            payload: [5usize; 12],
+            drops: &drops,
+        });
+
+        let mut cloned = t.clone();
+        cloned.payload[0] = 6;
+
+        assert_ne!(t.as_ptr(), cloned.as_ptr());
+        assert_eq!(t.payload[0], 5);
+        assert_eq!(cloned.payload[0], 6);
+
+        drop(t);
+        assert_eq!(drops.get(), 1);
+        drop(cloned);
+        assert_eq!(drops.get(), 2);
+    }
+
+    /// Debug Implementation
+
+    #[test]
+    fn debug_forwards_to_value() {
+        let t = Trident::new(SmallCopy { i: 1, j: 2 });
+
+        assert_eq!(format!("{:?}", t), "SmallCopy { i: 1, j: 2 }");
+    }
+
+    #[test]
+    fn debug_alternate_shows_storage() {
+        let small = Trident::new(3);
+        let large = Trident::new(LargeCopy([0; 20]));
+
+        assert_eq!(format!("{:#?}", small), "Inline(\n    3,\n)");
+        assert!(format!("{:#?}", large).starts_with("Boxed(\n    LargeCopy(\n"));
+    }
+
+    /// Display Implementation
+
+    #[test]
+    fn display_forwards_to_value() {
+        let t = Trident::new(String::from("hello"));
+
+        assert_eq!(format!("{}", t), "hello");
+        assert_eq!(format!("{:>7}", t), "  hello");
+    }
+
+    /// PartialEq Implementation
+
+    #[test]
+    fn compares_tridents() {
+        assert_eq!(Trident::new(3), Trident::new(3));
+        assert_ne!(Trident::new(3), Trident::new(4));
+
+        assert_eq!(
+            Trident::new(LargeCopy([1; 20])),
+            Trident::new(LargeCopy([1; 20]))
+        );
+        assert_ne!(
+            Trident::new(LargeCopy([1; 20])),
+            Trident::new(LargeCopy([2; 20]))
+        );
+    }
+
+    #[test]
+    fn compares_against_bare_value() {
+        assert_eq!(
+            Trident::new(SmallCopy { i: 1, j: 2 }),
+            SmallCopy { i: 1, j: 2 }
+        );
+        assert_ne!(Trident::new(Large([1; 20])), Large([2; 20]));
+    }
+
+    /// Ord Implementation
+
+    #[test]
+    fn orders_tridents() {
+        assert!(Trident::new(1) < Trident::new(2));
+        assert!(Trident::new(LargeCopy([2; 20])) > Trident::new(LargeCopy([1; 20])));
+
+        let mut v: Vec<_> = [3, 1, 2].iter().copied().map(Trident::new).collect();
+        v.sort();
+        assert_eq!(v, [1, 2, 3].map(Trident::new));
+
+        let set: BTreeSet<_> = ["b", "a", "b"]
+            .iter()
+            .map(|s| Trident::new(s.to_string()))
+            .collect();
+        assert_eq!(set.len(), 2);
+        assert_eq!(set.iter().next().unwrap(), &Trident::new("a".to_string()));
+    }
+
+    /// Hash Implementation
+
+    #[test]
+    fn hashes_as_value() {
+        assert_eq!(hash_of(&Trident::new(3)), hash_of(&3));
+        assert_eq!(hash_of(&Trident::new([7u64; 8])), hash_of(&[7u64; 8]));
+
+        let set: HashSet<_> = [1, 2, 1].iter().copied().map(Trident::new).collect();
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(&Trident::new(2)));
+    }
+
+    fn hash_of<T: Hash>(t: &T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        t.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Default Implementation
+
+    #[test]
+    fn defaults_to_default_value() {
+        #[derive(Default)]
+        struct Holder {
+            small: Trident<u32>,
+            large: Trident<[u64; 8]>,
+        }
+
+        let holder = Holder::default();
+
+        assert_eq!(holder.small, 0);
+        assert_eq!(holder.large, [0; 8]);
+    }
+
+    /// Conversions
+
+    #[test]
+    fn converts_from_value() {
+        fn takes_into<T>(t: impl Into<Trident<T>>) -> Trident<T> {
+            t.into()
+        }
+
+        let small: Trident<_> = 3.into();
+        assert_eq!(small, 3);
+
+        let large = takes_into(Large([4; 20]));
+        assert_eq!(large, Large([4; 20]));
+    }
+
+    #[test]
+    fn converts_with_extension_trait() {
+        use super::IntoTrident;
+
+        assert_eq!(
+            SmallCopy { i: 1, j: 2 }.into_trident(),
+            SmallCopy { i: 1, j: 2 }
+        );
+        assert_eq!(LargeCopy([5; 20]).into_trident(), LargeCopy([5; 20]));
+    }
+
+    /// AsRef/AsMut Implementations
+
+    #[test]
+    fn usable_with_as_ref_bounds() {
+        fn total(v: impl AsRef<[i32; 20]>) -> i32 {
+            v.as_ref().iter().sum()
+        }
+
+        fn bump(mut v: impl AsMut<i32>) -> i32 {
+            *v.as_mut() += 1;
+            *v.as_mut()
+        }
+
+        assert_eq!(total(Trident::new([2; 20])), 40);
+        assert_eq!(bump(Trident::new(6)), 7);
+    }
+
+    /// Borrow Implementations
+
+    #[test]
+    fn looks_up_by_borrowed_key() {
+        let mut map = HashMap::new();
+        map.insert(Trident::new(String::from("one")), 1);
+        map.insert(Trident::new(String::from("two")), 2);
+
+        assert_eq!(map.get("one"), Some(&1));
+        assert_eq!(map.get(&String::from("two")), Some(&2));
+        assert_eq!(map.get("three"), None);
+
+        let set: BTreeSet<_> = [vec![1, 2], vec![3]]
+            .into_iter()
+            .map(Trident::new)
+            .collect();
+        assert!(set.contains(&[3][..]));
+    }
+
+    /// Pointer Implementation
+
+    #[test]
+    fn formats_payload_address() {
+        let small = Trident::new(3);
+        let large = Trident::new(Large([0; 20]));
+
+        assert_eq!(format!("{:p}", small), format!("{:p}", small.as_ptr()));
+        assert_eq!(format!("{:p}", large), format!("{:p}", large.as_ptr()));
+        assert_ne!(format!("{:p}", large), format!("{:p}", &large));
+    }
+
+    /// Iterator Implementations
+
+    #[test]
+    fn iterates_small_iterator() {
+        let mut t = Trident::new(1..5);
+
+        assert_eq!(t.len(), 4);
+        assert_eq!(t.next_back(), Some(4));
+        assert_eq!(t.by_ref().map(|x| x * 10).collect::<Vec<_>>(), [10, 20, 30]);
+        assert_eq!(t.next(), None);
+    }
+
+    #[test]
+    fn iterates_large_iterator() {
+        let t = Trident::new([1u64; 8].into_iter().chain([2u64; 8]));
+
+        let mut total = 0;
+        for x in t.rev() {
+            total += x;
+        }
+        assert_eq!(total, 24);
+    }
+
+    /// Error Implementation
+
+    #[test]
+    fn forwards_error_source() {
+        use std::{error::Error, fmt};
+
+        #[derive(Debug)]
+        struct Inner;
+
+        impl fmt::Display for Inner {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "inner")
+            }
+        }
+
+        impl Error for Inner {}
+
+        #[derive(Debug)]
+        struct Outer(Inner);
+
+        impl fmt::Display for Outer {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "outer")
+            }
+        }
+
+        impl Error for Outer {
+            fn source(&self) -> Option<&(dyn Error + 'static)> {
+                Some(&self.0)
+            }
+        }
+
+        fn fails() -> Result<(), Box<dyn Error>> {
+            Err(Trident::new(Outer(Inner)))?;
+            Ok(())
+        }
+
+        let err = fails().unwrap_err();
+        assert_eq!(err.to_string(), "outer");
+        assert_eq!(err.source().unwrap().to_string(), "inner");
+    }
+
+    /// fmt::Write Implementation
+
+    #[test]
+    fn writes_formatted_text() {
+        use std::fmt::Write;
+
+        let mut t = Trident::new(String::new());
+
+        write!(t, "{}+{}", 1, 2).unwrap();
+        t.write_char('=').unwrap();
+
+        assert_eq!(t.as_str(), "1+2=");
+    }
+
+    /// into_inner
+
+    #[test]
+    fn into_inner_doesnt_double_drop() {
+        let drops = Cell::new(0);
+
+        let small = Trident::new(CloneDtor {
+            payload: 1u8,
+            drops: &drops,
+        });
+        let small = small.into_inner();
+        assert_eq!(drops.get(), 0);
+        drop(small);
+        assert_eq!(drops.get(), 1);
+
+        let large = Trident::new(CloneDtor {
+            payload: [1usize; 12],
+            drops: &drops,
+        });
+        let large = large.into_inner();
+        assert_eq!(drops.get(), 1);
+        drop(large);
+        assert_eq!(drops.get(), 2);
+    }
+
+    /// into_erased
+
+    #[test]
+    fn into_erased_keeps_storage() {
+        let drops = Cell::new(0);
+
+        let large = Trident::new(CloneDtor {
+            payload: [4usize; 12],
+            drops: &drops,
+        });
+        let addr = large.as_ptr();
+
+        let erased = large.into_erased();
+        assert_eq!(drops.get(), 0);
+        // SAFETY: created from the same type
+        assert_eq!(unsafe { erased.as_ptr::<CloneDtor<[usize; 12]>>() }, addr);
+
+        // SAFETY: created from the same type
+        let large = unsafe { erased.into_trident::<CloneDtor<[usize; 12]>>() };
+        assert_eq!(large.as_ptr(), addr);
+        assert_eq!(large.payload, [4; 12]);
+        drop(large);
+        assert_eq!(drops.get(), 1);
+
+        let small = Trident::new(SmallCopy { i: 3, j: 4 }).into_erased();
+        // SAFETY: created from the same type
+        assert_eq!(
+            unsafe { small.get::<SmallCopy>() },
+            SmallCopy { i: 3, j: 4 }
+        );
+    }
+
+    /// new_with
+
+    #[test]
+    fn constructs_in_place() {
+        let small = Trident::new_with(|slot| slot.write(SmallCopy { i: 5, j: 6 }));
+        assert_eq!(small, SmallCopy { i: 5, j: 6 });
+
+        let large = Trident::<[u8; 4096]>::new_with(|slot| {
+            let ptr = slot.as_mut_ptr() as *mut u8;
+            for i in 0..4096 {
+                // SAFETY: writing within the slot
+                unsafe { ptr.add(i).write(i as u8) };
+            }
+            // SAFETY: fully initialised above
+            unsafe { slot.assume_init_mut() }
+        });
+        assert!(large.iter().enumerate().all(|(i, &b)| b == i as u8));
+    }
+
+    #[test]
+    #[should_panic(expected = "reference to the slot")]
+    fn new_with_rejects_other_reference() {
+        static mut OTHER: Large = Large([0; 20]);
+
+        // SAFETY: only referenced here
+        let _ = Trident::<Large>::new_with(|_| unsafe { &mut *std::ptr::addr_of_mut!(OTHER) });
+    }
+
+    /// try_new
+
+    #[test]
+    fn try_new_succeeds() {
+        assert_eq!(Trident::try_new(3).unwrap(), 3);
+        assert_eq!(Trident::try_new(Large([2; 20])).unwrap(), Large([2; 20]));
+    }
+
+    /// Box Interop
+
+    #[test]
+    fn adopts_box_allocation() {
+        let boxed = Box::new(Large([6; 20]));
+        let addr = &*boxed as *const Large;
+
+        let t = Trident::<Large>::from(boxed);
+        assert_eq!(t.as_ptr(), addr);
+
+        let boxed = t.into_box();
+        assert_eq!(&*boxed as *const Large, addr);
+        assert_eq!(*boxed, Large([6; 20]));
+    }
+
+    #[test]
+    fn converts_small_box() {
+        let t = Trident::<String>::from(Box::new(String::from("small")));
+        assert_eq!(t.as_str(), "small");
+        assert_eq!(*t.into_box(), "small");
+    }
+
+    /// Raw Parts
+
+    #[test]
+    fn round_trips_raw_parts() {
+        let drops = Cell::new(0);
+
+        let t = Trident::new(CloneDtor {
+            payload: [8usize; 12],
+            drops: &drops,
+        });
+        let words = t.into_raw_parts();
+        assert_eq!(drops.get(), 0);
+
+        // SAFETY: from the same T's into_raw_parts
+        let t = unsafe { Trident::<CloneDtor<[usize; 12]>>::from_raw_parts(words) };
+        assert_eq!(t.payload, [8; 12]);
+        drop(t);
+        assert_eq!(drops.get(), 1);
+
+        let words = Trident::new(SmallCopy { i: 1, j: 2 }).into_raw_parts();
+        // SAFETY: from the same T's into_raw_parts
+        let t = unsafe { Trident::<SmallCopy>::from_raw_parts(words) };
+        assert_eq!(t, SmallCopy { i: 1, j: 2 });
+    }
+
+    /// leak
+
+    #[test]
+    fn leaks_static_references() {
+        let large = Trident::new(Large([5; 20]));
+        let addr = large.as_ptr();
+
+        let leaked: &'static mut Large = large.leak();
+        assert_eq!(leaked as *const Large, addr);
+        leaked.0[0] = 6;
+        assert_eq!(leaked.0[..2], [6, 5]);
+
+        let small: &'static mut u32 = Trident::new(9u32).leak();
+        *small += 1;
+        assert_eq!(*small, 10);
+
+        // SAFETY: leaked from a box, so reclaim to keep leak checkers quiet
+        unsafe {
+            drop(Box::from_raw(leaked as *mut Large));
+            drop(Box::from_raw(small as *mut u32));
+        }
+    }
+
+    /// replace, set & take
+
+    #[test]
+    fn replaces_within_allocation() {
+        let drops = Cell::new(0);
+        let dtor = |n| CloneDtor {
+            payload: [n; 12],
+            drops: &drops,
+        };
+
+        let mut t = Trident::new(dtor(1));
+        let addr = t.as_ptr();
+
+        let old = t.replace(dtor(2));
+        assert_eq!(old.payload, [1; 12]);
+        assert_eq!(t.payload, [2; 12]);
+        assert_eq!(t.as_ptr(), addr);
+        drop(old);
+        assert_eq!(drops.get(), 1);
+
+        t.set(dtor(3));
+        assert_eq!(drops.get(), 2);
+        assert_eq!(t.payload, [3; 12]);
+        assert_eq!(t.as_ptr(), addr);
+    }
+
+    #[test]
+    fn takes_value() {
+        let mut t = Trident::new(vec![1, 2]);
+
+        assert_eq!(t.take(), [1, 2]);
+        assert!(t.is_empty());
+    }
+
+    /// map
+
+    #[test]
+    fn maps_reusing_allocation() {
+        let t = Trident::new([1u64; 8]);
+        let addr = t.as_ptr() as usize;
+
+        let mapped = t.map(|a| a.map(|x| x as i64 * -2));
+        assert_eq!(mapped, [-2; 8]);
+        assert_eq!(mapped.as_ptr() as usize, addr);
+    }
+
+    #[test]
+    fn maps_across_storage() {
+        let small = Trident::new(4u8).map(|x| [x as u64; 8]);
+        assert_eq!(small, [4; 8]);
+
+        let large = small.map(|a| a.iter().sum::<u64>());
+        assert_eq!(large, 32);
+
+        let resized = Trident::new([1u8; 32]).map(|a| [a[0]; 64]);
+        assert_eq!(resized, [1; 64]);
+    }
+
+    #[test]
+    fn map_drops_once() {
+        let drops = Cell::new(0);
+
+        let t = Trident::new(CloneDtor {
+            payload: [1usize; 12],
+            drops: &drops,
+        });
+        let mapped = t.map(|d| d.payload[0] + 1);
+        assert_eq!(drops.get(), 1);
+        assert_eq!(mapped, 2);
+    }
+
+    /// try_map
+
+    #[test]
+    fn try_maps() {
+        let ok: Result<_, ()> = Trident::new([2u64; 8]).try_map(|a| Ok(a.map(|x| x + 1)));
+        assert_eq!(ok.unwrap(), [3; 8]);
+
+        let small: Result<Trident<u8>, _> = Trident::new(300u32).try_map(u8::try_from);
+        assert!(small.is_err());
+    }
+
+    #[test]
+    fn try_map_error_drops_and_frees() {
+        let drops = Cell::new(0);
+
+        let t = Trident::new(CloneDtor {
+            payload: [1usize; 12],
+            drops: &drops,
+        });
+        let err: Result<Trident<[usize; 13]>, _> = t.try_map(|d| Err(d.payload[0]));
+        assert_eq!(err.unwrap_err(), 1);
+        assert_eq!(drops.get(), 1);
+    }
+
+    /// MaybeUninit
+
+    #[test]
+    fn writes_uninit() {
+        let t = Trident::<Large>::uninit();
+        let addr = t.as_ptr() as usize;
+
+        let t = t.write(Large([3; 20]));
+        assert_eq!(t, Large([3; 20]));
+        assert_eq!(t.as_ptr() as usize, addr);
+
+        assert_eq!(Trident::<u16>::uninit().write(4), 4);
+    }
+
+    #[test]
+    fn fills_uninit_incrementally() {
+        let mut t = Trident::<[u32; 64]>::uninit();
+
+        let ptr = t.as_mut_ptr() as *mut u32;
+        for i in 0..64 {
+            // SAFETY: within the array
+            unsafe { ptr.add(i).write(i as u32) };
+        }
+
+        // SAFETY: fully initialised above
+        let t = unsafe { t.assume_init() };
+        assert_eq!(t[63], 63);
+    }
+
+    /// new_inline
+
+    #[test]
+    fn constructs_in_const_context() {
+        static TABLE: [Trident<(u32, u32)>; 2] =
+            [Trident::new_inline((1, 2)), Trident::new_inline((3, 4))];
+        const SMALL: Trident<SmallCopy> = Trident::new_inline(SmallCopy { i: -1, j: 1 });
+
+        assert_eq!(TABLE[1], (3, 4));
+        assert_eq!(SMALL.get(), SmallCopy { i: -1, j: 1 });
+    }
+
+    #[test]
+    fn mutates_through_shared_references() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        static COUNTER: Trident<AtomicU32> = Trident::new_inline(AtomicU32::new(0));
+        COUNTER.fetch_add(1, Ordering::Relaxed);
+        assert_eq!(COUNTER.load(Ordering::Relaxed), 1);
+
+        let cell = Trident::new(Cell::new(1u8));
+        let (a, b): (&Cell<u8>, &Cell<u8>) = (&cell, &cell);
+        a.set(b.get() + 1);
+        assert_eq!(cell.into_inner().get(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "too large")]
+    fn new_inline_rejects_large_types() {
+        let _ = Trident::new_inline(Large([0; 20]));
+    }
+
+    /// recycle
+
+    #[test]
+    fn recycles_allocation() {
+        let drops = Cell::new(0);
+
+        let t = Trident::new(CloneDtor {
+            payload: [1usize; 12],
+            drops: &drops,
+        });
+        let addr = t.as_ptr() as usize;
+
+        let recycled = t.recycle([2usize; 13]);
+        assert_eq!(drops.get(), 1);
+        assert_eq!(recycled, [2; 13]);
+        assert_eq!(recycled.as_ptr() as usize, addr);
+
+        let resized = recycled.recycle(3u8);
+        assert_eq!(resized, 3);
+    }
+
+    /// swap
+
+    #[test]
+    fn swaps_inline() {
+        let mut a = Trident::new(SmallCopy { i: 1, j: 2 });
+        let mut b = Trident::new(SmallCopy { i: 3, j: 4 });
+
+        a.swap(&mut b);
+        assert_eq!(a.get(), SmallCopy { i: 3, j: 4 });
+        assert_eq!(b.get(), SmallCopy { i: 1, j: 2 });
+    }
+
+    #[test]
+    fn swaps_boxed_by_pointer() {
+        let mut a = Trident::new(LargeCopy([1; 20]));
+        let mut b = Trident::new(LargeCopy([2; 20]));
+        let (pa, pb) = (a.as_ptr(), b.as_ptr());
+
+        a.swap(&mut b);
+        assert_eq!(a.get(), LargeCopy([2; 20]));
+        assert_eq!(b.get(), LargeCopy([1; 20]));
+        assert_eq!((a.as_ptr(), b.as_ptr()), (pb, pa));
+    }
+
+    /// forget
+
+    #[test]
+    fn forget_skips_drop() {
+        let drops = Cell::new(0);
+
+        let mut t = Trident::new(CloneDtor {
+            payload: [1usize; 12],
+            drops: &drops,
+        });
+        let ptr = t.as_mut_ptr();
+        t.forget();
+        assert_eq!(drops.get(), 0);
+
+        // SAFETY: the allocation was forgotten, so we now own it
+        drop(unsafe { Box::from_raw(ptr) });
+        assert_eq!(drops.get(), 1);
+    }
+
+    /// clone_from
+
+    #[derive(PartialEq, Debug)]
+    struct CloneFrom {
+        payload: [usize; 8],
+        clone_froms: u32,
+    }
+
+    impl Clone for CloneFrom {
+        fn clone(&self) -> Self {
+            Self {
+                payload: self.payload,
+                clone_froms: 0,
+            }
+        }
+
+        fn clone_from(&mut self, source: &Self) {
+            self.payload = source.payload;
+            self.clone_froms += 1;
+        }
+    }
+
+    #[test]
+    fn clone_from_reuses_allocation() {
+        let source = Trident::new(CloneFrom {
+            payload: [1; 8],
+            clone_froms: 0,
+        });
+        let mut dest = Trident::new(CloneFrom {
+            payload: [2; 8],
+            clone_froms: 0,
+        });
+        let ptr = dest.as_ptr();
+
+        dest.clone_from(&source);
+        assert_eq!(dest.as_ptr(), ptr);
+        assert_eq!(dest.payload, [1; 8]);
+        assert_eq!(dest.clone_froms, 1);
+    }
+
+    /// map_in_place
+
+    #[test]
+    fn maps_in_place() {
+        let mut small = Trident::new(String::from("a"));
+        let mut large = Trident::new(LargeCopy([1; 20]));
+        let ptr = large.as_ptr();
+
+        small.map_in_place(|s| s + "b");
+        large.map_in_place(|LargeCopy(a)| LargeCopy(a.map(|i| i + 1)));
+
+        assert_eq!(*small, "ab");
+        assert_eq!(large.get(), LargeCopy([2; 20]));
+        assert_eq!(large.as_ptr(), ptr);
+    }
+
+    /// Storage migration
+
+    #[test]
+    fn spills_to_heap() {
+        let mut t = Trident::new(SmallCopy { i: 1, j: 2 });
+        assert!(t.is_inline());
+
+        t.spill_to_heap();
+        assert!(!t.is_inline());
+        let ptr = t.as_ptr();
+
+        t.spill_to_heap();
+        let moved = t;
+        assert_eq!(moved.as_ptr(), ptr);
+        assert_eq!(moved.get(), SmallCopy { i: 1, j: 2 });
+        assert_eq!(moved.into_inner(), SmallCopy { i: 1, j: 2 });
+    }
+
+    #[test]
+    fn makes_inline() {
+        let mut t = Trident::new(String::from("x").into_boxed_str());
+        t.spill_to_heap();
+
+        assert!(t.try_make_inline());
+        assert!(t.is_inline());
+        assert!(t.try_make_inline());
+        assert_eq!(&**t, "x");
+
+        let mut large = Trident::new(Large([0; 20]));
+        assert!(!large.try_make_inline());
+        assert!(!large.is_inline());
+    }
+
+    #[test]
+    fn debugs_spilled() {
+        let mut t = Trident::new(1u8);
+        t.spill_to_heap();
+        assert_eq!(format!("{:#?}", t), "Boxed(\n    1,\n)");
+    }
+
+    #[test]
+    fn drops_spilled() {
+        let drops = Cell::new(0);
+
+        let mut t = Trident::new(CloneDtor {
+            payload: 1u8,
+            drops: &drops,
+        });
+        t.spill_to_heap();
+
+        let boxed = t.into_box();
+        assert_eq!(boxed.payload, 1);
+        drop(boxed);
+        assert_eq!(drops.get(), 1);
+    }
+
+    /// Storage class
+
+    #[test]
+    fn reports_storage_class() {
+        let small = Trident::new(1u8);
+        let large = Trident::new(Large([0; 20]));
+
+        assert!(small.is_inline());
+        assert!(!large.is_inline());
+    }
+
+    #[test]
+    fn boxes_overaligned_type() {
+        #[repr(align(64))]
+        struct Aligned(u8);
+
+        const { assert!(!Trident::<Aligned>::IS_INLINE) }
+
+        let t = Trident::new(Aligned(3));
+        assert_eq!(t.as_ptr() as usize % 64, 0);
+        assert_eq!(t.0, 3);
+    }
+
+    /// Rc and Arc
+
+    #[test]
+    fn converts_to_rc() {
+        let small: Rc<String> = Trident::new(String::from("small")).into();
+        let large: Rc<LargeCopy> = Trident::new(LargeCopy([4; 20])).into();
+
+        assert_eq!(*small, "small");
+        assert_eq!(*large, LargeCopy([4; 20]));
+    }
+
+    #[test]
+    fn converts_to_arc() {
+        let drops = Cell::new(0);
+
+        let arc: Arc<CloneDtor<_>> = Arc::from(Trident::new(CloneDtor {
+            payload: [7usize; 12],
+            drops: &drops,
+        }));
+        assert_eq!(drops.get(), 0);
+        assert_eq!(arc.payload, [7; 12]);
+
+        drop(arc);
+        assert_eq!(drops.get(), 1);
+    }
+
+    /// new_boxed
+
+    #[test]
+    fn new_boxed_always_spills() {
+        let small = Trident::new_boxed(SmallCopy { i: 1, j: 2 });
+        let large = Trident::new_boxed(LargeCopy([3; 20]));
+
+        assert!(!small.is_inline());
+        assert!(!large.is_inline());
+        assert_eq!(small.get(), SmallCopy { i: 1, j: 2 });
+        assert_eq!(*small.into_box(), SmallCopy { i: 1, j: 2 });
+        assert_eq!(large.into_inner(), LargeCopy([3; 20]));
+    }
+
+    #[test]
+    fn new_boxed_keeps_the_type() {
+        let mut mixed = [
+            Trident::new(SmallCopy { i: 1, j: 2 }),
+            Trident::new_boxed(SmallCopy { i: 3, j: 4 }),
+        ];
+
+        assert!(mixed[0].is_inline());
+        assert!(!mixed[1].is_inline());
+        assert!(mixed[1].try_make_inline());
+        assert!(mixed[1].is_inline());
+        assert_eq!(mixed[1].get(), SmallCopy { i: 3, j: 4 });
+
+        let boxed = Trident::new_boxed(SmallCopy { i: 5, j: 6 });
+        let addr = boxed.as_ptr();
+        assert_eq!(&*boxed.into_box() as *const _, addr);
     }
 }
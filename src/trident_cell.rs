@@ -0,0 +1,168 @@
+use std::{cell::UnsafeCell, fmt, mem, ptr};
+
+use crate::Trident;
+
+/**
+ * A mutable memory location, as `std::cell::Cell<T>`, with the value stored as in `Trident<T>`, so
+ * small values are inline and large ones boxed.
+ *
+ * The value is only ever copied or moved in and out, never referenced, so it can be changed
+ * through a shared reference without `RefCell`'s borrow tracking.
+ */
+pub struct TridentCell<T> {
+    value: UnsafeCell<Trident<T>>,
+}
+
+impl<T> TridentCell<T> {
+    /**
+     * Create a `TridentCell<T>` containing `t`.
+     */
+    pub fn new(t: T) -> Self {
+        Self {
+            value: UnsafeCell::new(Trident::new(t)),
+        }
+    }
+
+    /**
+     * Get a copy of the value.
+     */
+    pub fn get(&self) -> T
+    where
+        T: Copy,
+    {
+        // SAFETY: no references to the value exist outside of this cell's methods, and we're
+        // !Sync, so nothing can be writing it
+        unsafe { **self.value.get() }
+    }
+
+    /**
+     * Set the value, dropping the previous one.
+     */
+    pub fn set(&self, t: T) {
+        drop(self.replace(t));
+    }
+
+    /**
+     * Set the value, returning the previous one.
+     */
+    pub fn replace(&self, t: T) -> T {
+        // SAFETY: as get(), and the previous value is only dropped once we've returned
+        mem::replace(unsafe { &mut **self.value.get() }, t)
+    }
+
+    /**
+     * Swap the values of two cells.
+     */
+    pub fn swap(&self, other: &Self) {
+        if !ptr::eq(self, other) {
+            // SAFETY: as get(), and the cells are distinct
+            unsafe { mem::swap(&mut **self.value.get(), &mut **other.value.get()) }
+        }
+    }
+
+    /**
+     * Take the value, leaving `T::default()`.
+     */
+    pub fn take(&self) -> T
+    where
+        T: Default,
+    {
+        self.replace(T::default())
+    }
+
+    /**
+     * Get a mutable reference to the value, which the `&mut self` guarantees is unique.
+     */
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+
+    /**
+     * Convert to the contained `T`.
+     */
+    pub fn into_inner(self) -> T {
+        self.value.into_inner().into_inner()
+    }
+}
+
+impl<T: Copy> Clone for TridentCell<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.get())
+    }
+}
+
+impl<T: Default> Default for TridentCell<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T> From<T> for TridentCell<T> {
+    fn from(t: T) -> Self {
+        Self::new(t)
+    }
+}
+
+impl<T: Copy + PartialEq> PartialEq for TridentCell<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.get() == other.get()
+    }
+}
+
+impl<T: Copy + Eq> Eq for TridentCell<T> {}
+
+impl<T: Copy + fmt::Debug> fmt::Debug for TridentCell<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TridentCell")
+            .field("value", &self.get())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Copy, Debug, PartialEq, Default)]
+    struct Stats {
+        frames: u64,
+        dropped: u64,
+        peak: [u32; 6],
+    }
+
+    #[test]
+    fn mutates_through_shared_references() {
+        let small = TridentCell::new(1u32);
+        let large = TridentCell::new(Stats::default());
+        let (a, b) = (&large, &large);
+
+        small.set(small.get() + 1);
+        a.set(Stats {
+            frames: 10,
+            ..b.get()
+        });
+        assert_eq!(small.replace(7), 2);
+        assert_eq!(large.get().frames, 10);
+        assert_eq!(format!("{small:?}"), "TridentCell { value: 7 }");
+
+        let other = TridentCell::new(Stats {
+            dropped: 3,
+            ..Default::default()
+        });
+        large.swap(&other);
+        large.swap(&large);
+        assert_eq!(large.take().dropped, 3);
+        assert_eq!(other.into_inner().frames, 10);
+        assert_eq!(large, TridentCell::default());
+    }
+
+    #[test]
+    fn holds_non_copy_values() {
+        let mut cell = TridentCell::new(String::from("a"));
+
+        cell.set(String::from("b"));
+        cell.get_mut().push('c');
+        assert_eq!(cell.replace(String::new()), "bc");
+        assert_eq!(cell.into_inner(), "");
+    }
+}
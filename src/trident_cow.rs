@@ -0,0 +1,253 @@
+use std::{
+    borrow::Borrow,
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    ops::Deref,
+};
+
+use crate::Trident;
+
+/**
+ * A clone-on-write pointer, as `std::borrow::Cow<'a, T>`, where an owned value is stored as in
+ * `Trident<T>`, so it's either borrowed, owned inline, or owned and boxed.
+ *
+ * For example, a configuration value may usually be borrowed from a static table, and only owned
+ * once it's overridden, with a small override not allocating.
+ */
+pub enum TridentCow<'a, T> {
+    /**
+     * A borrowed value.
+     */
+    Borrowed(&'a T),
+    /**
+     * An owned value, inline if it fits.
+     */
+    Owned(Trident<T>),
+}
+
+impl<'a, T> TridentCow<'a, T> {
+    /**
+     * Create an owned `TridentCow`.
+     */
+    pub fn owned(t: T) -> Self {
+        Self::Owned(Trident::new(t))
+    }
+
+    /**
+     * Whether the value is borrowed.
+     */
+    pub fn is_borrowed(&self) -> bool {
+        matches!(self, Self::Borrowed(_))
+    }
+
+    /**
+     * Whether the value is owned.
+     */
+    pub fn is_owned(&self) -> bool {
+        !self.is_borrowed()
+    }
+
+    /**
+     * Whether the value is owned and stored inline.
+     */
+    pub fn is_inline(&self) -> bool {
+        match self {
+            Self::Borrowed(_) => false,
+            Self::Owned(t) => t.is_inline(),
+        }
+    }
+
+    /**
+     * Get a mutable reference to the value, cloning it first if it's borrowed.
+     */
+    pub fn to_mut(&mut self) -> &mut T
+    where
+        T: Clone,
+    {
+        if let Self::Borrowed(t) = *self {
+            *self = Self::owned(t.clone());
+        }
+
+        match self {
+            Self::Borrowed(_) => unreachable!(),
+            Self::Owned(t) => t,
+        }
+    }
+
+    /**
+     * Convert to an owned `T`, cloning it if it's borrowed.
+     */
+    pub fn into_owned(self) -> T
+    where
+        T: Clone,
+    {
+        match self {
+            Self::Borrowed(t) => t.clone(),
+            Self::Owned(t) => t.into_inner(),
+        }
+    }
+
+    /**
+     * Convert to an owned `Trident<T>`, cloning the value if it's borrowed.
+     */
+    pub fn into_trident(self) -> Trident<T>
+    where
+        T: Clone,
+    {
+        match self {
+            Self::Borrowed(t) => Trident::new(t.clone()),
+            Self::Owned(t) => t,
+        }
+    }
+}
+
+impl<T> Deref for TridentCow<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        match self {
+            Self::Borrowed(t) => t,
+            Self::Owned(t) => t,
+        }
+    }
+}
+
+impl<T> AsRef<T> for TridentCow<'_, T> {
+    fn as_ref(&self) -> &T {
+        self
+    }
+}
+
+impl<T> Borrow<T> for TridentCow<'_, T> {
+    fn borrow(&self) -> &T {
+        self
+    }
+}
+
+/**
+ * A borrowed value is cloned by copying the reference.
+ */
+impl<T: Clone> Clone for TridentCow<'_, T> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Borrowed(t) => Self::Borrowed(t),
+            Self::Owned(t) => Self::Owned(t.clone()),
+        }
+    }
+}
+
+impl<T: Default> Default for TridentCow<'_, T> {
+    fn default() -> Self {
+        Self::owned(T::default())
+    }
+}
+
+impl<'a, T> From<&'a T> for TridentCow<'a, T> {
+    fn from(t: &'a T) -> Self {
+        Self::Borrowed(t)
+    }
+}
+
+impl<T> From<Trident<T>> for TridentCow<'_, T> {
+    fn from(t: Trident<T>) -> Self {
+        Self::Owned(t)
+    }
+}
+
+impl<T: PartialEq> PartialEq for TridentCow<'_, T> {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl<T: Eq> Eq for TridentCow<'_, T> {}
+
+impl<T: PartialOrd> PartialOrd for TridentCow<'_, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        (**self).partial_cmp(&**other)
+    }
+}
+
+impl<T: Ord> Ord for TridentCow<'_, T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (**self).cmp(&**other)
+    }
+}
+
+impl<T: Hash> Hash for TridentCow<'_, T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for TridentCow<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for TridentCow<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Limits {
+        timeout_ms: u32,
+        retries: u8,
+    }
+
+    static DEFAULTS: [Limits; 2] = [
+        Limits {
+            timeout_ms: 100,
+            retries: 3,
+        },
+        Limits {
+            timeout_ms: 5000,
+            retries: 0,
+        },
+    ];
+
+    #[test]
+    fn clones_on_write() {
+        let mut limits = TridentCow::from(&DEFAULTS[0]);
+        assert!(limits.is_borrowed());
+        assert_eq!(limits.timeout_ms, 100);
+
+        limits.to_mut().retries = 5;
+        assert!(limits.is_owned());
+        assert!(limits.is_inline());
+        assert_eq!(DEFAULTS[0].retries, 3);
+
+        let before = limits.to_mut() as *const Limits;
+        limits.to_mut().timeout_ms = 200;
+        assert_eq!(limits.to_mut() as *const Limits, before);
+        assert_eq!(
+            limits.into_owned(),
+            Limits {
+                timeout_ms: 200,
+                retries: 5
+            }
+        );
+    }
+
+    #[test]
+    fn owns_large_values() {
+        let table = [String::from("a"), String::from("b")];
+        let borrowed = TridentCow::from(&table);
+        let mut owned = borrowed.clone();
+
+        owned.to_mut()[1].push('c');
+        assert!(borrowed.is_borrowed());
+        assert!(owned.is_owned() && !owned.is_inline());
+        assert_ne!(borrowed, owned);
+        assert_eq!(owned.into_trident().concat(), "abc");
+        assert_eq!(borrowed.into_owned(), table);
+    }
+}
@@ -0,0 +1,343 @@
+#[cfg(feature = "nightly")]
+use std::marker::Unsize;
+use std::{
+    alloc::{self, Layout},
+    fmt,
+    marker::PhantomData,
+    mem,
+    ops::{Deref, DerefMut},
+    ptr::{self, NonNull},
+};
+
+use crate::payload::{self, Payload};
+use crate::Trident;
+
+/**
+ * A `Trident<T>` whose `T` has been unsized to `U`, for example a `dyn Trait`, as
+ * `Box<dyn Trait>`, in three words.
+ *
+ * As in `SmallFn`, a `T` of up to two words is stored inline, and a larger one is boxed. `U`'s
+ * metadata can't be created from a stored word on stable, so the third word is a function,
+ * monomorphised for `T`, which finds the payload and unsizes a pointer to it. `ThinDyn` stores
+ * the metadata itself, on nightly.
+ *
+ * `Trident<T>` itself can't be unsized with `CoerceUnsized`, as it holds its `T` by value, rather
+ * than through a pointer the compiler could attach metadata to. Instead, convert with `into_dyn`,
+ * on nightly, or `coerce_dyn!`.
+ */
+pub struct TridentDyn<U: ?Sized> {
+    payload: Payload,
+    resolve: unsafe fn(*mut Payload) -> *mut U,
+    _phantom: PhantomData<U>,
+}
+
+// SAFETY: the `T` is only reachable as a `U`, so is as `Send` and `Sync` as `U`
+unsafe impl<U: ?Sized + Send> Send for TridentDyn<U> {}
+unsafe impl<U: ?Sized + Sync> Sync for TridentDyn<U> {}
+
+impl<U: ?Sized> TridentDyn<U> {
+    /**
+     * Create a `TridentDyn<U>` from a `Payload` and a function which, given a pointer to it,
+     * finds the value and unsizes a pointer to it.
+     *
+     * Unsafe because `resolve` must return a pointer to the value in the `Payload` it's given, as
+     * mutable as that `Payload`.
+     */
+    unsafe fn from_raw_parts(payload: Payload, resolve: unsafe fn(*mut Payload) -> *mut U) -> Self {
+        Self {
+            payload,
+            resolve,
+            _phantom: PhantomData,
+        }
+    }
+
+    /**
+     * Whether the contained value is stored inline.
+     */
+    pub fn is_inline(&self) -> bool {
+        payload::inlines_layout(Layout::for_value(&**self))
+    }
+
+    fn as_ptr(&self) -> *const U {
+        // SAFETY: `resolve` was created for our payload, and the pointer is only written through
+        // if U allows, via the Payload's UnsafeCell
+        unsafe { (self.resolve)(&self.payload as *const Payload as *mut Payload) }
+    }
+
+    fn as_mut_ptr(&mut self) -> *mut U {
+        // SAFETY: `resolve` was created for our payload
+        unsafe { (self.resolve)(&mut self.payload) }
+    }
+}
+
+impl<T> Trident<T> {
+    /**
+     * Unsize the `T` to a `U`, for example a `dyn Trait` it implements, reusing its allocation if
+     * it's boxed in both.
+     */
+    #[cfg(feature = "nightly")]
+    pub fn into_dyn<U: ?Sized>(self) -> TridentDyn<U>
+    where
+        T: Unsize<U>,
+    {
+        // SAFETY: `resolve_unsize::<T, U>` finds our T
+        unsafe { TridentDyn::from_raw_parts(Payload::from_trident(self), resolve_unsize::<T, U>) }
+    }
+
+    /**
+     * Unsize the `T` to a `U` with `f`, which casts a pointer to it, for example
+     * `|p| p as *mut dyn Trait`, reusing its allocation if it's boxed in both. On stable, this
+     * stands in for `into_dyn`.
+     *
+     * `f` must capture nothing, and fails to compile otherwise, as it's called whenever the `U`
+     * is accessed. See `coerce_dyn!`, which wraps this.
+     *
+     * # Safety
+     *
+     * `f` must return the pointer it's given, unsized, for example by casting it.
+     */
+    pub unsafe fn coerce_with<U: ?Sized, F>(self, f: F) -> TridentDyn<U>
+    where
+        F: Fn(*mut T) -> *mut U + Copy,
+    {
+        #[allow(clippy::let_unit_value)]
+        let () = CoerceFn::<F>::CAPTURES_NOTHING;
+        let _ = f;
+
+        TridentDyn::from_raw_parts(Payload::from_trident(self), resolve_with::<T, U, F>)
+    }
+}
+
+struct CoerceFn<F>(PhantomData<F>);
+
+impl<F> CoerceFn<F> {
+    const CAPTURES_NOTHING: () = assert!(
+        mem::size_of::<F>() == 0,
+        "the function passed to coerce_with must capture nothing"
+    );
+}
+
+#[cfg(feature = "nightly")]
+impl<T: Unsize<U>, U: ?Sized> From<Trident<T>> for TridentDyn<U> {
+    fn from(t: Trident<T>) -> Self {
+        t.into_dyn()
+    }
+}
+
+/**
+ * Find the `T` in the `Payload` at `payload`, and unsize a pointer to it.
+ *
+ * Unsafe because `payload` must point to a `Payload` holding a `T`.
+ */
+#[cfg(feature = "nightly")]
+unsafe fn resolve_unsize<T: Unsize<U>, U: ?Sized>(payload: *mut Payload) -> *mut U {
+    Payload::resolve::<T>(payload)
+}
+
+/**
+ * Find the `T` in the `Payload` at `payload`, and unsize a pointer to it with an `F`.
+ *
+ * Unsafe because `payload` must point to a `Payload` holding a `T`, and `F` must be as described
+ * by `coerce_with`.
+ */
+unsafe fn resolve_with<T, U: ?Sized, F>(payload: *mut Payload) -> *mut U
+where
+    F: Fn(*mut T) -> *mut U + Copy,
+{
+    // F is zero-sized, so this copies the F given to coerce_with
+    let f = ptr::read(NonNull::<F>::dangling().as_ptr());
+
+    f(Payload::resolve::<T>(payload))
+}
+
+/**
+ * Convert a `Trident<T>` to a `TridentDyn<U>`, for a `U` such as `dyn Trait` which `T` can be
+ * unsized to: `coerce_dyn!(t, dyn Trait)`. This works on stable, as `Trident::into_dyn` does on
+ * nightly.
+ */
+#[macro_export]
+macro_rules! coerce_dyn {
+    ($trident:expr, $u:ty) => {
+        match $trident {
+            trident => {
+                let trident: $crate::Trident<_> = trident;
+
+                // SAFETY: the closure only casts its argument
+                unsafe { trident.coerce_with(|p| p as *mut $u) }
+            }
+        }
+    };
+}
+
+impl<U: ?Sized> Deref for TridentDyn<U> {
+    type Target = U;
+
+    fn deref(&self) -> &U {
+        // SAFETY: we hold a valid payload
+        unsafe { &*self.as_ptr() }
+    }
+}
+
+impl<U: ?Sized> DerefMut for TridentDyn<U> {
+    fn deref_mut(&mut self) -> &mut U {
+        // SAFETY: we hold a valid payload
+        unsafe { &mut *self.as_mut_ptr() }
+    }
+}
+
+impl<U: ?Sized> Drop for TridentDyn<U> {
+    fn drop(&mut self) {
+        let inline = self.is_inline();
+        let layout = Layout::for_value(&**self);
+        let ptr = self.as_mut_ptr();
+
+        // SAFETY: we hold a valid payload, which isn't used again. Freed as boxed, if not inline
+        unsafe {
+            ptr.drop_in_place();
+            if !inline && layout.size() != 0 {
+                alloc::dealloc(ptr as *mut u8, layout);
+            }
+        }
+    }
+}
+
+impl<U: ?Sized + fmt::Debug> fmt::Debug for TridentDyn<U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<U: ?Sized + fmt::Display> fmt::Display for TridentDyn<U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, fmt::Display, rc::Rc};
+
+    use super::*;
+
+    trait Shape {
+        fn area(&self) -> u64;
+        fn grow(&mut self);
+    }
+
+    struct Square(u64);
+
+    struct Polygon {
+        sides: [u64; 8],
+        drops: Rc<Cell<u32>>,
+    }
+
+    impl Shape for Square {
+        fn area(&self) -> u64 {
+            self.0 * self.0
+        }
+
+        fn grow(&mut self) {
+            self.0 += 1;
+        }
+    }
+
+    impl Shape for Polygon {
+        fn area(&self) -> u64 {
+            self.sides.iter().sum()
+        }
+
+        fn grow(&mut self) {
+            self.sides[0] += 1;
+        }
+    }
+
+    impl Drop for Polygon {
+        fn drop(&mut self) {
+            self.drops.set(self.drops.get() + 1);
+        }
+    }
+
+    #[test]
+    fn dispatches() {
+        let drops = Rc::new(Cell::new(0));
+        let mut shapes: Vec<TridentDyn<dyn Shape>> = vec![
+            coerce_dyn!(Trident::new(Square(2)), dyn Shape),
+            coerce_dyn!(
+                Trident::new(Polygon {
+                    sides: [1; 8],
+                    drops: drops.clone(),
+                }),
+                dyn Shape
+            ),
+        ];
+
+        assert!(shapes[0].is_inline());
+        assert!(!shapes[1].is_inline());
+
+        for shape in &mut shapes {
+            shape.grow();
+        }
+        assert_eq!(shapes.iter().map(|s| s.area()).collect::<Vec<_>>(), [9, 9]);
+
+        drop(shapes);
+        assert_eq!(drops.get(), 1);
+    }
+
+    #[test]
+    fn keeps_spilled_storage() {
+        let mut t = Trident::new([7u8; 20]);
+        t.spill_to_heap();
+        let boxed = t.as_ptr() as *const u8;
+
+        let s = coerce_dyn!(t, dyn fmt::Debug);
+        assert!(!s.is_inline());
+        assert_eq!(&*s as *const dyn fmt::Debug as *const u8, boxed);
+        assert_eq!(format!("{:?}", s), format!("{:?}", [7u8; 20]));
+
+        let mut t = Trident::new(Box::<str>::from("inline"));
+        t.spill_to_heap();
+
+        let s = coerce_dyn!(t, dyn Display);
+        assert!(s.is_inline());
+        assert_eq!(s.to_string(), "inline");
+    }
+
+    #[test]
+    fn is_three_words() {
+        assert_eq!(
+            mem::size_of::<TridentDyn<dyn Shape>>(),
+            3 * mem::size_of::<usize>()
+        );
+
+        let full = coerce_dyn!(Trident::new([1u64, 2, 3]), dyn fmt::Debug);
+        assert!(!full.is_inline());
+        assert_eq!(format!("{:?}", full), "[1, 2, 3]");
+    }
+
+    #[test]
+    fn is_send_for_send_traits() {
+        fn assert_send<T: Send>(_: &T) {}
+
+        let s = coerce_dyn!(Trident::new(1u8), dyn Display + Send);
+        assert_send(&s);
+        assert_eq!(format!("{}", s), "1");
+    }
+
+    #[cfg(feature = "nightly")]
+    #[test]
+    fn unsizes_on_nightly() {
+        let drops = Rc::new(Cell::new(0));
+        let shapes: [TridentDyn<dyn Shape>; 2] = [
+            Trident::new(Square(3)).into_dyn(),
+            Trident::new(Polygon {
+                sides: [2; 8],
+                drops: drops.clone(),
+            })
+            .into(),
+        ];
+
+        assert_eq!(shapes.iter().map(|s| s.area()).collect::<Vec<_>>(), [9, 16]);
+        drop(shapes);
+        assert_eq!(drops.get(), 1);
+    }
+}
@@ -0,0 +1,325 @@
+use std::{
+    cell::Cell,
+    error::Error,
+    fmt,
+    marker::PhantomData,
+    mem::{self, ManuallyDrop},
+    ops::{Deref, DerefMut},
+};
+
+use crate::payload::{self, Payload};
+
+/**
+ * A mutable memory location with dynamically checked borrows, as `std::cell::RefCell<T>`, in three
+ * words, as a `Trident<T>`.
+ *
+ * The borrow state can't be packed into a `Trident<T>`'s own storage, as an inline `T` may fill all
+ * 3 words, leaving no spare bits. Instead, as in `SmallFn`, a `T` of up to two words is stored
+ * inline and a larger one is boxed, leaving the third word for the borrow state.
+ */
+pub struct TridentRefCell<T> {
+    value: Payload,
+    // the number of shared borrows, or MUTABLY_BORROWED
+    borrows: Cell<isize>,
+    _phantom: PhantomData<T>,
+}
+
+/**
+ * A shared borrow of a `TridentRefCell<T>`'s value, from `TridentRefCell::borrow`.
+ */
+pub struct TridentRef<'a, T> {
+    cell: &'a TridentRefCell<T>,
+}
+
+/**
+ * A mutable borrow of a `TridentRefCell<T>`'s value, from `TridentRefCell::borrow_mut`.
+ */
+pub struct TridentRefMut<'a, T> {
+    cell: &'a TridentRefCell<T>,
+}
+
+/**
+ * The error returned by `TridentRefCell::try_borrow` when the value is mutably borrowed.
+ */
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BorrowError;
+
+/**
+ * The error returned by `TridentRefCell::try_borrow_mut` when the value is borrowed.
+ */
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BorrowMutError;
+
+const MUTABLY_BORROWED: isize = -1;
+
+impl<T> TridentRefCell<T> {
+    /**
+     * Create a `TridentRefCell<T>` containing `t`.
+     */
+    pub fn new(t: T) -> Self {
+        Self {
+            value: Payload::new(t),
+            borrows: Cell::new(0),
+            _phantom: PhantomData,
+        }
+    }
+
+    /**
+     * Whether the value is stored inline.
+     */
+    pub fn is_inline(&self) -> bool {
+        payload::inlines::<T>()
+    }
+
+    /**
+     * Borrow the value, or return an error if it's mutably borrowed.
+     */
+    pub fn try_borrow(&self) -> Result<TridentRef<'_, T>, BorrowError> {
+        let borrows = self.borrows.get();
+        if borrows == MUTABLY_BORROWED {
+            return Err(BorrowError);
+        }
+
+        self.borrows
+            .set(borrows.checked_add(1).expect("too many borrows"));
+        Ok(TridentRef { cell: self })
+    }
+
+    /**
+     * Borrow the value.
+     *
+     * # Panics
+     *
+     * Panics if the value is mutably borrowed.
+     */
+    #[track_caller]
+    pub fn borrow(&self) -> TridentRef<'_, T> {
+        match self.try_borrow() {
+            Ok(r) => r,
+            Err(e) => panic!("{e}"),
+        }
+    }
+
+    /**
+     * Mutably borrow the value, or return an error if it's borrowed.
+     */
+    pub fn try_borrow_mut(&self) -> Result<TridentRefMut<'_, T>, BorrowMutError> {
+        if self.borrows.get() != 0 {
+            return Err(BorrowMutError);
+        }
+
+        self.borrows.set(MUTABLY_BORROWED);
+        Ok(TridentRefMut { cell: self })
+    }
+
+    /**
+     * Mutably borrow the value.
+     *
+     * # Panics
+     *
+     * Panics if the value is borrowed.
+     */
+    #[track_caller]
+    pub fn borrow_mut(&self) -> TridentRefMut<'_, T> {
+        match self.try_borrow_mut() {
+            Ok(r) => r,
+            Err(e) => panic!("{e}"),
+        }
+    }
+
+    /**
+     * Set the value, returning the previous one.
+     *
+     * # Panics
+     *
+     * Panics if the value is borrowed.
+     */
+    #[track_caller]
+    pub fn replace(&self, t: T) -> T {
+        mem::replace(&mut *self.borrow_mut(), t)
+    }
+
+    /**
+     * Get a mutable reference to the value, which the `&mut self` guarantees is unique.
+     */
+    pub fn get_mut(&mut self) -> &mut T {
+        // SAFETY: our payload holds a T, and the &mut self means nothing else borrows it
+        unsafe { &mut *Payload::resolve::<T>(&mut self.value) }
+    }
+
+    /**
+     * Convert to the contained `T`.
+     */
+    pub fn into_inner(self) -> T {
+        let mut this = ManuallyDrop::new(self);
+
+        // SAFETY: our payload holds a T, and isn't used again
+        unsafe { Payload::take::<T>(&mut this.value) }
+    }
+
+    fn as_ptr(&self) -> *mut T {
+        // SAFETY: our payload holds a T. The pointer is only written through while mutably
+        // borrowed, via the Payload's UnsafeCell
+        unsafe { Payload::resolve::<T>(&self.value as *const Payload as *mut Payload) }
+    }
+}
+
+impl<T> Drop for TridentRefCell<T> {
+    fn drop(&mut self) {
+        // SAFETY: our payload holds a T, which isn't used again
+        unsafe { Payload::drop_in_place::<T>(&mut self.value) }
+    }
+}
+
+impl fmt::Display for BorrowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("already mutably borrowed")
+    }
+}
+
+impl Error for BorrowError {}
+
+impl fmt::Display for BorrowMutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("already borrowed")
+    }
+}
+
+impl Error for BorrowMutError {}
+
+impl<T> Deref for TridentRef<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: we hold a shared borrow, so there are no mutable ones
+        unsafe { &*self.cell.as_ptr() }
+    }
+}
+
+impl<T> Drop for TridentRef<'_, T> {
+    fn drop(&mut self) {
+        self.cell.borrows.set(self.cell.borrows.get() - 1);
+    }
+}
+
+impl<T> Deref for TridentRefMut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: we hold the only borrow
+        unsafe { &*self.cell.as_ptr() }
+    }
+}
+
+impl<T> DerefMut for TridentRefMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // SAFETY: we hold the only borrow
+        unsafe { &mut *self.cell.as_ptr() }
+    }
+}
+
+impl<T> Drop for TridentRefMut<'_, T> {
+    fn drop(&mut self) {
+        self.cell.borrows.set(0);
+    }
+}
+
+impl<T: Clone> Clone for TridentRefCell<T> {
+    #[track_caller]
+    fn clone(&self) -> Self {
+        Self::new(self.borrow().clone())
+    }
+}
+
+impl<T: Default> Default for TridentRefCell<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T> From<T> for TridentRefCell<T> {
+    fn from(t: T) -> Self {
+        Self::new(t)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for TridentRefCell<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_struct("TridentRefCell");
+        match self.try_borrow() {
+            Ok(value) => d.field("value", &*value),
+            Err(_) => d.field("value", &format_args!("<borrowed>")),
+        };
+        d.finish()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for TridentRef<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for TridentRefMut<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracks_borrows() {
+        let cell = TridentRefCell::new(vec![1, 2]);
+
+        {
+            let a = cell.borrow();
+            let b = cell.borrow();
+            assert_eq!(a.len() + b.len(), 4);
+            assert_eq!(cell.try_borrow_mut().err(), Some(BorrowMutError));
+            assert_eq!(format!("{cell:?}"), "TridentRefCell { value: [1, 2] }");
+        }
+
+        {
+            let mut m = cell.borrow_mut();
+            m.push(3);
+            assert_eq!(cell.try_borrow().err(), Some(BorrowError));
+            assert!(cell.try_borrow_mut().is_err());
+            assert_eq!(format!("{cell:?}"), "TridentRefCell { value: <borrowed> }");
+        }
+
+        assert_eq!(cell.replace(vec![]), [1, 2, 3]);
+        assert_eq!(cell.clone().into_inner(), Vec::<i32>::new());
+    }
+
+    #[test]
+    fn holds_large_values() {
+        let mut cell = TridentRefCell::new([String::new(), String::new()]);
+        assert!(!cell.is_inline());
+
+        cell.borrow_mut()[1].push_str("large");
+        cell.get_mut()[0].push_str("value");
+        assert_eq!(cell.borrow().concat(), "valuelarge");
+    }
+
+    #[test]
+    fn is_three_words() {
+        let cell = TridentRefCell::new(Cell::new([1u8; 16]));
+        assert!(cell.is_inline());
+        assert_eq!(mem::size_of_val(&cell), 3 * mem::size_of::<usize>());
+
+        cell.borrow().set([2; 16]);
+        assert_eq!(cell.into_inner().get(), [2; 16]);
+    }
+
+    #[test]
+    #[should_panic(expected = "already borrowed")]
+    fn panics_on_conflicting_borrows() {
+        let cell = TridentRefCell::new(0u8);
+
+        let _shared = cell.borrow();
+        let _ = cell.borrow_mut();
+    }
+}
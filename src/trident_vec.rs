@@ -0,0 +1,606 @@
+use std::{
+    cmp, fmt,
+    hash::{Hash, Hasher},
+    iter::FromIterator,
+    marker::PhantomData,
+    mem::{self, ManuallyDrop, MaybeUninit},
+    ops::{Deref, DerefMut},
+    ptr::{self, NonNull},
+    slice,
+};
+
+use crate::erased::Words;
+use crate::limits::{NWORDS, SIZE_LIMIT};
+use crate::Erased;
+
+/**
+ * The byte which tags the storage: for inline elements, `INLINE_TAG` with the length, and
+ * otherwise the last byte of `Spilled::cap_le`, whose top bit is clear.
+ */
+const TAG: usize = SIZE_LIMIT - 1;
+
+/**
+ * Set in the tag of inline elements, alongside their length.
+ */
+const INLINE_TAG: u8 = 0x80;
+
+/**
+ * A growable vector, as `Vec<T>`, which stores its elements within three words while they fit,
+ * and moves them to a `Vec` once they don't.
+ *
+ * As many `T`s as fit before the last byte are stored inline, with that byte recording the
+ * length: 23 `u8`s, 5 `u32`s, or 2 `u64`s on 64-bit. Zero-sized `T`s, and `T`s more aligned than
+ * a word, are always stored in a `Vec`, which doesn't allocate for the former.
+ *
+ * Unlike `SmallSlice`, which has no spare word, the `Vec`'s capacity is kept, packed alongside
+ * the tag, so pushing to a spilled `TridentVec` doesn't reallocate each time.
+ */
+pub struct TridentVec<T> {
+    erased: Erased,
+    _phantom: PhantomData<T>,
+}
+
+/**
+ * A `Vec`'s parts. `cap_le` is stored little-endian whatever the target, so its top byte, which
+ * is clear as a `Vec` of non-zero-sized `T`s holds at most `isize::MAX` bytes, is the tag.
+ */
+#[repr(C)]
+struct Spilled<T> {
+    ptr: NonNull<T>,
+    len: usize,
+    cap_le: usize,
+}
+
+// SAFETY: owns its Ts
+unsafe impl<T: Send> Send for TridentVec<T> {}
+unsafe impl<T: Sync> Sync for TridentVec<T> {}
+
+impl<T> TridentVec<T> {
+    /**
+     * The most `T`s which are stored inline.
+     */
+    pub const INLINE_CAPACITY: usize =
+        if mem::size_of::<T>() == 0 || mem::align_of::<T>() > mem::align_of::<usize>() {
+            0
+        } else {
+            TAG / mem::size_of::<T>()
+        };
+
+    /**
+     * Create an empty `TridentVec`.
+     */
+    pub fn new() -> Self {
+        let mut this = Self {
+            erased: Erased::new_inline([MaybeUninit::<usize>::uninit(); NWORDS]),
+            _phantom: PhantomData,
+        };
+
+        if Self::INLINE_CAPACITY == 0 {
+            this.set_vec(Vec::new());
+        } else {
+            // SAFETY: no Ts are initialised yet
+            unsafe { this.set_inline_len(0) };
+        }
+        this
+    }
+
+    /**
+     * Create an empty `TridentVec`, with space for at least `capacity` `T`s, which allocates if
+     * more than fit inline.
+     */
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut this = Self::new();
+        this.reserve(capacity);
+        this
+    }
+
+    fn words(&self) -> *const u8 {
+        // SAFETY: always created inline, via new_inline, at the size of the whole storage
+        unsafe { self.erased.as_ptr::<Words>() as *const u8 }
+    }
+
+    fn words_mut(&mut self) -> *mut u8 {
+        // SAFETY: as words()
+        unsafe { self.erased.as_mut_ptr::<Words>() as *mut u8 }
+    }
+
+    fn tag(&self) -> u8 {
+        // SAFETY: the tag byte is always initialised
+        unsafe { *self.words().add(TAG) }
+    }
+
+    /**
+     * Record the number of inline `T`s.
+     *
+     * Unsafe because the `TridentVec` must be inline, and the first `len` `T`s initialised.
+     */
+    unsafe fn set_inline_len(&mut self, len: usize) {
+        *self.words_mut().add(TAG) = INLINE_TAG | len as u8;
+    }
+
+    fn inline_len(&self) -> usize {
+        (self.tag() & !INLINE_TAG) as usize
+    }
+
+    fn inline_ptr(&mut self) -> *mut T {
+        self.words_mut() as *mut T
+    }
+
+    /**
+     * Take ownership of `v`'s parts, overwriting any inline `T`s without dropping them.
+     */
+    fn set_vec(&mut self, v: Vec<T>) {
+        let mut v = ManuallyDrop::new(v);
+        let spilled = Spilled {
+            // SAFETY: a Vec's pointer is non-null
+            ptr: unsafe { NonNull::new_unchecked(v.as_mut_ptr()) },
+            len: v.len(),
+            cap_le: v.capacity().to_le(),
+        };
+
+        // SAFETY: the storage is exactly the size of Spilled, and suitably aligned
+        unsafe { ptr::write(self.words_mut() as *mut Spilled<T>, spilled) };
+    }
+
+    /**
+     * Run `f` on our `Vec`, storing its parts again afterwards, even if `f` panics.
+     *
+     * Unsafe because we must be spilled.
+     */
+    unsafe fn with_vec<R>(&mut self, f: impl FnOnce(&mut Vec<T>) -> R) -> R {
+        struct Restore<'a, T> {
+            this: &'a mut TridentVec<T>,
+            v: ManuallyDrop<Vec<T>>,
+        }
+
+        impl<T> Drop for Restore<'_, T> {
+            fn drop(&mut self) {
+                // SAFETY: taken once, here
+                self.this
+                    .set_vec(unsafe { ManuallyDrop::take(&mut self.v) });
+            }
+        }
+
+        let spilled = ptr::read(self.words() as *const Spilled<T>);
+        let v = Vec::from_raw_parts(
+            spilled.ptr.as_ptr(),
+            spilled.len,
+            usize::from_le(spilled.cap_le),
+        );
+        let mut restore = Restore {
+            this: self,
+            v: ManuallyDrop::new(v),
+        };
+
+        f(&mut restore.v)
+    }
+
+    /**
+     * Move the inline `T`s to a `Vec` with space for at least `capacity` `T`s.
+     *
+     * Unsafe because we must be inline.
+     */
+    unsafe fn spill(&mut self, capacity: usize) {
+        let len = self.inline_len();
+        let mut v = Vec::with_capacity(cmp::max(capacity, len));
+
+        ptr::copy_nonoverlapping(self.inline_ptr(), v.as_mut_ptr(), len);
+        v.set_len(len);
+        self.set_vec(v);
+    }
+
+    /**
+     * Whether the elements are stored inline.
+     */
+    pub fn is_inline(&self) -> bool {
+        Self::INLINE_CAPACITY != 0 && self.tag() & INLINE_TAG != 0
+    }
+
+    /**
+     * The number of `T`s which can be held without reallocating.
+     */
+    pub fn capacity(&self) -> usize {
+        if self.is_inline() {
+            Self::INLINE_CAPACITY
+        } else {
+            // SAFETY: spilled
+            let spilled = unsafe { &*(self.words() as *const Spilled<T>) };
+            usize::from_le(spilled.cap_le)
+        }
+    }
+
+    /**
+     * Make space for at least `additional` more `T`s.
+     */
+    pub fn reserve(&mut self, additional: usize) {
+        let required = self
+            .len()
+            .checked_add(additional)
+            .expect("capacity overflow");
+
+        // SAFETY: spilled if not inline
+        unsafe {
+            if !self.is_inline() {
+                self.with_vec(|v| v.reserve(additional));
+            } else if required > Self::INLINE_CAPACITY {
+                self.spill(required);
+            }
+        }
+    }
+
+    /**
+     * Append `t`, moving the elements to a `Vec` if they no longer fit inline.
+     */
+    pub fn push(&mut self, t: T) {
+        if self.is_inline() {
+            let len = self.inline_len();
+
+            if len < Self::INLINE_CAPACITY {
+                // SAFETY: within the inline capacity
+                unsafe {
+                    ptr::write(self.inline_ptr().add(len), t);
+                    self.set_inline_len(len + 1);
+                }
+                return;
+            }
+
+            // SAFETY: inline
+            unsafe { self.spill(Self::INLINE_CAPACITY * 2) };
+        }
+
+        // SAFETY: spilled
+        unsafe { self.with_vec(|v| v.push(t)) }
+    }
+
+    /**
+     * Remove and return the last `T`, if any.
+     */
+    pub fn pop(&mut self) -> Option<T> {
+        if !self.is_inline() {
+            // SAFETY: spilled
+            return unsafe { self.with_vec(Vec::pop) };
+        }
+
+        let len = self.inline_len().checked_sub(1)?;
+
+        // SAFETY: the T at `len` is initialised, and no longer counted
+        unsafe {
+            self.set_inline_len(len);
+            Some(ptr::read(self.inline_ptr().add(len)))
+        }
+    }
+
+    /**
+     * Insert `t` at `index`, shifting later elements up.
+     *
+     * # Panics
+     *
+     * Panics if `index` is greater than the length.
+     */
+    pub fn insert(&mut self, index: usize, t: T) {
+        let len = self.len();
+        assert!(
+            index <= len,
+            "insertion index {} beyond length {}",
+            index,
+            len
+        );
+
+        if self.is_inline() && len == Self::INLINE_CAPACITY {
+            // SAFETY: inline
+            unsafe { self.spill(Self::INLINE_CAPACITY * 2) };
+        }
+
+        if !self.is_inline() {
+            // SAFETY: spilled
+            return unsafe { self.with_vec(|v| v.insert(index, t)) };
+        }
+
+        // SAFETY: within the inline capacity, with the index checked
+        unsafe {
+            let p = self.inline_ptr().add(index);
+            ptr::copy(p, p.add(1), len - index);
+            ptr::write(p, t);
+            self.set_inline_len(len + 1);
+        }
+    }
+
+    /**
+     * Remove and return the `T` at `index`, shifting later elements down.
+     *
+     * # Panics
+     *
+     * Panics if `index` is out of bounds.
+     */
+    pub fn remove(&mut self, index: usize) -> T {
+        let len = self.len();
+        assert!(
+            index < len,
+            "removal index {} out of bounds of {}",
+            index,
+            len
+        );
+
+        if !self.is_inline() {
+            // SAFETY: spilled
+            return unsafe { self.with_vec(|v| v.remove(index)) };
+        }
+
+        // SAFETY: in bounds, and the T is moved out before the later ones are moved over it
+        unsafe {
+            let p = self.inline_ptr().add(index);
+            let t = ptr::read(p);
+            ptr::copy(p.add(1), p, len - index - 1);
+            self.set_inline_len(len - 1);
+            t
+        }
+    }
+
+    /**
+     * Drop the elements from `len` onwards.
+     */
+    pub fn truncate(&mut self, len: usize) {
+        if !self.is_inline() {
+            // SAFETY: spilled
+            return unsafe { self.with_vec(|v| v.truncate(len)) };
+        }
+
+        let old_len = self.inline_len();
+        if len >= old_len {
+            return;
+        }
+
+        // SAFETY: the tail is initialised, and no longer counted before it's dropped, so a
+        // panicking drop doesn't drop it again
+        unsafe {
+            self.set_inline_len(len);
+            let tail = ptr::slice_from_raw_parts_mut(self.inline_ptr().add(len), old_len - len);
+            ptr::drop_in_place(tail);
+        }
+    }
+
+    /**
+     * Drop all elements, keeping any allocation.
+     */
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    /**
+     * Get the elements as a slice.
+     */
+    pub fn as_slice(&self) -> &[T] {
+        // SAFETY: inline, the words start with the tagged number of Ts. Otherwise, they're a
+        // Spilled
+        unsafe {
+            if self.is_inline() {
+                slice::from_raw_parts(self.words() as *const T, self.inline_len())
+            } else {
+                let spilled = &*(self.words() as *const Spilled<T>);
+                slice::from_raw_parts(spilled.ptr.as_ptr(), spilled.len)
+            }
+        }
+    }
+
+    /**
+     * Get the elements as a mutable slice.
+     */
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        // SAFETY: as as_slice()
+        unsafe {
+            if self.is_inline() {
+                let len = self.inline_len();
+                slice::from_raw_parts_mut(self.inline_ptr(), len)
+            } else {
+                let spilled = &*(self.words() as *const Spilled<T>);
+                slice::from_raw_parts_mut(spilled.ptr.as_ptr(), spilled.len)
+            }
+        }
+    }
+
+    /**
+     * Convert to a `Vec<T>`, which allocates if the elements are inline.
+     */
+    pub fn into_vec(self) -> Vec<T> {
+        let mut this = ManuallyDrop::new(self);
+
+        // SAFETY: `this` is never dropped, so ownership of the Ts moves to the Vec
+        unsafe {
+            if this.is_inline() {
+                this.spill(0);
+            }
+            this.with_vec(mem::take)
+        }
+    }
+}
+
+impl<T> Drop for TridentVec<T> {
+    fn drop(&mut self) {
+        if self.is_inline() {
+            self.clear();
+        } else {
+            // SAFETY: spilled, and not used again
+            unsafe { self.with_vec(|v| drop(mem::take(v))) }
+        }
+    }
+}
+
+impl<T> Default for TridentVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Deref for TridentVec<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T> DerefMut for TridentVec<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T> Extend<T> for TridentVec<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for t in iter {
+            self.push(t);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for TridentVec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut this = Self::new();
+        this.extend(iter);
+        this
+    }
+}
+
+/**
+ * Keeps the `Vec`'s allocation, if it has one.
+ */
+impl<T> From<Vec<T>> for TridentVec<T> {
+    fn from(v: Vec<T>) -> Self {
+        let mut this = Self::new();
+        if v.capacity() != 0 || Self::INLINE_CAPACITY == 0 {
+            this.set_vec(v);
+        }
+        this
+    }
+}
+
+impl<T> From<TridentVec<T>> for Vec<T> {
+    fn from(v: TridentVec<T>) -> Self {
+        v.into_vec()
+    }
+}
+
+impl<T: Clone> Clone for TridentVec<T> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T: PartialEq> PartialEq for TridentVec<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<T: Eq> Eq for TridentVec<T> {}
+
+impl<T: Hash> Hash for TridentVec<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for TridentVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_slice(), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+
+    #[test]
+    fn spills_when_full() {
+        let mut v = TridentVec::<u32>::new();
+
+        for i in 0..TridentVec::<u32>::INLINE_CAPACITY as u32 {
+            v.push(i);
+        }
+        assert!(v.is_inline());
+
+        v.push(100);
+        assert!(!v.is_inline());
+        assert!(v.capacity() > v.len());
+
+        let ptr = v.as_ptr();
+        v.push(101);
+        assert_eq!(v.as_ptr(), ptr);
+        assert_eq!(v.pop(), Some(101));
+        assert_eq!(v.last(), Some(&100));
+        assert_eq!(
+            std::mem::size_of::<TridentVec<u32>>(),
+            std::mem::size_of::<Erased>()
+        );
+    }
+
+    #[test]
+    fn edits_inline() {
+        let mut v: TridentVec<u8> = b"hello".iter().copied().collect();
+
+        v.insert(0, b'>');
+        assert_eq!(v.remove(1), b'h');
+        v[0] = b'J';
+        v.truncate(3);
+        assert_eq!(&*v, b"Jel");
+        assert_eq!(v.pop(), Some(b'l'));
+        v.clear();
+        assert_eq!(v.pop(), None);
+        assert!(v.is_inline());
+    }
+
+    #[test]
+    fn edits_spilled() {
+        let mut v: TridentVec<u64> = (0..3).collect();
+        assert!(!v.is_inline());
+
+        v.insert(1, 10);
+        assert_eq!(v.remove(0), 0);
+        v.truncate(2);
+        assert_eq!(Vec::from(v.clone()), [10, 1]);
+        assert_eq!(v, TridentVec::from(vec![10, 1]));
+    }
+
+    #[test]
+    fn converts() {
+        let mut v = Vec::with_capacity(10);
+        v.push(String::from("a"));
+        let ptr = v.as_ptr();
+
+        let tv = TridentVec::from(v);
+        assert!(!tv.is_inline());
+        assert_eq!(tv.capacity(), 10);
+        let v = tv.into_vec();
+        assert_eq!(v.as_ptr(), ptr);
+
+        let tv = TridentVec::<u8>::from(Vec::new());
+        assert!(tv.is_inline());
+        assert_eq!(tv.capacity(), TridentVec::<u8>::INLINE_CAPACITY);
+
+        let mut inline = TridentVec::with_capacity(1);
+        inline.push(Box::<str>::from("b"));
+        assert!(inline.is_inline());
+        assert_eq!(inline.into_vec(), [Box::from("b")]);
+
+        let units: TridentVec<()> = std::iter::repeat_n((), 50).collect();
+        assert_eq!(units.len(), 50);
+        assert!(!units.is_inline());
+    }
+
+    #[test]
+    fn drops() {
+        let rc = Rc::new(());
+
+        let mut inline = TridentVec::new();
+        inline.push(rc.clone());
+        let spilled: TridentVec<_> = std::iter::repeat_n(rc.clone(), 8).collect();
+        assert_eq!(Rc::strong_count(&rc), 10);
+
+        inline.truncate(0);
+        assert_eq!(Rc::strong_count(&rc), 9);
+        drop(spilled);
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+}